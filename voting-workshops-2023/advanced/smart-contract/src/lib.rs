@@ -2,52 +2,692 @@
 //!
 //! # Description
 //! A contract that allows for conducting an election with several voting
-//! options. An `end_time` is set when the election is initialized. Only
+//! options. An `end_time`, and optionally a `start_time`, is set when the
+//! election is initialized. Only
 //! accounts are eligible to vote. Each account can change its
 //! selected voting option as often as it desires until the `end_time` is
-//! reached. No voting will be possible after the `end_time`.
+//! reached, unless the election is initialized with `allow_vote_change` set
+//! to `false`, in which case each account can only vote once. An optional
+//! `change_cooldown` limits how quickly an account can change its vote, which
+//! dampens flapping of the tally, and an optional `max_changes` limits how
+//! often it can change its vote. No voting will be possible after the
+//! `end_time`.
 //!
 //! # Operations
 //! The contract allows for
 //!  - `initializing` the election;
+//!  - `register` as eligible for a voting option with signatures from the
+//!    verifiers;
 //!  - `vote` for one of the voting options;
-//!  - `view` general information about the election and the tally.
+//!  - `registerCredential` to register for all voting options with a CIS-4
+//!    credential instead;
+//!  - `proveAllowListed` to prove that an account may vote in a closed
+//!    election;
+//!  - `voteFor` to submit a vote signed by another account on its behalf;
+//!  - `permit` to submit a registration or a vote signed by another account
+//!    following the CIS-3 standard, and `supportsPermit` to query which
+//!    entrypoints it supports;
+//!  - `voteEncrypted` to cast an encrypted ballot;
+//!  - `voteWithDeposit` to vote with a deposit of CCD in a quadratic election;
+//!  - `withdraw` to get a deposit back after the election;
+//!  - `delegate` the voting power of an account to another account, and
+//!    `undelegate` to take it back;
+//!  - `retractVote` to withdraw the ballot of an account entirely;
+//!  - `voteWriteIn` to vote for a country that is not a voting option;
+//!  - `view` general information about the election and the tally;
+//!  - `viewMetadata` to get the static information about the election
+//!    without the tally, and `viewTally` to get only the tally;
+//!  - `viewAdmin` to get the account that can call the admin entrypoints;
+//!  - `getVote` to look up the voting option of a single account, and
+//!    `getVotes` to look up the voting options of many accounts at once;
+//!  - `getTallyForOption` to get the number of votes of a single voting
+//!    option;
+//!  - `hasVoted` to check whether an account has voted;
+//!  - `verifyReceipt` to check the receipt returned for a vote;
+//!  - `viewOptions` to get the voting options with their indices;
+//!  - `viewOptionInfo` to get the metadata of the voting options, e.g. their
+//!    display names;
+//!  - `viewBallots` to page through the ballots of all voters;
+//!  - `viewTallyPartial` to recompute the tally from a page of the ballots;
+//!  - `viewVotersForOption` to page through the voters of a single voting
+//!    option;
+//!  - `viewEncryptedBallots` to page through the encrypted ballots;
+//!  - `viewTallyAuthority` to get the key that ballots are encrypted with;
+//!  - `getVerifierKey` to get the keys of the verifiers the election trusts;
+//!  - `viewTallyProof` to get the artifact posted with the decrypted tally;
+//!  - `voterCount` to get the number of accounts that have voted;
+//!  - `viewTurnout` to get the participation relative to the expected number
+//!    of voters;
+//!  - `viewVoteHistory` to get the votes of an account in the order they were
+//!    cast, if the election records them;
+//!  - `nonce` to get the nonce an account must have signed for its next
+//!    registration;
+//!  - `finalize` the election after the `end_time`;
+//!  - `getResult` to get the winner of a finalized election;
+//!  - `postTally` to let the tally authority post the decrypted tally;
+//!  - `startRunoff` to let the admin start a second round between the two
+//!    voting options with the most votes;
+//!  - `viewFirstRound` to get the result of the first round of a runoff;
+//!  - `viewHistory` to get the result of a past round of a recurring
+//!    election;
+//!  - `proposeExtension` of the `end_time`, which any voter can do;
+//!  - `voteOnExtension` to approve the proposed extension;
+//!  - `viewExtensionProposal` to see the pending extension proposal;
+//!  - `viewBlocs` to see the member countries of the bloc options;
+//!  - `transferAdmin` to hand over the admin role to another account;
+//!  - `extendEndTime` to let the admin extend the election;
+//!  - `pause` and `unpause` to let the admin suspend voting;
+//!  - `cancel` to let the admin void the election;
+//!  - `addOption` to let the admin add a voting option before the first vote;
+//!  - `removeOption` to let the admin remove a voting option without votes;
+//!  - `blacklist` and `unblacklist` to let the admin exclude an account from
+//!    the election;
+//!  - `withdrawFunds` to let the admin recover CCD that are not deposits;
+//!  - `cleanup` to let the admin delete the ballots after the retention
+//!    period;
+//!  - `certifyResult` to let the admin post the result certified by the
+//!    election authority;
+//!  - `updateDescription` to let the admin correct the description;
+//!  - `rotateVerifierKey` to let the admin replace a verifier key;
+//!  - `approveWriteIn` to let the admin turn a write-in into a voting option;
+//!  - `upgrade` to let the admin replace the code of the contract;
+//!  - `supports` to query which standards the contract implements.
+//!
+//! # Events
+//! Every accepted vote is logged as a `VotingEvent::Vote`, so indexers and
+//! frontends can follow the election live without polling `view`.
+//!
+//! `init` logs a `VotingEvent::ElectionCreated`, so indexers can catalogue
+//! new elections without invoking every new instance of the module. Events
+//! are limited to 512 bytes, so it contains the hashes of the description and
+//! of the verifier keys instead of the values themselves.
+//!
+//! # Receipts
+//! `vote`, `voteWithDeposit`, and `voteFor` return a receipt, which is the
+//! SHA2-256 hash of the serialized address of the voter, the voting option,
+//! and the slot time of the vote as 8 little-endian bytes of milliseconds.
+//! The contract stores the receipt of the latest vote of each voter, so a
+//! voter can later prove to third parties with `verifyReceipt` that its
+//! ballot was recorded. A retracted or deleted ballot invalidates its receipt.
+//!
+//! # Contract voters
+//! Only accounts can vote by default. An election can allow specific
+//! contracts, e.g. a DAO treasury or a multisig wallet, to `vote` by listing
+//! them as `contract_voters` at initialization. The admin vouches for these
+//! contracts, so they vote for any option without registering, each with a
+//! weight of 1, and their votes are logged as `VotingEvent::ContractVote`.
+//! Therefore, contract voters cannot be combined with weighted or encrypted
+//! votes.
+//!
+//! # Voter limit
+//! An election can optionally be limited to `max_voters` voters, which bounds
+//! the number of ballots the contract stores. Once the limit is reached, new
+//! accounts are rejected, while the accounts that have already voted can
+//! still change their vote. A retracted or removed ballot frees up its place.
+//!
+//! # Vote history
+//! An election can optionally record every vote of an account with
+//! `record_vote_history`, so the changes of mind of the voters can be analyzed
+//! from the chain data alone. Only the votes for voting options are recorded,
+//! not retractions, and the history is deleted together with the ballots.
+//!
+//! # Logs only
+//! For mass-participation events, an election can be set to `logs_only`, in
+//! which case the contract does not store the ballots or the tally. Each vote
+//! is only logged as a `VoteLogged` event, with the weight of the voter, and
+//! off-chain indexers tally the latest vote of each voter. The state then does
+//! not grow with the number of voters, while `view` reports a tally of 0 votes,
+//! the receipts cannot be checked with `verifyReceipt`, and the election cannot
+//! be finalized. Features that rely on the stored ballots, such as vote change
+//! restrictions, vote limits, a quorum, quadratic voting, encrypted ballots,
+//! recurring elections, the vote history, and badges, cannot be combined with
+//! it.
+//!
+//! # Badges
+//! An election can optionally be set up with a `badge_contract`, i.e. a CIS-2
+//! contract that mints a non-transferable "I voted" badge. When an account
+//! votes for the first time, the contract invokes `mint` on the badge
+//! contract with the account as the owner, and the vote is rejected if
+//! minting fails. Each account receives at most one badge, even if it retracts
+//! and casts its vote again. The badge contract is responsible for making the
+//! badges non-transferable, and for only letting the election mint them.
+//!
+//! # Minimum balance
+//! An election can optionally require voters to hold a minimum balance on
+//! their account, which is checked every time they vote. This allows for
+//! stake-gated governance.
+//!
+//! # Blocs
+//! A voting option can represent a bloc of countries, e.g. `NORDICS`, whose
+//! member countries are provided at initialization. To vote for a bloc, the
+//! voter needs a signature from the verifier on the bloc identifier, which
+//! the verifier only issues if the voter does *not* live in any of the member
+//! countries. Bloc identifiers are longer than two bytes, so they cannot be
+//! confused with country codes.
+//!
+//! # Extending the election
+//! Voters can decide to extend the election. Any account that has voted can
+//! propose a new `end_time`. The proposal is applied automatically as soon as
+//! the percentage of voters configured at initialization, i.e. the
+//! `extension_approval_percentage`, has approved it. Only one proposal can be
+//! pending at a time, and it can only be approved before the `end_time`.
+//!
+//! # Finalization
+//! Once the `end_time` has passed, anyone can `finalize` the election. This
+//! freezes the tally and determines the winner, which is the voting option
+//! with the most votes. On a tie, the winner is determined by the
+//! `tie_breaking` policy chosen at initialization, and all tied options are
+//! reported by `getResult`. The policy can let the option listed first win,
+//! let the option that reached the winning number of votes first win, or
+//! declare no winner. After finalization, `view` serves the
+//! frozen tally. An election can require a `quorum` of voters, and the result
+//! reports whether it was reached, so elections with too low participation
+//! can be declared invalid on-chain.
+//!
+//! If no voting option has received more than half of the votes, the admin
+//! can `startRunoff` after finalization. This opens a second voting window
+//! that is restricted to the two options with the most votes. The other
+//! options are removed, and the ballots of the first round are discarded. The
+//! registrations are reused, so voters do not need new signatures from the
+//! verifiers, but only voters registered for one of the two options can vote
+//! in the runoff. The runoff is finalized like the first round, and there is
+//! at most one runoff.
+//!
+//! An election initialized with a `recurrence` never stays finalized. When a
+//! round is finalized, its result is archived, which `viewHistory` serves, and
+//! the next round starts right away with the same voting options and a new
+//! `end_time` one `recurrence` later. As in a runoff, the registrations are
+//! kept and the ballots are discarded. Since the result of the latest round is
+//! archived immediately, `getResult` is not available for recurring
+//! elections, and neither is a runoff.
+//!
+//! An election can be initialized with a `retention_period`. Once it has
+//! passed after the `end_time` of a finalized or cancelled election, the admin
+//! can `cleanup` the ballots to shrink the state of the instance. The frozen
+//! tally and the result are kept, but the votes of individual accounts can no
+//! longer be looked up. The registrations and deposits are kept as well.
+//!
+//! # Certification
+//! An election can optionally be set up with the `certification_key` of an
+//! election authority. Once the election is finalized, the admin can post the
+//! official result with `certifyResult`, i.e. a tally together with the
+//! signature of the authority on the `CertificationMessage`. The contract
+//! keeps the certified result next to the tally it computed, and `view`
+//! returns both, while the `ResultCertified` event tells whether they match.
+//! The result of a recurring election moves to the history as soon as it is
+//! finalized, so it cannot be certified.
+//!
+//! # Admin
+//! The account that initializes the election becomes its admin. The admin
+//! can hand over the role to another account with `transferAdmin`, and
+//! extend the `end_time` with `extendEndTime` as long as the election has not
+//! been finalized. The admin can also `pause` voting, e.g. when a compromise of
+//! the verifier key is suspected, and `unpause` it again. Finally, a
+//! misconfigured election can be abandoned with `cancel`, which voids the
+//! election for good. Until the first vote is cast, the admin can correct the
+//! voting options with `addOption`. Voting options without votes can be
+//! removed with `removeOption`. Removed options keep their index, so the
+//! indices of the remaining options and the recorded ballots stay valid. The
+//! description can be corrected at any time with `updateDescription`. If a
+//! verifier key is compromised or expires, the admin can replace it with
+//! `rotateVerifierKey`, after which signatures from the old key are rejected.
+//!
+//! If a verifier signature turns out to have been issued on a fraudulent
+//! proof, the admin can `blacklist` the account until the election is
+//! finalized. Its ballot is removed from the tally, its delegation is
+//! withdrawn, and it can neither register, vote, nor delegate until the admin
+//! calls `unblacklist`. The voting power delegated to a blacklisted account
+//! is not counted until the delegators undelegate and vote themselves.
+//!
+//! Bugfixes to the voting logic can be shipped to a live election with
+//! `upgrade`, which replaces the module of the instance while keeping its
+//! state, so the recorded ballots are not lost. If the new module stores its
+//! state differently, the upgrade can call a migration entrypoint of the new
+//! module in the same transaction, which converts the state before anyone
+//! else can interact with the instance.
+//!
+//! # Registration
+//! Before voting, an account must `register` for a voting option with
+//! signatures from the verifiers that it does *not* live in the country.
+//! Registration closes at the `registration_end_time`, which can be earlier
+//! than the `end_time`, so eligibility proofs must be submitted early while
+//! votes can still be changed until the election closes. The
+//! signatures are checked and consumed once, after which the account can
+//! `vote` for the option, and change its vote between the options it has
+//! registered for, by providing just the country code. This keeps the vote
+//! parameter small and cheap, and voters do not have to contact the verifiers
+//! again to change their vote.
+//!
+//! The election is initialized with the `statement_hash`, i.e. the hash of the
+//! statement that the verifiers must check, and the signatures must include
+//! it. A misconfigured or malicious verifier that checks a weaker statement
+//! therefore cannot register voters.
+//!
+//! Alternatively, an election can be initialized with a `credential_registry`,
+//! i.e. a CIS-4 contract, to check the eligibility on-chain without trusting
+//! the verifiers. Accounts then `registerCredential` instead, which binds a
+//! credential from the registry to the account with a signature of its
+//! holder, and makes the account eligible for all voting options. Every vote
+//! checks that the credential is still active, so revoking it in the registry
+//! also revokes the eligibility. The registry only stores the status of the
+//! credentials, so the issuer is trusted to only issue them to eligible
+//! voters.
+//!
+//! A voter without CCD to pay for transactions can sign its registration and
+//! vote with the keys of its account instead, and let anyone submit it with
+//! `voteFor`. The signature includes the nonce of the voter, so it can only be
+//! used once.
+//!
+//! Wallets and sponsors that follow the CIS-3 standard can use `permit`
+//! instead, which submits a `register` or a `vote` signed by the voter. The
+//! permit shares the nonce with the verifiers: a permitted vote increments it,
+//! and a permitted registration consumes it together with the signatures from
+//! the verifiers, which must be issued for the same nonce.
+//!
+//! # Closed elections
+//! An election can be restricted to an allow-list of accounts by providing the
+//! `allow_list_root` of a Merkle tree over the accounts at initialization, so
+//! the init parameter stays small even for thousands of voters. Before
+//! registering, an account proves its membership once with
+//! `proveAllowListed`, which anyone can submit. Accounts that have not proven
+//! their membership can neither register nor delegate.
+//!
+//! A leaf of the tree is the SHA2-256 hash of the byte `0x00` followed by the
+//! 32 bytes of the account address. An inner node is the SHA2-256 hash of the
+//! byte `0x01` followed by its two children, the smaller one first.
+//!
+//! # Weighted voting
+//! An election can be initialized with `weights` for the accounts, e.g. their
+//! shares in a DAO. The tally then sums the weights of the voters instead of
+//! counting one vote per ballot, and accounts without a weight cannot vote.
+//! The weights are fixed at initialization.
+//!
+//! Alternatively, an election can be initialized with `token_weights`, which
+//! configures a CIS-2 token contract. `vote` then queries `balanceOf` on that
+//! contract and records the token balance of the voter as its weight. The
+//! balance is snapshotted at the time of the vote, so transferring the tokens
+//! afterwards does not change the tally until the account votes again. Votes
+//! for approved write-ins are weighted by the balance at the time of the
+//! approval.
+//!
+//! # Quadratic voting
+//! An election initialized with `quadratic_voting` weighs the votes by the
+//! square root of a deposit of CCD instead. Voters vote with the payable
+//! `voteWithDeposit`, and the weight of an account is the integer square root
+//! of the total amount of microCCD it has deposited, so buying more influence
+//! gets increasingly expensive. Depositing more when changing the vote adds to
+//! the deposit of the account. The contract holds the deposits and records the
+//! amount locked by each voter, until the voter calls `withdraw` after the
+//! `end_time`, or after the election has been cancelled.
+//!
+//! Any CCD on the contract beyond the deposits, which should not exist but
+//! would otherwise be stranded, can be transferred to the `funds_recipient`
+//! by the admin with `withdrawFunds`. The deposits cannot be withdrawn that
+//! way.
+//!
+//! # Time-weighted voting
+//! An election can be `time_weighted` to reward early voters. The weight of a
+//! vote is multiplied by a factor that decreases linearly from
+//! `TIME_WEIGHT_SCALE` at the start of the election, or its initialization if
+//! it has no `start_time`, to 1 at the `end_time`. The factor is applied on
+//! top of any other weighting, and a changed vote gets the factor of the time
+//! of the change. The tally sums up the weighted votes, while `view` also
+//! reports the number of ballots of each voting option. The ballots of a
+//! time-weighted election cannot be encrypted, and neither contracts nor
+//! delegations can take part in it.
+//!
+//! # Delegation
+//! Instead of voting, an account can `delegate` its voting power to another
+//! account, which can in turn delegate it further. The account at the end of
+//! such a chain of delegations is the representative, and its vote counts
+//! with its own weight plus the weight delegated to it. The weight of a
+//! delegating account is snapshotted when it delegates. An account cannot
+//! vote while it delegates, and it cannot delegate after voting, so it has to
+//! retract its vote first. Delegations that would form a cycle, or a chain of
+//! more than `MAX_DELEGATION_DEPTH` delegations, are rejected. `undelegate`
+//! removes the weight from the representative again.
+//!
+//! # Encrypted ballots
+//! An election can be initialized with a `tally_authority`, in which case the
+//! ballots are secret. Voters register as usual, but cast their vote with
+//! `voteEncrypted`, which stores one ElGamal ciphertext per voting option,
+//! encrypted under the key of the tally authority. Each ciphertext encrypts 1
+//! for the voted-for option and 0 for the others. After the `end_time`, the
+//! tally authority decrypts the sum of the ballots off-chain and posts the
+//! tally with `postTally`, together with an artifact, e.g. proofs of correct
+//! decryption, that lets auditors check it against the ballots. This finalizes
+//! the election.
+//!
+//! The contract cannot check the ciphertexts, so the tally authority is
+//! trusted to only count well-formed ballots for options the voters have
+//! registered for. For the same reason, encrypted ballots cannot be weighted,
+//! delegated, retracted, or written in, `max_votes_per_option` is not
+//! enforced, and a tie that would be broken by the earliest option to reach
+//! the winning count is won by the option listed first.
+//!
+//! # Write-ins
+//! Voters can also vote for a country that is not a voting option with
+//! `voteWriteIn`, which requires signatures from the verifiers just like
+//! `register`. The write-in is pending until the admin approves it with
+//! `approveWriteIn`, which adds the country as a voting option and counts the
+//! pending votes for it retroactively. Voting for a regular option withdraws
+//! a pending write-in of the account.
+//!
+//! # Multiple verifiers
+//! To avoid trusting a single verifier, an election can be initialized with
+//! several verifier keys and a threshold. A registration is only accepted if
+//! at least `verifier_threshold` distinct verifiers have signed it. Each verifier can
+//! use either Ed25519 or ECDSA on the secp256k1 curve, which is determined by
+//! the type of its public key.
+//!
+//! An election initialized without verifier keys and a threshold of 0 is not
+//! verified, i.e. accounts can `vote` for any voting option without
+//! registering, like in the basic version of the contract. This lets
+//! organizers choose how strict each election is.
 //!
 //! # Tests
 //! The tests exist in the `./tests/tests.rs` file.
 //!
 //! # Changes in the advanced version
-//! The advanced version of the contract checks a signature from the verifier when voting.
+//! The advanced version of the contract checks a signature from the verifier when registering for a voting option.
 //! This is a large improvement over the basic version, where the eligibilty check for the country
 //! only occurs in the frontend. This allows users to circumvent the country check when communicating
 //! with the contract directly, for example with concordium-client.
 //! The primary changes are:
-//!  - Include the public key of the verifier on initialization,
-//!  - Include the signature from the verifier when voting, and ensure that it is correct.
+//!  - Include the public keys of the verifiers on initialization,
+//!  - Include the signatures from the verifiers when registering, and ensure that enough of them are correct,
+//!  - Only accept votes for the voting options that the account has registered for.
+//!
+//! The types shared with the verifier, i.e. the registration parameter, the errors,
+//! and the message signed by the verifier, live in the `voting-types` crate.
 //!
-//! Note: Vec<VotingOption> (among other variables) is an input parameter to the
+//! Note: Vec<OptionInfo> (among other variables) is an input parameter to the
 //! `init` function. Since there is a limit to the parameter size (65535 Bytes),
-//! the size of the Vec<VotingOption> is limited.
+//! the size of the Vec<OptionInfo> is limited.
 //! https://developer.concordium.software/en/mainnet/smart-contracts/general/contract-instances.html#limits
+//! To fail with a specific error well before that limit, an election has at
+//! most `MAX_OPTIONS` voting options, including removed ones, and a
+//! description of at most `MAX_DESCRIPTION_LENGTH` bytes.
+
+use concordium_std::{
+    collections::{BTreeMap, BTreeSet},
+    *,
+};
+
+pub use voting_types::*;
+
+/// The maximum number of delegations in a chain, which bounds the energy of
+/// walking the chain when delegating and undelegating.
+pub const MAX_DELEGATION_DEPTH: u32 = 8;
+
+/// The maximum number of voting options of an election, including the options
+/// that have been removed.
+pub const MAX_OPTIONS: usize = 64;
+
+/// The maximum length of the description of an election, in bytes.
+pub const MAX_DESCRIPTION_LENGTH: usize = 1024;
+
+/// The factor that the weight of a vote cast at the start of a time-weighted
+/// election is multiplied by. It decreases linearly to 1 at the `end_time`.
+pub const TIME_WEIGHT_SCALE: u64 = 1000;
+
+/// A voting option together with the metadata that frontends need to display
+/// it, so they do not need a lookup table from country codes to display names.
+#[derive(Serialize, SchemaType, Clone, PartialEq, Eq, Debug)]
+pub struct OptionInfo {
+    /// The country code, or the identifier of a bloc, which is used when
+    /// registering and voting.
+    pub code: VotingOption,
+    /// The display name of the voting option.
+    pub label: String,
+    /// A URL with more information about the voting option, e.g. its flag.
+    pub url: String,
+    /// The SHA2-256 hash of the image at the `url`, if any.
+    pub image_hash: Option<HashSha2256>,
+}
+
+/// The policy that determines the winner if several voting options share the
+/// highest number of votes.
+#[derive(Serialize, SchemaType, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TieBreakingPolicy {
+    /// The tied option that was listed first wins.
+    FirstListed,
+    /// The tied option that reached the winning number of votes first wins.
+    EarliestToReachCount,
+    /// There is no winner.
+    NoWinner,
+}
+
+/// The identifier of a CIS-2 token, which is between 0 and 255 bytes long.
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct TokenId(#[concordium(size_length = 1)] pub Vec<u8>);
+
+impl schema::SchemaType for TokenId {
+    fn get_type() -> schema::Type {
+        schema::Type::ByteList(schema::SizeLength::U8)
+    }
+}
+
+/// The CIS-2 token whose balances are the voting weights of the accounts.
+#[derive(Serialize, SchemaType, Clone)]
+pub struct TokenWeights {
+    /// The CIS-2 contract that holds the token balances.
+    pub contract: ContractAddress,
+    /// The token whose balance is the voting weight.
+    pub token_id: TokenId,
+}
+
+/// A query for the balance of a token, as defined by the CIS-2 standard.
+#[derive(Serial)]
+struct BalanceOfQuery {
+    token_id: TokenId,
+    address: Address,
+}
+
+/// The parameter of the CIS-2 function `balanceOf`.
+#[derive(Serial)]
+struct BalanceOfQueryParams {
+    #[concordium(size_length = 2)]
+    queries: Vec<BalanceOfQuery>,
+}
+
+/// The parameter of the `mint` function of the CIS-2 badge contract, which
+/// mints an "I voted" badge to the `owner`. Minting is not part of the CIS-2
+/// standard, so the badge contract must accept this parameter.
+#[derive(Serial)]
+struct MintBadgeParams {
+    owner: Address,
+}
+
+/// A CIS-2 token amount. The standard encodes amounts in LEB128 with up to 256
+/// bits, but this contract only supports amounts that fit in an `u64`.
+struct TokenAmount(u64);
+
+impl Deserial for TokenAmount {
+    fn deserial<R: Read>(source: &mut R) -> ParseResult<Self> {
+        let mut amount: u64 = 0;
+        for i in 0..10 {
+            let byte = source.read_u8()?;
+            let value = u64::from(byte & 0x7f);
+            // Reject amounts that do not fit in an `u64`.
+            let shifted = value << (7 * i);
+            if shifted >> (7 * i) != value {
+                return Err(ParseError::default());
+            }
+            amount |= shifted;
+            if byte & 0x80 == 0 {
+                return Ok(TokenAmount(amount));
+            }
+        }
+        Err(ParseError::default())
+    }
+}
 
-use concordium_std::{collections::BTreeMap, *};
+/// The return value of the CIS-2 function `balanceOf`, with one amount per
+/// query.
+#[derive(Deserial)]
+struct BalanceOfQueryResponse(#[concordium(size_length = 2)] Vec<TokenAmount>);
 
-/// A vote including a signature from the verifier, which verifies that the voter does *not* live in the country voted for.
+/// The standards that the contract implements, as advertised by `supports`.
+const SUPPORTED_STANDARDS: [&str; 2] = ["CIS-0", "CIS-3"];
+
+/// The entrypoints that can be invoked with `permit`, as advertised by
+/// `supportsPermit`.
+const PERMIT_ENTRYPOINTS: [&str; 2] = ["register", "vote"];
+
+/// The identifier of a standard, e.g. `CIS-0`, which is between 0 and 255
+/// ASCII characters long, as defined by the CIS-0 standard.
+#[derive(Serialize, SchemaType, Clone, PartialEq, Eq, Debug)]
+pub struct StandardIdentifier(#[concordium(size_length = 1)] pub String);
+
+/// The parameter type for the contract function `supports`.
+#[derive(Serialize, SchemaType)]
+pub struct SupportsQueryParams {
+    /// The standards to query support for.
+    #[concordium(size_length = 2)]
+    pub queries: Vec<StandardIdentifier>,
+}
+
+/// Whether the contract implements a standard, as defined by the CIS-0
+/// standard.
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub enum SupportResult {
+    /// The standard is not implemented.
+    NoSupport,
+    /// The standard is implemented by this contract.
+    Support,
+    /// The standard is implemented by the listed contracts on behalf of this
+    /// contract.
+    SupportBy(#[concordium(size_length = 1)] Vec<ContractAddress>),
+}
+
+/// The `return_value` type of the contract function `supports`, with one
+/// result per query.
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct SupportsQueryResponse {
+    /// The results in the order of the queries.
+    #[concordium(size_length = 2)]
+    pub results: Vec<SupportResult>,
+}
+
+/// The parameter type for the contract function `upgrade`.
+#[derive(Serialize, SchemaType)]
+pub struct UpgradeParameter {
+    /// The module to replace the code of the contract with.
+    pub module: ModuleReference,
+    /// An entrypoint of the new module to call right after the upgrade, with
+    /// its parameter, e.g. to migrate the state.
+    pub migrate: Option<(OwnedEntrypointName, OwnedParameter)>,
+}
+
+/// A delegation of the voting power of an account.
+#[derive(Serialize, Clone, Copy)]
+struct Delegation {
+    /// The account that the voting power is delegated to.
+    delegate: AccountAddress,
+    /// The own voting weight of the delegating account when it delegated.
+    weight: u64,
+}
+
+/// A point on the elliptic curve that the ballots are encrypted over, e.g.
+/// Ristretto255, in its 32-byte compressed encoding.
+pub type CompressedPoint = [u8; 32];
+
+/// An ElGamal ciphertext `(c1, c2) = (r * G, m * G + r * K)` of an option
+/// indicator `m` under the public key `K` of the tally authority. The
+/// indicator is 1 for the voted-for option and 0 for the others.
+#[derive(Serialize, SchemaType, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ElGamalCiphertext {
+    /// The ephemeral part `r * G` of the ciphertext.
+    pub c1: CompressedPoint,
+    /// The encrypted indicator `m * G + r * K`.
+    pub c2: CompressedPoint,
+}
+
+/// The authority that decrypts and tallies the ballots of an election with
+/// encrypted ballots.
+#[derive(Serialize, SchemaType, Clone, PartialEq, Eq, Debug)]
+pub struct TallyAuthority {
+    /// The account that posts the decrypted tally.
+    pub account: AccountAddress,
+    /// The ElGamal public key that the voters encrypt their ballots with.
+    pub encryption_key: CompressedPoint,
+}
+
+/// The status of a credential, as defined by the CIS-4 standard.
+#[derive(Deserial, PartialEq, Eq)]
+enum CredentialStatus {
+    Active,
+    Revoked,
+    Expired,
+    NotActivated,
+}
+
+/// The parameter type for the contract function `registerCredential`.
+#[derive(Serialize, SchemaType)]
+pub struct CredentialRegistration {
+    /// The public key that identifies the holder of the credential in the
+    /// CIS-4 registry.
+    pub holder_id: PublicKeyEd25519,
+    /// The signature of the holder on the `CredentialMessage`.
+    pub signature: SignatureEd25519,
+}
+
+/// The message that the holder of a credential signs to bind the credential
+/// to an account in an election.
+#[derive(Serialize, SchemaType)]
+pub struct CredentialMessage {
+    /// The address of the voting contract instance.
+    pub contract_address: ContractAddress,
+    /// The account that votes with the credential.
+    pub account: AccountAddress,
+}
+
+/// A node of the Merkle tree over the accounts on the allow-list, i.e. a
+/// SHA2-256 hash.
+pub type MerkleNode = [u8; 32];
+
+/// The parameter type for the contract function `proveAllowListed`.
+#[derive(Serialize, SchemaType)]
+pub struct AllowListProof {
+    /// The account whose membership of the allow-list is proven.
+    pub account: AccountAddress,
+    /// The siblings of the nodes on the path from the leaf of the account to
+    /// the root, starting with the sibling of the leaf.
+    pub proof: Vec<MerkleNode>,
+}
+
+/// The parameter type for the contract function `postTally`.
 #[derive(Serialize, SchemaType)]
-pub struct VoteParameter {
-    /// The country voted on.
-    pub country_code: String,
-    /// The signature from the verifier, with the message `(account_address, country_code)`.
+pub struct PostTallyParameter {
+    /// The number of votes of each voting option, in the order of their vote
+    /// indices, including removed options.
+    pub tally: Vec<VoteCount>,
+    /// The artifact that lets auditors verify the tally against the encrypted
+    /// ballots, e.g. proofs of correct decryption of the summed ciphertexts.
+    pub proof: Vec<u8>,
+}
+
+/// The officially certified result of an election, which the admin posts
+/// with `certifyResult`. It is also the parameter type of that function.
+#[derive(Serialize, SchemaType, Clone, PartialEq, Eq, Debug)]
+pub struct CertifiedResult {
+    /// The map connects each voting option to its number of votes, as
+    /// certified by the election authority.
+    pub tally: BTreeMap<VotingOption, VoteCount>,
+    /// The signature of the election authority on the `CertificationMessage`.
     pub signature: SignatureEd25519,
 }
 
-/// A voting option, i.e. a country code.
-pub type VotingOption = String;
-/// The voting options are stored in a vector. The vector index is used to refer
-/// to a specific voting option.
-pub type VoteIndex = u32;
-/// Number of votes.
-pub type VoteCount = u32;
+/// The message that the election authority signs to certify the result of
+/// an election.
+#[derive(Serialize, SchemaType)]
+pub struct CertificationMessage {
+    /// The address of the voting contract instance.
+    pub contract_address: ContractAddress,
+    /// The certified tally.
+    pub tally: BTreeMap<VotingOption, VoteCount>,
+}
 
 /// The parameter type for the contract function `init`.
 /// Takes a description, the voting options, and the `end_time` to start the
@@ -56,213 +696,5086 @@ pub type VoteCount = u32;
 pub struct InitParameter {
     /// The description of the election.
     pub description: String,
-    /// A vector of all voting options.
-    pub options: Vec<VotingOption>,
+    /// The identifier of the election, which the verifiers include in the
+    /// signed message.
+    pub election_id: String,
+    /// A vector of all voting options, together with their metadata.
+    pub options: Vec<OptionInfo>,
+    /// The first timestamp that an account can vote, if any. Without a
+    /// `start_time`, the election opens when this smart contract is
+    /// initialized. Accounts can register before the `start_time`.
+    pub start_time: Option<Timestamp>,
     /// The last timestamp that an account can vote.
-    /// The election is open from the point in time that this smart contract is
-    /// initialized until the `end_time`.
+    /// The election is open from the `start_time` until the `end_time`.
     pub end_time: Timestamp,
-    /// The public signature of the verifier for the voting proof.
-    pub verifier_public_key: PublicKeyEd25519,
+    /// The last timestamp that an account can register for a voting option.
+    pub registration_end_time: Timestamp,
+    /// The public keys of the verifiers for the voting proof. Without verifier
+    /// keys, accounts can vote without registering, like in the basic version
+    /// of the contract.
+    pub verifier_public_keys: Vec<VerifierPublicKey>,
+    /// The number of distinct verifiers that must have signed a vote. It must
+    /// be 0 if there are no verifier keys.
+    pub verifier_threshold: u8,
+    /// The percentage of voters that must approve a proposed extension of the
+    /// `end_time` before it is applied, from 1 to 100.
+    pub extension_approval_percentage: u8,
+    /// The member countries of the voting options that represent a bloc.
+    pub blocs: BTreeMap<VotingOption, Vec<VotingOption>>,
+    /// The minimum total balance an account must hold to vote, if any.
+    pub min_balance: Option<Amount>,
+    /// Whether accounts can change their vote. If `false`, each account can
+    /// only vote once.
+    pub allow_vote_change: bool,
+    /// The minimum time between two votes of the same account, if any.
+    pub change_cooldown: Option<Duration>,
+    /// The maximum number of times an account can change its vote, if any.
+    pub max_changes: Option<u8>,
+    /// The maximum number of votes a voting option can receive, if any. In a
+    /// weighted election, this limits the sum of the weights.
+    pub max_votes_per_option: Option<VoteCount>,
+    /// The minimum number of accounts that must vote for the result to be
+    /// valid.
+    pub quorum: u32,
+    /// The policy that determines the winner on a tie.
+    pub tie_breaking: TieBreakingPolicy,
+    /// The voting weights of the accounts, if the votes are weighted. Accounts
+    /// without a weight cannot vote.
+    pub weights: Option<Vec<(AccountAddress, u64)>>,
+    /// The CIS-2 token whose balances are the voting weights, if the votes are
+    /// weighted by a token. It cannot be combined with `weights`.
+    pub token_weights: Option<TokenWeights>,
+    /// Whether the votes are weighted by the square root of a deposit of CCD.
+    /// It cannot be combined with `weights` or `token_weights`.
+    pub quadratic_voting: bool,
+    /// The authority that decrypts and tallies the ballots, if the ballots are
+    /// encrypted. It cannot be combined with weighted votes.
+    pub tally_authority: Option<TallyAuthority>,
+    /// The root of the Merkle tree over the accounts that are allowed to
+    /// vote, if the election is closed.
+    pub allow_list_root: Option<MerkleNode>,
+    /// The CIS-4 registry whose credentials make accounts eligible to vote, if
+    /// the eligibility is checked on-chain. The verifiers are not used in that
+    /// case, so `verifier_public_keys` can be empty.
+    pub credential_registry: Option<ContractAddress>,
+    /// The time between the ends of two rounds, if the election recurs. After
+    /// a round is finalized, its result is archived and the next round starts
+    /// with the same voting options.
+    pub recurrence: Option<Duration>,
+    /// The contracts that may vote, e.g. a DAO treasury or a multisig wallet.
+    /// Other contracts are rejected.
+    pub contract_voters: Vec<ContractAddress>,
+    /// The maximum number of voters, if any. Once it is reached, only the
+    /// voters that have already voted can vote.
+    pub max_voters: Option<u32>,
+    /// The account that `withdrawFunds` transfers stray CCD to. If it is not
+    /// set, the CCD are transferred to the admin.
+    pub funds_recipient: Option<AccountAddress>,
+    /// The time after the `end_time` that the ballots are kept for, if they can
+    /// be deleted with `cleanup` at all.
+    pub retention_period: Option<Duration>,
+    /// The number of voters that the election expects, e.g. the number of
+    /// registered members, which `viewTurnout` reports the turnout against.
+    /// An expected number of 0 is treated as not provided.
+    pub expected_voters: Option<u32>,
+    /// Whether to record every vote of an account, with its time, so the
+    /// changes of mind can be analyzed with `viewVoteHistory`.
+    pub record_vote_history: bool,
+    /// Whether to only log the votes as events instead of storing the ballots
+    /// and the tally, which leaves the tallying to off-chain indexers.
+    pub logs_only: bool,
+    /// The public key of the election authority that certifies the official
+    /// result with `certifyResult`, if any.
+    pub certification_key: Option<PublicKeyEd25519>,
+    /// The CIS-2 contract that mints a non-transferable "I voted" badge to an
+    /// account on its first accepted vote, if any.
+    pub badge_contract: Option<ContractAddress>,
+    /// Whether the weight of a vote decays linearly from the start of the
+    /// election to the `end_time`, to reward early voters.
+    pub time_weighted: bool,
+    /// The SHA2-256 hash of the statement that the verifiers must check, which
+    /// their signatures must include.
+    pub statement_hash: HashSha2256,
 }
 
 /// The `return_value` type of the contract function `view`.
 /// Returns a description, the `end_time`, the voting options as a vector, and
 /// the number of voting options of the current election.
 /// Also returns the tally of votes.
+///
+/// It starts with the fields of `ElectionHeader`, which other contracts parse
+/// to identify the election, so new fields must be added after them.
 #[derive(Serial, Deserial, SchemaType)]
 pub struct VotingView {
     /// The description of the election.
     pub description: String,
+    /// The identifier of the election, which must be included when requesting
+    /// a signature from a verifier.
+    pub election_id: String,
+    /// The first timestamp that an account can vote, if any.
+    pub start_time: Option<Timestamp>,
     /// The last timestamp that an account can vote.
-    /// The election is open from the point in time that this smart contract is
-    /// initialized until the `end_time`.
+    /// The election is open from the `start_time` until the `end_time`.
     pub end_time: Timestamp,
-    /// The map connects the index of a voting option to the number of votes
-    /// it received so far.
+    /// The last timestamp that an account can register for a voting option.
+    pub registration_end_time: Timestamp,
+    /// The map connects each voting option to the number of votes it received
+    /// so far. Options without votes are included with a count of 0.
     pub tally: BTreeMap<VotingOption, VoteCount>,
+    /// The current status of the election.
+    pub status: ElectionStatus,
+    /// The result certified by the election authority, if any, which can
+    /// differ from the `tally` computed by the contract.
+    pub certified_result: Option<CertifiedResult>,
+    /// The map connects each voting option to the number of ballots cast for
+    /// it, regardless of their weight, which the `tally` sums up.
+    pub ballot_counts: BTreeMap<VotingOption, u32>,
 }
 
-/// The contract state
-#[derive(Serialize, Clone)]
-struct State {
+/// The value returned by the contract function `view`. It serializes to the
+/// same bytes as `VotingView`, but borrows the strings from the state instead
+/// of cloning them.
+#[derive(Serial)]
+struct VotingViewRef<'a> {
+    description: &'a str,
+    election_id: &'a str,
+    start_time: Option<Timestamp>,
+    end_time: Timestamp,
+    registration_end_time: Timestamp,
+    tally: BTreeMap<&'a str, VoteCount>,
+    status: ElectionStatus,
+    certified_result: Option<&'a CertifiedResult>,
+    ballot_counts: BTreeMap<&'a str, u32>,
+}
+
+/// The `return_value` type of the contract function `viewMetadata`, which is
+/// the information of `VotingView` that does not depend on the votes.
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct MetadataView {
     /// The description of the election.
-    description: String,
-    /// The public key of the verifier.
-    verifier_public_key: PublicKeyEd25519,
-    /// The map connects a voter to the index of the voted-for voting option.
-    ballots: BTreeMap<AccountAddress, VoteIndex>,
+    pub description: String,
     /// The last timestamp that an account can vote.
-    /// The election is open from the point in time that this smart contract is
-    /// initialized until the `end_time`.
-    end_time: Timestamp,
-    /// A vector of all voting options.
-    options: Vec<VotingOption>,
+    pub end_time: Timestamp,
+    /// The current status of the election.
+    pub status: ElectionStatus,
+    /// The voting options, in the order they were listed.
+    pub options: Vec<VotingOption>,
 }
 
-/// The different errors that the `vote` function can produce.
-#[derive(Reject, Serialize, PartialEq, Eq, Debug, SchemaType)]
-pub enum VotingError {
-    /// Raised when parsing the parameter failed.
-    #[from(ParseError)]
-    ParsingFailed,
-    /// Raised when the vote is placed after the election has ended.
-    VotingFinished,
-    /// Raised when voting for a voting option that does not exist.
-    InvalidVotingOption,
-    /// Raised when a smart contract tries to participate in the election. Only
-    /// accounts are allowed to vote.
-    ContractVoter,
-    /// The signature from the verifier is invalid.
-    InvalidSignature,
+/// The status of the election.
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub enum ElectionStatus {
+    /// Accounts can vote.
+    Open,
+    /// The admin has paused voting.
+    Paused,
+    /// The `end_time` has passed, but the election has not been finalized.
+    Ended,
+    /// The election has been finalized.
+    Finalized,
+    /// The admin has cancelled the election.
+    Cancelled,
+    /// The `start_time` has not been reached yet.
+    NotStarted,
 }
 
-/// A custom alias type for the `Result` type with the error type fixed to
-/// `VotingError`.
-pub type VotingResult<T> = Result<T, VotingError>;
+/// The parameter type for the contract function `viewBallots`.
+/// Selects a page of ballots, ordered by the account address of the voter.
+#[derive(Serialize, SchemaType)]
+pub struct ViewBallotsParameter {
+    /// The number of ballots to skip.
+    pub skip: u32,
+    /// The maximum number of ballots to return.
+    pub limit: u32,
+}
 
-/// The data used for signature message.
-pub struct SignatureMessageData {
-    /// The account address for which the proof was verified.
-    pub account_address: AccountAddress,
-    /// The country code for the country which the account does *not* have residency in.
-    pub country_code: String,
+/// The parameter type for the contract function `viewVotersForOption`.
+/// Selects a page of the voters of a voting option, ordered by their account
+/// address.
+#[derive(Serialize, SchemaType)]
+pub struct ViewVotersForOptionParameter {
+    /// The voting option to get the voters of.
+    pub option: VotingOption,
+    /// The last voter of the previous page, or `None` for the first page.
+    pub cursor: Option<AccountAddress>,
+    /// The maximum number of voters to return.
+    pub limit: u32,
 }
 
-impl Serial for SignatureMessageData {
-    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
-        self.account_address.serial(out)?;
-        out.write_all(&self.country_code.as_bytes())
-    }
+/// The parameter type for the contract function `viewTallyPartial`.
+/// Selects a page of the ballots to tally, in the order that the voters first
+/// voted.
+#[derive(Serialize, SchemaType)]
+pub struct ViewTallyPartialParameter {
+    /// The number of ballot positions to tally, which is the maximum number
+    /// of ballots in the page.
+    pub limit: u32,
+    /// The `cursor` returned for the previous page, or `None` for the first
+    /// page.
+    pub cursor: Option<u64>,
 }
 
-// Contract functions
+/// The `return_value` type of the contract function `viewTallyPartial`.
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct PartialTallyView {
+    /// The map connects each voting option to the votes of the ballots in the
+    /// page. Options without votes in the page are left out.
+    pub tally: BTreeMap<VotingOption, VoteCount>,
+    /// The cursor to pass to get the next page, i.e. the position of its
+    /// first ballot, or `None` if all ballots have been tallied.
+    pub cursor: Option<u64>,
+}
 
-/// Initialize the contract instance and start the election.
-/// A description, the vector of all voting options, and an `end_time`
-/// have to be provided.
-#[init(contract = "voting", parameter = "InitParameter")]
-fn init<S: HasStateApi>(
-    ctx: &impl HasInitContext,
-    _state_builder: &mut StateBuilder<S>,
-) -> InitResult<State> {
-    // Parse the parameter.
-    let param: InitParameter = ctx.parameter_cursor().get()?;
+/// The parameter type for the contract function `verifyReceipt`.
+#[derive(Serialize, SchemaType)]
+pub struct VerifyReceiptParameter {
+    /// The voter that the receipt was issued to.
+    pub voter: Address,
+    /// The receipt returned when the voter voted.
+    pub receipt: HashSha2256,
+}
 
-    // Set the state.
-    Ok(State {
-        description: param.description,
-        verifier_public_key: param.verifier_public_key,
-        ballots: BTreeMap::new(),
-        end_time: param.end_time,
-        options: param.options,
-    })
+/// The parameter type for the contract function `rotateVerifierKey`.
+#[derive(Serialize, SchemaType)]
+pub struct RotateVerifierKeyParameter {
+    /// The index of the verifier key to replace.
+    pub index: u8,
+    /// The new public key of the verifier.
+    pub new_key: VerifierPublicKey,
 }
 
-/// Enables accounts to vote for a specific voting option. Each account can
-/// change its selected voting option with this function as often as it desires
-/// until the `end_time` is reached.
+/// The parameter type for the contract function `voteFor`.
+/// A vote signed by the voter, which anyone can submit on its behalf.
+#[derive(Serialize, SchemaType)]
+pub struct VoteForParameter {
+    /// The account that the vote is cast for.
+    pub voter: AccountAddress,
+    /// The registration of the voter for the voting option it votes for,
+    /// including the signatures from the verifiers.
+    pub vote: RegisterParameter,
+    /// The signatures of the voter on the `VoteForMessage`, in the format of
+    /// messages signed in the wallets, see `voteFor`.
+    pub voter_signature: AccountSignatures,
+}
+
+/// The message that a voter signs to let someone else submit its vote with
+/// `voteFor`.
+#[derive(Serialize, SchemaType)]
+pub struct VoteForMessage {
+    /// The address of the voting contract instance that the vote is meant for.
+    pub contract_address: ContractAddress,
+    /// The nonce of the voter, which is the same nonce the verifiers sign. It
+    /// is incremented when the vote is accepted, so the signature can only be
+    /// used once.
+    pub nonce: u64,
+    /// The time until which the signature is valid, which is the expiry of
+    /// the signatures from the verifiers.
+    pub expiry: Timestamp,
+    /// The voting option voted for.
+    pub country_code: VotingOption,
+}
+
+/// The message that an account signs to let someone else invoke an
+/// entrypoint on its behalf with `permit`, as defined by the CIS-3 standard.
+#[derive(Serialize, SchemaType)]
+pub struct PermitMessage {
+    /// The address of the voting contract instance that the message is meant
+    /// for.
+    pub contract_address: ContractAddress,
+    /// The nonce of the signer, which is the same nonce the verifiers sign.
+    pub nonce: u64,
+    /// The time until which the signature is valid.
+    pub timestamp: Timestamp,
+    /// The entrypoint to invoke, i.e. `register` or `vote`.
+    pub entry_point: OwnedEntrypointName,
+    /// The serialized parameter of the entrypoint.
+    #[concordium(size_length = 2)]
+    pub payload: Vec<u8>,
+}
+
+/// The parameter type for the contract function `permit`.
+#[derive(Serialize, SchemaType)]
+pub struct PermitParam {
+    /// The signatures of the signer on the hash of the `message`.
+    pub signature: AccountSignatures,
+    /// The account that signed the message, on whose behalf the entrypoint
+    /// is invoked.
+    pub signer: AccountAddress,
+    /// The signed message.
+    pub message: PermitMessage,
+}
+
+/// The parameter type for the contract function `supportsPermit`.
+#[derive(Serialize, SchemaType)]
+pub struct SupportsPermitQueryParams {
+    /// The entrypoints to query support for.
+    #[concordium(size_length = 2)]
+    pub queries: Vec<OwnedEntrypointName>,
+}
+
+/// The event logged by `permit`, as defined by the CIS-3 standard.
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct NonceEvent {
+    /// The nonce that was used by the permit.
+    pub nonce: u64,
+    /// The account that signed the permit.
+    pub sponsoree: AccountAddress,
+}
+
+/// The final result of the election, which is stored when finalizing.
+#[derive(Serialize, SchemaType, Clone)]
+pub struct ElectionResult {
+    /// The map connects each voting option to the number of votes it
+    /// received.
+    pub tally: BTreeMap<VotingOption, VoteCount>,
+    /// The winner of the election.
+    pub outcome: ElectionOutcome,
+}
+
+/// A vote of an account in its vote history.
+#[derive(Serialize, SchemaType, Clone, PartialEq, Eq, Debug)]
+pub struct VoteRecord {
+    /// The time of the vote.
+    pub timestamp: Timestamp,
+    /// The voting option that the account voted for.
+    pub option: VotingOption,
+}
+
+/// The `return_value` type of the contract function `getVerifierKey`.
+#[derive(Serialize, SchemaType, Clone, PartialEq, Eq, Debug)]
+pub struct VerifierKeysView {
+    /// The public keys of the verifiers, in the order of their indices.
+    pub keys: Vec<VerifierPublicKey>,
+    /// The number of verifiers that must sign a registration.
+    pub threshold: u8,
+}
+
+/// The `return_value` type of the contract function `viewTurnout`.
+#[derive(Serialize, SchemaType, Clone, PartialEq, Eq, Debug)]
+pub struct TurnoutView {
+    /// The number of voters that have voted.
+    pub votes_cast: u32,
+    /// The number of voters that the election expects, if it was provided at
+    /// initialization.
+    pub expected_voters: Option<u32>,
+    /// The `votes_cast` per thousand `expected_voters`, rounded down, if the
+    /// expected number of voters was provided. It exceeds 1000 if more voters
+    /// voted than expected.
+    pub permille: Option<u64>,
+}
+
+/// The `return_value` type of the contract function `getResult`.
+#[derive(Serialize, SchemaType, Clone, PartialEq, Eq, Debug)]
+pub struct ElectionOutcome {
+    /// The voting option with the most votes, or `None` if nobody voted.
+    /// On a tie, the winner is determined by the `tie_breaking` policy.
+    pub winner: Option<VotingOption>,
+    /// The number of votes the winner received.
+    pub winner_count: VoteCount,
+    /// The voting options that share the highest number of votes, in the
+    /// order they were listed, if there is more than one. Otherwise empty.
+    pub tied: Vec<VotingOption>,
+    /// Whether at least `quorum` accounts voted. If not, the result is
+    /// invalid.
+    pub quorum_reached: bool,
+}
+
+/// A proposal to extend the `end_time` of the election.
+#[derive(Serialize, SchemaType, Clone)]
+pub struct ExtensionProposal {
+    /// The voter that proposed the extension.
+    pub proposer: AccountAddress,
+    /// The proposed new `end_time`.
+    pub new_end_time: Timestamp,
+    /// The voters that have approved the extension, including the proposer.
+    pub approvals: BTreeSet<AccountAddress>,
+}
+
+/// The event logged when an account casts or changes its vote.
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct VoteEvent {
+    /// The account that voted.
+    pub voter: AccountAddress,
+    /// The previously selected voting option, if the account had voted before.
+    pub old_option: Option<VotingOption>,
+    /// The newly selected voting option.
+    pub new_option: VotingOption,
+}
+
+/// The events logged by the contract.
 ///
-/// This "advanced" version includes both the voting option, which is a country code, and a signature from the verifier.
-/// It will only accept the vote if the voting option is present in the list of voting options AND the signature is valid, which means that the sender does *not* live in the country voted on.
+/// The tags of the events are their positions, apart from `Nonce`, which uses
+/// the tag 250 defined by the CIS-3 standard.
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+#[concordium(repr(u8))]
+pub enum VotingEvent {
+    /// A voter proposed to extend the `end_time`.
+    ExtensionProposed {
+        /// The voter that proposed the extension.
+        proposer: AccountAddress,
+        /// The proposed new `end_time`.
+        new_end_time: Timestamp,
+    },
+    /// A voter approved the pending extension proposal.
+    ExtensionApproved {
+        /// The voter that approved the extension.
+        voter: AccountAddress,
+    },
+    /// The `end_time` was extended.
+    EndTimeExtended {
+        /// The previous `end_time`.
+        old_end_time: Timestamp,
+        /// The new `end_time`.
+        new_end_time: Timestamp,
+    },
+    /// An account cast or changed its vote.
+    Vote(VoteEvent),
+    /// The election was finalized.
+    Finalized {
+        /// The voting option with the most votes, or `None` if nobody voted.
+        winner: Option<VotingOption>,
+    },
+    /// The admin paused voting.
+    Paused,
+    /// The admin resumed voting.
+    Unpaused,
+    /// The admin cancelled the election.
+    Cancelled,
+    /// The admin added a voting option.
+    OptionAdded {
+        /// The added voting option.
+        option: VotingOption,
+    },
+    /// The admin removed a voting option.
+    OptionRemoved {
+        /// The removed voting option.
+        option: VotingOption,
+    },
+    /// The admin updated the description. The description itself is not
+    /// logged, as it may exceed the maximum size of an event, but it can be
+    /// retrieved with `view`.
+    DescriptionUpdated,
+    /// The admin replaced the public key of the verifier.
+    VerifierKeyRotated {
+        /// The index of the verifier key.
+        index: u8,
+        /// The previous public key of the verifier.
+        old_key: VerifierPublicKey,
+        /// The new public key of the verifier.
+        new_key: VerifierPublicKey,
+    },
+    /// The admin role was transferred to another account.
+    AdminTransferred {
+        /// The previous admin.
+        old_admin: AccountAddress,
+        /// The new admin.
+        new_admin: AccountAddress,
+    },
+    /// A voter withdrew its ballot.
+    VoteRetracted {
+        /// The voter that retracted its vote.
+        voter: AccountAddress,
+        /// The voting option the voter had voted for.
+        option: VotingOption,
+    },
+    /// A voter voted for a country that is not a voting option. The vote is
+    /// pending until the admin approves the write-in.
+    WriteInSubmitted {
+        /// The voter that submitted the write-in.
+        voter: AccountAddress,
+        /// The country written in.
+        option: VotingOption,
+    },
+    /// An account delegated its voting power to another account.
+    Delegated {
+        /// The account that delegated its voting power.
+        delegator: AccountAddress,
+        /// The account that the voting power was delegated to.
+        delegate: AccountAddress,
+    },
+    /// An account took back the voting power it had delegated.
+    Undelegated {
+        /// The account that took back its voting power.
+        delegator: AccountAddress,
+        /// The account that the voting power was delegated to.
+        delegate: AccountAddress,
+    },
+    /// An account withdrew its deposit.
+    DepositWithdrawn {
+        /// The account that withdrew its deposit.
+        account: AccountAddress,
+        /// The amount of CCD transferred back to the account.
+        amount: Amount,
+    },
+    /// An account cast or replaced its encrypted ballot.
+    EncryptedVote {
+        /// The account that voted.
+        voter: AccountAddress,
+    },
+    /// The admin started a runoff between the two voting options with the
+    /// most votes.
+    RunoffStarted {
+        /// The voting options in the runoff.
+        options: Vec<VotingOption>,
+        /// The `end_time` of the runoff.
+        end_time: Timestamp,
+    },
+    /// A new round of a recurring election started after the previous round
+    /// was finalized.
+    RoundStarted {
+        /// The number of the new round, starting from 0 for the first round.
+        round: u32,
+        /// The `end_time` of the new round.
+        end_time: Timestamp,
+    },
+    /// A contract voter cast or changed its vote.
+    ContractVote {
+        /// The contract that voted.
+        voter: ContractAddress,
+        /// The previously selected voting option, if the contract had voted
+        /// before.
+        old_option: Option<VotingOption>,
+        /// The newly selected voting option.
+        new_option: VotingOption,
+    },
+    /// The admin excluded an account from the election.
+    Blacklisted {
+        /// The excluded account.
+        account: AccountAddress,
+    },
+    /// The admin lifted the exclusion of an account.
+    Unblacklisted {
+        /// The account that may vote again.
+        account: AccountAddress,
+    },
+    /// The admin transferred CCD that are not deposits off the contract.
+    FundsWithdrawn {
+        /// The account that received the CCD.
+        recipient: AccountAddress,
+        /// The amount of CCD transferred.
+        amount: Amount,
+    },
+    /// The admin deleted the ballots after the retention period.
+    BallotsCleared,
+    /// A voter cast or changed its vote in an election that only logs the
+    /// votes. The latest vote of a voter replaces its previous votes.
+    VoteLogged {
+        /// The account or contract that voted.
+        voter: Address,
+        /// The voting option that the voter voted for.
+        option: VotingOption,
+        /// The voting weight of the voter.
+        weight: u64,
+    },
+    /// The admin posted the result certified by the election authority.
+    ResultCertified {
+        /// Whether the certified tally matches the tally computed by the
+        /// contract.
+        matches_computed: bool,
+    },
+    /// The election was initialized.
+    ElectionCreated {
+        /// The SHA2-256 hash of the description.
+        description_hash: HashSha2256,
+        /// The number of voting options.
+        option_count: u32,
+        /// The last timestamp that an account can vote.
+        end_time: Timestamp,
+        /// The SHA2-256 hash of the serialized verifier keys, which
+        /// `getVerifierKey` returns.
+        verifier_keys_hash: HashSha2256,
+    },
+    /// A permit of an account was executed.
+    #[concordium(tag = 250)]
+    Nonce(NonceEvent),
+}
+
+/// The contract state
 ///
-/// It rejects if:
-/// - It fails to parse the parameter.
-/// - The voting option does not exist.
-/// - A contract tries to vote.
-/// - It is past the `end_time`.
-/// - The signature is invalid.
-#[receive(
-    contract = "voting",
-    name = "vote",
-    mutable,
-    parameter = "VoteParameter",
-    error = "VotingError",
-    crypto_primitives
-)]
-fn vote<S: HasStateApi>(
-    ctx: &impl HasReceiveContext,
-    host: &mut impl HasHost<State, StateApiType = S>,
-    crypto_primitives: &impl HasCryptoPrimitives,
-) -> VotingResult<()> {
-    // Check that the election hasn't finished yet.
-    if ctx.metadata().slot_time() > host.state().end_time {
-        return Err(VotingError::VotingFinished);
+/// The ballots are kept in a `StateMap`, so they are only loaded from the
+/// contract state when they are accessed. This allows the election to scale
+/// to a large number of voters. The number of ballots, and thereby the size of
+/// the state, can additionally be bounded with `max_voters`.
+#[derive(Serial, DeserialWithState)]
+#[concordium(state_parameter = "S")]
+struct State<S> {
+    /// The account that can call the admin entrypoints.
+    admin: AccountAddress,
+    /// The description of the election.
+    description: String,
+    /// The identifier of the election, which the verifiers include in the
+    /// signed message.
+    election_id: String,
+    /// The public keys of the verifiers.
+    verifier_public_keys: Vec<VerifierPublicKey>,
+    /// The number of distinct verifiers that must have signed a vote.
+    verifier_threshold: u8,
+    /// The map connects a voter to the index of the voted-for voting option.
+    ballots: StateMap<AccountAddress, VoteIndex, S>,
+    /// The map connects the position of a ballot, in the order that the
+    /// voters first voted, to the voter, so a page of ballots can be read
+    /// without iterating over the ballots before it.
+    ballot_order: StateMap<u64, AccountAddress, S>,
+    /// The map connects a voter to the position of its ballot in
+    /// `ballot_order`.
+    ballot_positions: StateMap<AccountAddress, u64, S>,
+    /// The position of the next new ballot in `ballot_order`.
+    next_ballot_position: u64,
+    /// The contracts that may vote.
+    contract_voters: StateSet<ContractAddress, S>,
+    /// The map connects a contract voter to the index of the voted-for voting
+    /// option.
+    contract_ballots: StateMap<ContractAddress, VoteIndex, S>,
+    /// The map connects a voter to the receipt of its latest vote.
+    receipts: StateMap<Address, HashSha2256, S>,
+    /// The number of accounts that have voted. This is tracked separately, as
+    /// the length of a `StateMap` is not available.
+    voter_count: u32,
+    /// The maximum number of voters, if any.
+    max_voters: Option<u32>,
+    /// The map connects an account to the indices of the voting options that
+    /// it has registered for, i.e. that it is eligible to vote for.
+    eligibility: StateMap<AccountAddress, BTreeSet<VoteIndex>, S>,
+    /// The map connects an account to the nonce that the signatures for its
+    /// next registration must include. It is incremented on every accepted
+    /// registration, so a signature cannot be replayed.
+    nonces: StateMap<AccountAddress, u64, S>,
+    /// The map connects the index of a voting option to the number of votes
+    /// it received so far. It is updated on every vote, so the tally does not
+    /// have to be recomputed from the ballots.
+    tally: BTreeMap<VoteIndex, VoteCount>,
+    /// The first timestamp that an account can vote, if any.
+    start_time: Option<Timestamp>,
+    /// The last timestamp that an account can vote.
+    /// The election is open from the `start_time` until the `end_time`.
+    end_time: Timestamp,
+    /// The last timestamp that an account can register for a voting option.
+    registration_end_time: Timestamp,
+    /// A vector of all voting options with their metadata, including removed
+    /// ones.
+    options: Vec<OptionInfo>,
+    /// The indices of the voting options that have been removed. Removed
+    /// options stay in `options`, so the indices of the others are stable.
+    removed_options: BTreeSet<VoteIndex>,
+    /// The percentage of voters that must approve a proposed extension.
+    extension_approval_percentage: u8,
+    /// The pending proposal to extend the `end_time`, if any.
+    extension_proposal: Option<ExtensionProposal>,
+    /// The member countries of the voting options that represent a bloc.
+    blocs: BTreeMap<VotingOption, Vec<VotingOption>>,
+    /// The minimum total balance an account must hold to vote, if any.
+    min_balance: Option<Amount>,
+    /// Whether accounts can change their vote.
+    allow_vote_change: bool,
+    /// The minimum time between two votes of the same account, if any.
+    change_cooldown: Option<Duration>,
+    /// The map connects a voter to the time of its last vote. It is only
+    /// maintained if a `change_cooldown` is set.
+    last_vote_times: StateMap<AccountAddress, Timestamp, S>,
+    /// The maximum number of times an account can change its vote, if any.
+    max_changes: Option<u8>,
+    /// The map connects a voter to the number of times it has changed its
+    /// vote. It is only maintained if `max_changes` is set.
+    change_counts: StateMap<AccountAddress, u8, S>,
+    /// The maximum number of votes a voting option can receive, if any.
+    max_votes_per_option: Option<VoteCount>,
+    /// The minimum number of accounts that must vote for the result to be
+    /// valid.
+    quorum: u32,
+    /// The policy that determines the winner on a tie.
+    tie_breaking: TieBreakingPolicy,
+    /// The number of votes that have been counted, including changed votes.
+    /// It orders the votes when breaking a tie by the earliest option to reach
+    /// the winning count, and is only maintained for that policy.
+    vote_sequence: u64,
+    /// The map connects a voting option and a number of votes to the
+    /// `vote_sequence` at which the option first reached that number of votes.
+    /// It is only maintained if ties are broken by the earliest option to
+    /// reach the winning count.
+    count_reached: StateMap<(VoteIndex, VoteCount), u64, S>,
+    /// Whether the votes are weighted by `weights`.
+    weighted: bool,
+    /// The map connects an account to its voting weight, if the votes are
+    /// weighted.
+    weights: StateMap<AccountAddress, u64, S>,
+    /// The CIS-2 token whose balances are the voting weights, if the votes are
+    /// weighted by a token.
+    token_weights: Option<TokenWeights>,
+    /// Whether the votes are weighted by the square root of a deposit of CCD.
+    quadratic_voting: bool,
+    /// The map connects an account to the amount of CCD it has deposited and
+    /// that is locked in the contract, if the votes are quadratic.
+    deposits: StateMap<AccountAddress, Amount, S>,
+    /// The sum of the `deposits`, which is the part of the balance of the
+    /// contract that belongs to the voters.
+    total_deposits: Amount,
+    /// The account that stray CCD are transferred to, if not the admin.
+    funds_recipient: Option<AccountAddress>,
+    /// The time after the `end_time` that the ballots are kept for, if any.
+    retention_period: Option<Duration>,
+    /// The number of voters that the election expects, if any.
+    expected_voters: Option<u32>,
+    /// Whether every vote of an account is recorded in `vote_history`.
+    record_vote_history: bool,
+    /// The map connects an account to its votes in the order they were cast,
+    /// if `record_vote_history` is set.
+    vote_history: StateMap<AccountAddress, Vec<VoteRecord>, S>,
+    /// Whether the votes are only logged, without storing the ballots and
+    /// the tally.
+    logs_only: bool,
+    /// The public key of the election authority that certifies the result,
+    /// if any.
+    certification_key: Option<PublicKeyEd25519>,
+    /// The result certified by the election authority, if it has been posted.
+    certified_result: Option<CertifiedResult>,
+    /// The CIS-2 contract that mints the "I voted" badges, if any.
+    badge_contract: Option<ContractAddress>,
+    /// The accounts that a badge has been minted to.
+    badge_holders: StateSet<AccountAddress, S>,
+    /// Whether the weight of a vote decays over the course of the election.
+    time_weighted: bool,
+    /// The time the contract was initialized, which is the start of a
+    /// time-weighted election without a `start_time`.
+    initialized_at: Timestamp,
+    /// The map connects each voting option to the number of ballots cast for
+    /// it, regardless of their weight.
+    ballot_counts: StateMap<VoteIndex, u32, S>,
+    /// The hash of the statement that the verifiers must check.
+    statement_hash: HashSha2256,
+    /// The map connects a delegating account to its delegation.
+    delegations: StateMap<AccountAddress, Delegation, S>,
+    /// The map connects an account to the total voting weight delegated to
+    /// it, directly or through other delegates.
+    received_weights: StateMap<AccountAddress, u64, S>,
+    /// The map connects an account to the lengths of the delegation chains
+    /// that end at it through its direct delegators, and how many of its
+    /// direct delegators contribute each length. The longest length is the
+    /// delegation depth of the account.
+    delegator_depths: StateMap<AccountAddress, BTreeMap<u32, u32>, S>,
+    /// The map connects a voter to the weight its vote was counted with.
+    ballot_weights: StateMap<AccountAddress, u64, S>,
+    /// The map connects a voter to the country it has written in, until the
+    /// admin approves the write-in.
+    pending_write_ins: StateMap<AccountAddress, VotingOption, S>,
+    /// The authority that decrypts and tallies the ballots, if the ballots are
+    /// encrypted.
+    tally_authority: Option<TallyAuthority>,
+    /// The map connects a voter to its encrypted ballot, i.e. one ciphertext
+    /// per voting option. It is only used if the ballots are encrypted.
+    encrypted_ballots: StateMap<AccountAddress, Vec<ElGamalCiphertext>, S>,
+    /// The artifact posted by the tally authority together with the tally.
+    tally_proof: Option<Vec<u8>>,
+    /// The root of the Merkle tree over the accounts that are allowed to
+    /// vote, if the election is closed.
+    allow_list_root: Option<MerkleNode>,
+    /// The accounts that have proven that they are on the allow-list.
+    allow_listed: StateSet<AccountAddress, S>,
+    /// The accounts that the admin has excluded from the election.
+    blacklist: StateSet<AccountAddress, S>,
+    /// The CIS-4 registry whose credentials make accounts eligible to vote,
+    /// if any.
+    credential_registry: Option<ContractAddress>,
+    /// The map connects an account to the holder of the credential it votes
+    /// with.
+    credentials: StateMap<AccountAddress, PublicKeyEd25519, S>,
+    /// The map connects the holder of a credential to the account that votes
+    /// with it, so a credential can only be used by one account.
+    credential_holders: StateMap<PublicKeyEd25519, AccountAddress, S>,
+    /// The final result of the election, once it has been finalized.
+    result: Option<ElectionResult>,
+    /// The result of the first round, if a runoff has been started.
+    first_round: Option<ElectionResult>,
+    /// The time between the ends of two rounds, if the election recurs.
+    recurrence: Option<Duration>,
+    /// The number of rounds of a recurring election that have been finalized.
+    round: u32,
+    /// The map connects a finalized round of a recurring election to its
+    /// result.
+    history: StateMap<u32, ElectionResult, S>,
+    /// Whether voting is currently paused by the admin.
+    paused: bool,
+    /// Whether the admin has cancelled the election.
+    cancelled: bool,
+}
+
+impl<S: HasStateApi> State<S> {
+    /// Iterate over the voting options that have not been removed, together
+    /// with their indices, in the order they were listed.
+    fn active_options(&self) -> impl Iterator<Item = (VoteIndex, &VotingOption)> + Clone + '_ {
+        self.options
+            .iter()
+            .enumerate()
+            .map(|(index, option)| (index as VoteIndex, &option.code))
+            .filter(|(index, _)| !self.removed_options.contains(index))
     }
 
-    // Ensure that the sender is an account.
-    let acc = match ctx.sender() {
-        Address::Account(acc) => acc,
-        Address::Contract(_) => return Err(VotingError::ContractVoter),
-    };
+    /// Get the index of a voting option that has not been removed.
+    fn option_index(&self, option: &VotingOption) -> Option<VoteIndex> {
+        self.active_options()
+            .find(|(_, active_option)| *active_option == option)
+            .map(|(index, _)| index)
+    }
 
-    // Parse the parameter.
-    let new_vote: VoteParameter = ctx.parameter_cursor().get()?;
-    // Find the vote index in state.options. Or return an error, if it doesn't exist.
-    let new_vote_index = match host
-        .state()
-        .options
-        .iter()
-        .position(|o| *o == new_vote.country_code)
-    {
-        Some(vote_index) => vote_index as u32,
-        _ => return Err(VotingError::InvalidVotingOption),
-    };
+    /// Check that a voting option is well-formed, i.e. a country code or the
+    /// identifier of a bloc.
+    fn ensure_well_formed(&self, option: &VotingOption) -> VotingResult<()> {
+        if !is_country_code(option) && !self.blocs.contains_key(option) {
+            return Err(VotingError::MalformedCountryCode);
+        }
+        Ok(())
+    }
 
-    // Construct the message (account_address, country_code) and then use it to the check the signature.
-    let message_data = SignatureMessageData {
-        account_address: acc,
-        country_code: new_vote.country_code,
-    };
-    let message = to_bytes(&message_data);
-    if !crypto_primitives.verify_ed25519_signature(
-        host.state().verifier_public_key,
-        new_vote.signature,
-        &message,
-    ) {
-        return Err(VotingError::InvalidSignature);
+    /// Map the tally from the vote indices to the voting options. Every voting
+    /// option is included, also those without votes.
+    fn tally(&self) -> BTreeMap<VotingOption, VoteCount> {
+        self.active_options()
+            .map(|(index, option)| {
+                let count = self.tally.get(&index).copied().unwrap_or(0);
+                (option.clone(), count)
+            })
+            .collect()
     }
 
-    // Insert or replace the vote for the account.
-    host.state_mut()
-        .ballots
-        .entry(acc)
-        .and_modify(|old_vote_index| *old_vote_index = new_vote_index)
-        .or_insert(new_vote_index);
+    /// Get the frozen tally once the election is finalized. Otherwise, the
+    /// tally is maintained when voting, so it only has to be mapped to the
+    /// voting options.
+    fn current_tally(&self) -> BTreeMap<VotingOption, VoteCount> {
+        match &self.result {
+            Some(result) => result.tally.clone(),
+            None => self.tally(),
+        }
+    }
 
-    Ok(())
-}
+    /// Like `current_tally`, but borrows the voting options from the state
+    /// instead of cloning them.
+    fn current_tally_ref(&self) -> BTreeMap<&str, VoteCount> {
+        match &self.result {
+            Some(result) => result
+                .tally
+                .iter()
+                .map(|(option, count)| (option.as_str(), *count))
+                .collect(),
+            None => self
+                .active_options()
+                .map(|(index, option)| {
+                    let count = self.tally.get(&index).copied().unwrap_or(0);
+                    (option.as_str(), count)
+                })
+                .collect(),
+        }
+    }
 
-/// Get the election information.
-#[receive(contract = "voting", name = "view", return_value = "VotingView")]
-fn view<S: HasStateApi>(
-    _ctx: &impl HasReceiveContext,
-    host: &impl HasHost<State, StateApiType = S>,
-) -> ReceiveResult<VotingView> {
-    // Get information from the state.
-    let description = host.state().description.clone();
-    let end_time = host.state().end_time;
-    let options = host.state().options.clone();
-    let mut tally = BTreeMap::new();
-
-    // Sum up the ballots to a tally.
-    // Looping over data that can be changed by users should be avoided in
-    // production, as there might be so many ballots that the loop cannot be
-    // processed in time.
-    for (_, vote_index) in host.state().ballots.iter() {
-        // Get the VotingOption (String).
-        let voting_option = options[*vote_index as usize].clone();
-        // Increment the existing value or insert 1.
-        tally
-            .entry(voting_option)
-            .and_modify(|current_count| *current_count += 1)
-            .or_insert(1);
-    }
-
-    // Return the election information.
-    Ok(VotingView {
-        description,
-        end_time,
-        tally,
-    })
+    /// Derive the status of the election at `now`.
+    fn status(&self, now: Timestamp) -> ElectionStatus {
+        if self.cancelled {
+            ElectionStatus::Cancelled
+        } else if self.result.is_some() {
+            ElectionStatus::Finalized
+        } else if now > self.end_time {
+            ElectionStatus::Ended
+        } else if matches!(self.start_time, Some(start_time) if now < start_time) {
+            ElectionStatus::NotStarted
+        } else if self.paused {
+            ElectionStatus::Paused
+        } else {
+            ElectionStatus::Open
+        }
+    }
+
+    /// Check whether accounts must register before voting. This is the case
+    /// unless the election has neither verifiers nor a credential registry.
+    fn requires_registration(&self) -> bool {
+        !self.verifier_public_keys.is_empty() || self.credential_registry.is_some()
+    }
+
+    /// Get the voting option of the public ballot of an account, if any.
+    fn vote_of(&self, account: &AccountAddress) -> Option<VotingOption> {
+        self.ballots
+            .get(account)
+            .map(|vote_index| self.options[*vote_index as usize].code.clone())
+    }
+
+    /// Check whether an account has a ballot, either public or encrypted.
+    fn has_ballot(&self, account: &AccountAddress) -> bool {
+        self.ballots.get(account).is_some() || self.encrypted_ballots.get(account).is_some()
+    }
+
+    /// Add a voting option to the options that an account is eligible for.
+    fn add_eligible_option(&mut self, account: AccountAddress, vote_index: VoteIndex) {
+        let mut eligible_options = self
+            .eligibility
+            .get(&account)
+            .map(|eligible_options| eligible_options.clone())
+            .unwrap_or_default();
+        eligible_options.insert(vote_index);
+        self.eligibility.insert(account, eligible_options);
+    }
+
+    /// Check that an account may cast a vote at `now`, and record the vote
+    /// change if the account has voted before. A pending write-in counts as a
+    /// vote.
+    ///
+    /// It rejects if:
+    /// - The account has already voted and the election does not allow vote
+    ///   changes.
+    /// - The account has already changed its vote `max_changes` times.
+    /// - The account changes its vote before the `change_cooldown` has passed.
+    fn record_vote_change(&mut self, voter: AccountAddress, now: Timestamp) -> VotingResult<()> {
+        // Check that the account has not voted yet, if vote changes are not allowed.
+        let has_voted = self.has_ballot(&voter) || self.pending_write_ins.get(&voter).is_some();
+        if !self.allow_vote_change && has_voted {
+            return Err(VotingError::AlreadyVoted);
+        }
+
+        // Check that the account has not used up its vote changes, and count this change.
+        if let (Some(max_changes), true) = (self.max_changes, has_voted) {
+            let change_count = self
+                .change_counts
+                .get(&voter)
+                .map_or(0, |change_count| *change_count);
+            if change_count >= max_changes {
+                return Err(VotingError::TooManyVoteChanges);
+            }
+            self.change_counts.insert(voter, change_count + 1);
+        }
+
+        // Check that the cooldown since the last vote of the account has passed, and record the time of this vote.
+        if let Some(change_cooldown) = self.change_cooldown {
+            if let Some(last_vote_time) = self.last_vote_times.get(&voter) {
+                // A cooldown that overflows the timestamp never passes.
+                let too_soon = match last_vote_time.checked_add(change_cooldown) {
+                    Some(cooldown_end) => now < cooldown_end,
+                    None => true,
+                };
+                if too_soon {
+                    return Err(VotingError::VoteChangeTooSoon);
+                }
+            }
+            self.last_vote_times.insert(voter, now);
+        }
+
+        Ok(())
+    }
+
+    /// Get the voting weight of an account from the `weights`, which is 1
+    /// unless the votes are weighted.
+    fn fixed_voting_weight(&self, account: &AccountAddress) -> u64 {
+        if !self.weighted {
+            return 1;
+        }
+        self.weights.get(account).map_or(0, |weight| *weight)
+    }
+
+    /// Insert or replace the vote of an account, update the tally, and log
+    /// the vote.
+    ///
+    /// The vote is counted with the given `weight`, which replaces the weight
+    /// of a previous vote of the account.
+    ///
+    /// It rejects if the vote would make the voting option exceed
+    /// `max_votes_per_option`.
+    fn cast_vote(
+        &mut self,
+        voter: AccountAddress,
+        new_vote_index: VoteIndex,
+        weight: u64,
+        logger: &mut impl HasLogger,
+    ) -> VotingResult<()> {
+        if self.logs_only {
+            return self.log_vote(Address::Account(voter), new_vote_index, weight, logger);
+        }
+
+        if let Some(max_votes) = self.max_votes_per_option {
+            let mut count = self.tally.get(&new_vote_index).copied().unwrap_or(0);
+            // Do not count the previous vote of the account twice.
+            if self.ballots.get(&voter).map(|index| *index) == Some(new_vote_index) {
+                count -= self.ballot_weights.get(&voter).map_or(0, |weight| *weight);
+            }
+            if count
+                .checked_add(weight)
+                .ok_or(VotingError::WeightOverflow)?
+                > max_votes
+            {
+                return Err(VotingError::OptionFull);
+            }
+        }
+
+        let old_vote_index = self.ballots.insert(voter, new_vote_index);
+        self.move_ballot_count(old_vote_index, Some(new_vote_index));
+        let old_weight = self.ballot_weights.insert(voter, weight).unwrap_or(weight);
+
+        // Move the vote of the account in the tally, or count the new voter.
+        match old_vote_index {
+            Some(old_vote_index) => {
+                if let Some(count) = self.tally.get_mut(&old_vote_index) {
+                    *count -= old_weight;
+                }
+            }
+            None => {
+                self.count_new_voter()?;
+                self.order_ballot(voter);
+            }
+        }
+        self.count_votes(new_vote_index, weight)?;
+
+        // Log the vote.
+        logger.log(&VotingEvent::Vote(VoteEvent {
+            voter,
+            old_option: old_vote_index.map(|index| self.options[index as usize].code.clone()),
+            new_option: self.options[new_vote_index as usize].code.clone(),
+        }))?;
+
+        Ok(())
+    }
+
+    /// Append the ballot of a new voter to `ballot_order`.
+    fn order_ballot(&mut self, voter: AccountAddress) {
+        let position = self.next_ballot_position;
+        self.ballot_order.insert(position, voter);
+        self.ballot_positions.insert(voter, position);
+        self.next_ballot_position += 1;
+    }
+
+    /// Log the vote of a voter without storing it, if the election only logs
+    /// the votes.
+    fn log_vote(
+        &self,
+        voter: Address,
+        vote_index: VoteIndex,
+        weight: u64,
+        logger: &mut impl HasLogger,
+    ) -> VotingResult<()> {
+        logger.log(&VotingEvent::VoteLogged {
+            voter,
+            option: self.options[vote_index as usize].code.clone(),
+            weight,
+        })?;
+        Ok(())
+    }
+
+    /// Remove the ballot of an account from the tally, stop counting it as a
+    /// voter, withdraw its approval of a pending extension proposal, and log
+    /// the retraction. Returns whether the account had a ballot.
+    fn remove_ballot(
+        &mut self,
+        acc: AccountAddress,
+        logger: &mut impl HasLogger,
+    ) -> VotingResult<bool> {
+        let old_vote_index = match self.ballots.remove_and_get(&acc) {
+            Some(old_vote_index) => old_vote_index,
+            None => return Ok(false),
+        };
+        self.move_ballot_count(Some(old_vote_index), None);
+        // The position of the ballot is left empty.
+        if let Some(position) = self.ballot_positions.remove_and_get(&acc) {
+            self.ballot_order.remove(&position);
+        }
+
+        // Remove the vote from the tally and stop counting the voter.
+        self.receipts.remove(&Address::Account(acc));
+        let weight = self.ballot_weights.remove_and_get(&acc).unwrap_or(0);
+        if let Some(count) = self.tally.get_mut(&old_vote_index) {
+            *count -= weight;
+        }
+        self.voter_count -= 1;
+
+        logger.log(&VotingEvent::VoteRetracted {
+            voter: acc,
+            option: self.options[old_vote_index as usize].code.clone(),
+        })?;
+
+        if let Some(proposal) = self.extension_proposal.as_mut() {
+            proposal.approvals.remove(&acc);
+        }
+        Ok(true)
+    }
+
+    /// Withdraw the delegation of an account, including the weight delegated
+    /// to the account in the meantime. Returns the delegate, or `None` if the
+    /// account has not delegated.
+    fn remove_delegation(&mut self, acc: AccountAddress) -> Option<AccountAddress> {
+        let delegation = self.delegations.remove_and_get(&acc)?;
+
+        // Take back the snapshotted own weight together with the weight delegated
+        // to the account in the meantime.
+        let received_weight = self.received_weights.get(&acc).map_or(0, |weight| *weight);
+        self.remove_delegated_weight(delegation.delegate, delegation.weight + received_weight);
+        let depth = self.delegation_depth(acc);
+        self.update_delegation_depths(delegation.delegate, Some(depth + 1), None);
+        Some(delegation.delegate)
+    }
+
+    /// Insert or replace the vote of a contract, update the tally, and log the
+    /// vote. The contracts that may vote are set at initialization, so they
+    /// neither register nor need a minimum balance, and their votes have a
+    /// weight of 1.
+    ///
+    /// It rejects if:
+    /// - The contract is not one of the `contract_voters`.
+    /// - The voting option does not exist.
+    /// - The contract has already voted and the election does not allow vote
+    ///   changes.
+    /// - The vote would make the voting option exceed `max_votes_per_option`.
+    fn cast_contract_vote(
+        &mut self,
+        voter: ContractAddress,
+        new_vote: &VotingOption,
+        logger: &mut impl HasLogger,
+    ) -> VotingResult<()> {
+        if !self.contract_voters.contains(&voter) {
+            return Err(VotingError::ContractVoter);
+        }
+        self.ensure_well_formed(new_vote)?;
+        let new_vote_index =
+            self.option_index(new_vote)
+                .ok_or_else(|| VotingError::InvalidVotingOption {
+                    option: new_vote.clone(),
+                })?;
+        if self.logs_only {
+            return self.log_vote(Address::Contract(voter), new_vote_index, 1, logger);
+        }
+
+        let old_vote_index = self.contract_ballots.get(&voter).map(|index| *index);
+        if !self.allow_vote_change && old_vote_index.is_some() {
+            return Err(VotingError::AlreadyVoted);
+        }
+        if let Some(max_votes) = self.max_votes_per_option {
+            let count = self.tally.get(&new_vote_index).copied().unwrap_or(0);
+            // Do not count the previous vote of the contract twice.
+            if old_vote_index != Some(new_vote_index) && count + 1 > max_votes {
+                return Err(VotingError::OptionFull);
+            }
+        }
+
+        // Move the vote of the contract in the tally, or count the new voter.
+        self.contract_ballots.insert(voter, new_vote_index);
+        self.move_ballot_count(old_vote_index, Some(new_vote_index));
+        match old_vote_index {
+            Some(old_vote_index) => {
+                if let Some(count) = self.tally.get_mut(&old_vote_index) {
+                    *count -= 1;
+                }
+            }
+            None => self.count_new_voter()?,
+        }
+        self.count_votes(new_vote_index, 1)?;
+
+        logger.log(&VotingEvent::ContractVote {
+            voter,
+            old_option: old_vote_index.map(|index| self.options[index as usize].code.clone()),
+            new_option: new_vote.clone(),
+        })?;
+
+        Ok(())
+    }
+
+    /// Count a voter that has not voted before.
+    ///
+    /// It rejects if the number of voters has reached `max_voters`.
+    fn count_new_voter(&mut self) -> VotingResult<()> {
+        if self
+            .max_voters
+            .is_some_and(|max_voters| self.voter_count >= max_voters)
+        {
+            return Err(VotingError::VoterLimitReached);
+        }
+        self.voter_count += 1;
+        Ok(())
+    }
+
+    /// Move a ballot from the ballot count of the voting option
+    /// `old_vote_index` to the count of `new_vote_index`, if any.
+    fn move_ballot_count(
+        &mut self,
+        old_vote_index: Option<VoteIndex>,
+        new_vote_index: Option<VoteIndex>,
+    ) {
+        if let Some(old_vote_index) = old_vote_index {
+            if let Some(mut count) = self.ballot_counts.get_mut(&old_vote_index) {
+                *count -= 1;
+            }
+        }
+        if let Some(new_vote_index) = new_vote_index {
+            *self.ballot_counts.entry(new_vote_index).or_insert(0) += 1;
+        }
+    }
+
+    /// Get the number of ballots of each active voting option.
+    fn ballot_counts(&self) -> BTreeMap<&str, u32> {
+        self.active_options()
+            .map(|(index, option)| {
+                let count = self.ballot_counts.get(&index).map_or(0, |count| *count);
+                (option.as_str(), count)
+            })
+            .collect()
+    }
+
+    /// Get the weight of a vote with the voting weight `weight` cast at `now`.
+    /// In a time-weighted election, the weight is multiplied by a factor that
+    /// decreases linearly from `TIME_WEIGHT_SCALE` at the start of the
+    /// election to 1 at the `end_time`.
+    fn time_weight(&self, weight: u64, now: Timestamp) -> u64 {
+        if !self.time_weighted {
+            return weight;
+        }
+        let start = self.start_time.unwrap_or(self.initialized_at);
+        let duration = self
+            .end_time
+            .timestamp_millis()
+            .saturating_sub(start.timestamp_millis());
+        let remaining = self
+            .end_time
+            .timestamp_millis()
+            .saturating_sub(now.timestamp_millis())
+            .min(duration);
+        let factor = match duration {
+            0 => TIME_WEIGHT_SCALE,
+            _ => 1 + (TIME_WEIGHT_SCALE - 1) * remaining / duration,
+        };
+        weight.saturating_mul(factor)
+    }
+
+    /// Add `weight` votes to the tally of a voting option.
+    ///
+    /// It rejects if the tally of the voting option would overflow.
+    fn count_votes(&mut self, vote_index: VoteIndex, weight: u64) -> VotingResult<()> {
+        let count = self
+            .tally
+            .get(&vote_index)
+            .copied()
+            .unwrap_or(0)
+            .checked_add(weight)
+            .ok_or(VotingError::WeightOverflow)?;
+        self.tally.insert(vote_index, count);
+
+        // Remember when the option first reached its new count, to be able to break ties.
+        if self.tie_breaking == TieBreakingPolicy::EarliestToReachCount {
+            self.vote_sequence += 1;
+            self.count_reached
+                .entry((vote_index, count))
+                .or_insert(self.vote_sequence);
+        }
+        Ok(())
+    }
+
+    /// Get the accounts that voting power delegated to `delegate` passes
+    /// through, i.e. `delegate` and the accounts it delegates to in turn. The
+    /// last account is the representative.
+    fn delegation_chain(&self, delegate: AccountAddress) -> Vec<AccountAddress> {
+        let mut account = delegate;
+        let mut chain = vec![account];
+        while let Some(next) = self
+            .delegations
+            .get(&account)
+            .map(|delegation| delegation.delegate)
+        {
+            account = next;
+            chain.push(account);
+        }
+        chain
+    }
+
+    /// Add `weight` to the weight received by every account in the delegation
+    /// chain of `delegate`, and to the vote of the representative if it has
+    /// voted.
+    ///
+    /// It rejects if:
+    /// - The vote of the representative would make its voting option exceed
+    ///   `max_votes_per_option`.
+    /// - The weight received by an account in the chain would overflow.
+    fn add_delegated_weight(&mut self, delegate: AccountAddress, weight: u64) -> VotingResult<()> {
+        let chain = self.delegation_chain(delegate);
+        for account in chain.iter() {
+            let received_weight = self
+                .received_weights
+                .get(account)
+                .map_or(0, |weight| *weight)
+                .checked_add(weight)
+                .ok_or(VotingError::WeightOverflow)?;
+            self.received_weights.insert(*account, received_weight);
+        }
+
+        let representative = chain[chain.len() - 1];
+        if let Some(vote_index) = self.ballots.get(&representative).map(|index| *index) {
+            if let Some(max_votes) = self.max_votes_per_option {
+                if self
+                    .tally
+                    .get(&vote_index)
+                    .copied()
+                    .unwrap_or(0)
+                    .checked_add(weight)
+                    .ok_or(VotingError::WeightOverflow)?
+                    > max_votes
+                {
+                    return Err(VotingError::OptionFull);
+                }
+            }
+            let ballot_weight = self
+                .ballot_weights
+                .get(&representative)
+                .map_or(0, |weight| *weight)
+                .checked_add(weight)
+                .ok_or(VotingError::WeightOverflow)?;
+            self.ballot_weights.insert(representative, ballot_weight);
+            self.count_votes(vote_index, weight)?;
+        }
+        Ok(())
+    }
+
+    /// Remove `weight` from the weight received by every account in the
+    /// delegation chain of `delegate`, and from the vote of the representative
+    /// if it has voted.
+    fn remove_delegated_weight(&mut self, delegate: AccountAddress, weight: u64) {
+        let chain = self.delegation_chain(delegate);
+        for account in chain.iter() {
+            let received_weight = self
+                .received_weights
+                .get(account)
+                .map_or(0, |weight| *weight);
+            self.received_weights
+                .insert(*account, received_weight - weight);
+        }
+
+        let representative = chain[chain.len() - 1];
+        if let Some(vote_index) = self.ballots.get(&representative).map(|index| *index) {
+            let ballot_weight = self
+                .ballot_weights
+                .get(&representative)
+                .map_or(0, |weight| *weight);
+            self.ballot_weights
+                .insert(representative, ballot_weight - weight);
+            if let Some(count) = self.tally.get_mut(&vote_index) {
+                *count -= weight;
+            }
+        }
+    }
+
+    /// Get the number of delegations in the longest chain that ends at
+    /// `account`, which is 0 if nobody delegates to it.
+    fn delegation_depth(&self, account: AccountAddress) -> u32 {
+        self.delegator_depths
+            .get(&account)
+            .and_then(|depths| depths.keys().next_back().copied())
+            .unwrap_or(0)
+    }
+
+    /// Replace the chain length `old` that a direct delegator contributes to
+    /// `account` with `new`, and pass the change of the delegation depth of
+    /// `account` on down its delegation chain.
+    fn update_delegation_depths(
+        &mut self,
+        mut account: AccountAddress,
+        mut old: Option<u32>,
+        mut new: Option<u32>,
+    ) {
+        loop {
+            let old_depth = self.delegation_depth(account);
+            let mut depths = self
+                .delegator_depths
+                .get(&account)
+                .map(|depths| depths.clone())
+                .unwrap_or_default();
+            if let Some(old) = old {
+                if let Some(count) = depths.get_mut(&old) {
+                    *count -= 1;
+                    if *count == 0 {
+                        depths.remove(&old);
+                    }
+                }
+            }
+            if let Some(new) = new {
+                *depths.entry(new).or_insert(0) += 1;
+            }
+            if depths.is_empty() {
+                self.delegator_depths.remove(&account);
+            } else {
+                self.delegator_depths.insert(account, depths);
+            }
+
+            let new_depth = self.delegation_depth(account);
+            let next = self
+                .delegations
+                .get(&account)
+                .map(|delegation| delegation.delegate);
+            match next {
+                Some(next) if new_depth != old_depth => {
+                    old = Some(old_depth + 1);
+                    new = Some(new_depth + 1);
+                    account = next;
+                }
+                _ => break,
+            }
+        }
+    }
+
+    /// Freeze the tally and determine the winner, and log the result. In a
+    /// recurring election, the result is archived and the next round starts.
+    fn store_result(&mut self, now: Timestamp, logger: &mut impl HasLogger) -> VotingResult<()> {
+        // Find the voting options with the most votes, in the order they were listed.
+        let counts = self.active_options().map(|(index, option)| {
+            let count = self.tally.get(&index).copied().unwrap_or(0);
+            (index, option, count)
+        });
+        let winner_count = counts.clone().map(|(_, _, count)| count).max().unwrap_or(0);
+        // Nobody wins if nobody voted.
+        let top: Vec<(VoteIndex, &VotingOption)> = counts
+            .filter(|(_, _, count)| winner_count > 0 && *count == winner_count)
+            .map(|(index, option, _)| (index, option))
+            .collect();
+
+        // Break a tie according to the policy.
+        let winner = match self.tie_breaking {
+            _ if top.len() <= 1 => top.first(),
+            TieBreakingPolicy::FirstListed => top.first(),
+            TieBreakingPolicy::EarliestToReachCount => top.iter().min_by_key(|(index, _)| {
+                self.count_reached
+                    .get(&(*index, winner_count))
+                    .map_or(u64::MAX, |sequence| *sequence)
+            }),
+            TieBreakingPolicy::NoWinner => None,
+        }
+        .map(|(_, option)| (**option).clone());
+        // Only report the tied options if there actually is a tie.
+        let tied: Vec<VotingOption> = if top.len() > 1 {
+            top.into_iter().map(|(_, option)| option.clone()).collect()
+        } else {
+            Vec::new()
+        };
+
+        self.result = Some(ElectionResult {
+            tally: self.tally(),
+            outcome: ElectionOutcome {
+                winner: winner.clone(),
+                winner_count,
+                tied,
+                quorum_reached: self.voter_count >= self.quorum,
+            },
+        });
+        logger.log(&VotingEvent::Finalized { winner })?;
+
+        // A recurring election immediately continues with the next round.
+        if let Some(recurrence) = self.recurrence {
+            self.start_next_round(recurrence, now, logger)?;
+        }
+
+        Ok(())
+    }
+
+    /// Start a runoff between the two voting options with the most votes,
+    /// which ends at `end_time`. The result of the first round is archived,
+    /// the other options are removed, and all ballots are discarded, while
+    /// the registrations, delegations, and deposits are kept. A tie for the
+    /// second place is won by the option listed first.
+    ///
+    /// It rejects if the election has not been finalized, a runoff has already
+    /// been started, or the winner has more than half of the votes.
+    fn start_runoff(&mut self, end_time: Timestamp) -> VotingResult<Vec<VotingOption>> {
+        let result = self.result.take().ok_or(VotingError::NotFinalized)?;
+        if self.first_round.is_some() {
+            self.result = Some(result);
+            return Err(VotingError::RunoffAlreadyStarted);
+        }
+
+        // A runoff is only needed if no option has an absolute majority.
+        let mut counts: Vec<(VoteIndex, VoteCount)> = self
+            .active_options()
+            .map(|(index, _)| (index, self.tally.get(&index).copied().unwrap_or(0)))
+            .collect();
+        let total: VoteCount = counts.iter().map(|(_, count)| count).sum();
+        if counts.len() < 2 || result.outcome.winner_count * 2 > total {
+            self.result = Some(result);
+            return Err(VotingError::RunoffNotNeeded);
+        }
+        // The sort is stable, so tied options stay in the order they were listed.
+        counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+        let finalists: Vec<VoteIndex> = counts.iter().take(2).map(|(index, _)| *index).collect();
+        for (index, _) in counts.iter().skip(2) {
+            self.removed_options.insert(*index);
+            self.tally.remove(index);
+        }
+        self.first_round = Some(result);
+        self.discard_ballots();
+        self.end_time = end_time;
+
+        Ok(finalists
+            .iter()
+            .map(|index| self.options[*index as usize].code.clone())
+            .collect())
+    }
+
+    /// Delete the ballots and the records of the vote changes, without
+    /// touching the tally.
+    fn clear_ballots(&mut self) {
+        self.ballots.clear_flat();
+        self.ballot_order.clear_flat();
+        self.ballot_positions.clear_flat();
+        self.next_ballot_position = 0;
+        self.contract_ballots.clear_flat();
+        self.receipts.clear_flat();
+        self.encrypted_ballots.clear_flat();
+        self.ballot_weights.clear_flat();
+        self.last_vote_times.clear_flat();
+        self.change_counts.clear_flat();
+        self.count_reached.clear_flat();
+        self.pending_write_ins.clear_flat();
+        self.vote_history.clear_flat();
+    }
+
+    /// Discard all ballots and reset the tally of the voting options to 0, to
+    /// start a new round of voting. The registrations, delegations, and
+    /// deposits are kept.
+    fn discard_ballots(&mut self) {
+        let active: Vec<VoteIndex> = self.active_options().map(|(index, _)| index).collect();
+        for index in active {
+            self.tally.insert(index, 0);
+        }
+        self.ballot_counts.clear_flat();
+        self.clear_ballots();
+        self.voter_count = 0;
+        self.vote_sequence = 0;
+        self.extension_proposal = None;
+        self.tally_proof = None;
+    }
+
+    /// Archive the result of a recurring election and open the next round,
+    /// which starts at the old `end_time` and lasts for the `recurrence`. If
+    /// the election was finalized late, the rounds that have already passed
+    /// are skipped, so the rounds stay aligned to the original schedule.
+    fn start_next_round(
+        &mut self,
+        recurrence: Duration,
+        now: Timestamp,
+        logger: &mut impl HasLogger,
+    ) -> VotingResult<()> {
+        let result = self.result.take().ok_or(VotingError::NotFinalized)?;
+        self.history.insert(self.round, result);
+        self.round += 1;
+        self.discard_ballots();
+
+        // Move all deadlines by the same whole number of periods.
+        let period = recurrence.millis();
+        let elapsed = now
+            .timestamp_millis()
+            .saturating_sub(self.end_time.timestamp_millis());
+        let shift = Duration::from_millis((elapsed / period + 1).saturating_mul(period));
+        let shift_time = |time: Timestamp| {
+            time.checked_add(shift)
+                .unwrap_or(Timestamp::from_timestamp_millis(u64::MAX))
+        };
+        self.start_time = Some(self.end_time);
+        self.end_time = shift_time(self.end_time);
+        self.registration_end_time = shift_time(self.registration_end_time);
+
+        logger.log(&VotingEvent::RoundStarted {
+            round: self.round,
+            end_time: self.end_time,
+        })?;
+        Ok(())
+    }
+
+    /// Apply the pending extension proposal if enough voters have approved
+    /// it.
+    fn try_apply_extension(&mut self, logger: &mut impl HasLogger) -> VotingResult<()> {
+        let approved = match &self.extension_proposal {
+            Some(proposal) => {
+                proposal.approvals.len() as u64 * 100
+                    >= u64::from(self.voter_count) * u64::from(self.extension_approval_percentage)
+            }
+            None => false,
+        };
+        if !approved {
+            return Ok(());
+        }
+        if let Some(proposal) = self.extension_proposal.take() {
+            let old_end_time = self.end_time;
+            self.end_time = proposal.new_end_time;
+            logger.log(&VotingEvent::EndTimeExtended {
+                old_end_time,
+                new_end_time: proposal.new_end_time,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+// Contract functions
+
+/// Initialize the contract instance and start the election.
+/// A description, the vector of all voting options, and an `end_time`
+/// have to be provided.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The `end_time` has already passed.
+/// - There are no voting options, or two voting options have the same code.
+/// - A voting option is neither an ISO 3166-1 alpha-2 country code nor a bloc.
+/// - A bloc is not one of the voting options, its identifier is not longer
+///   than two bytes, or its members are not ISO 3166-1 alpha-2 country codes.
+/// - The verifier threshold is 0 while there are verifier keys, or larger
+///   than the number of verifier keys, or there are more than 256 verifier
+///   keys.
+/// - The `extension_approval_percentage` is 0 or larger than 100.
+/// - The `recurrence` is 0, or the election is recurring and quadratic.
+/// - There are contract voters, and the votes are weighted or encrypted.
+/// - The `max_voters` is 0.
+/// - The votes are weighted in more than one way, or are weighted and
+///   encrypted.
+#[init(
+    contract = "voting",
+    parameter = "InitParameter",
+    event = "VotingEvent",
+    enable_logger,
+    crypto_primitives
+)]
+fn init<S: HasStateApi>(
+    ctx: &impl HasInitContext,
+    state_builder: &mut StateBuilder<S>,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> InitResult<State<S>> {
+    // Parse the parameter.
+    let param: InitParameter = ctx.parameter_cursor().get()?;
+
+    // An election that has already ended could never receive a vote. The
+    // `end_time` itself is inclusive, so it may be the current slot time.
+    if param.end_time < ctx.metadata().slot_time() {
+        return Err(VotingError::EndTimeInPast.into());
+    }
+
+    // Check that there is something to vote for, and that every voting option
+    // can be referred to by its code unambiguously.
+    if param.options.is_empty() {
+        return Err(VotingError::NoOptions.into());
+    }
+    if param.options.len() > MAX_OPTIONS {
+        return Err(VotingError::TooManyOptions.into());
+    }
+    if param.description.len() > MAX_DESCRIPTION_LENGTH {
+        return Err(VotingError::DescriptionTooLong.into());
+    }
+    let mut codes = BTreeSet::new();
+    if !param
+        .options
+        .iter()
+        .all(|option| codes.insert(&option.code))
+    {
+        return Err(VotingError::DuplicateOption.into());
+    }
+    // Every voting option is a country or a bloc of countries.
+    if param
+        .options
+        .iter()
+        .any(|option| !is_country_code(&option.code) && !param.blocs.contains_key(&option.code))
+    {
+        return Err(VotingError::MalformedCountryCode.into());
+    }
+
+    // Check that the blocs are voting options that cannot be confused with
+    // country codes, and that their members are country codes.
+    for (bloc, members) in param.blocs.iter() {
+        if !param.options.iter().any(|option| option.code == *bloc)
+            || bloc.len() <= 2
+            || members.iter().any(|country| !is_country_code(country))
+        {
+            return Err(VotingError::InvalidBloc.into());
+        }
+    }
+
+    // Check that the threshold can be met, and that every verifier key can be
+    // referred to by an `u8` index. Without verifier keys, the threshold must
+    // be 0, in which case the votes are not verified. The verifiers are not
+    // used if the credentials are checked on-chain.
+    if param.credential_registry.is_none()
+        && ((param.verifier_threshold == 0 && !param.verifier_public_keys.is_empty())
+            || usize::from(param.verifier_threshold) > param.verifier_public_keys.len()
+            || param.verifier_public_keys.len() > 256)
+    {
+        return Err(VotingError::InvalidVerifierThreshold.into());
+    }
+
+    // The votes can be weighted by at most one of fixed weights, a token, or
+    // deposits.
+    // An encrypted ballot only holds option indicators, so it cannot carry a
+    // weight either.
+    let weightings = [
+        param.weights.is_some(),
+        param.token_weights.is_some(),
+        param.quadratic_voting,
+        param.tally_authority.is_some(),
+    ];
+    if weightings.iter().filter(|weighted| **weighted).count() > 1
+        || (param.time_weighted && param.tally_authority.is_some())
+    {
+        return Err(VotingError::ConflictingWeights.into());
+    }
+
+    // An election that nobody can vote in is useless.
+    if param.max_voters == Some(0) {
+        return Err(VotingError::InvalidVoterLimit.into());
+    }
+
+    // A contract votes with a weight of 1, and publicly.
+    if !param.contract_voters.is_empty() && (weightings.contains(&true) || param.time_weighted) {
+        return Err(VotingError::InvalidContractVoters.into());
+    }
+
+    // The deposits of a quadratic election can only be withdrawn after the
+    // election has ended for good, which a recurring election never does.
+    if let Some(recurrence) = param.recurrence {
+        if recurrence.millis() == 0 || param.quadratic_voting {
+            return Err(VotingError::InvalidRecurrence.into());
+        }
+    }
+
+    // Without stored ballots, the contract can neither check the previous
+    // votes of a voter nor count the votes.
+    if param.logs_only
+        && (!param.allow_vote_change
+            || param.change_cooldown.is_some()
+            || param.max_changes.is_some()
+            || param.max_votes_per_option.is_some()
+            || param.max_voters.is_some()
+            || param.quorum > 0
+            || param.quadratic_voting
+            || param.tally_authority.is_some()
+            || param.recurrence.is_some()
+            || param.record_vote_history
+            || param.badge_contract.is_some())
+    {
+        return Err(VotingError::InvalidLogsOnly.into());
+    }
+
+    // Without approvals, any voter could extend the election on their own,
+    // and more than all voters can never approve.
+    if !(1..=100).contains(&param.extension_approval_percentage) {
+        return Err(VotingError::InvalidApprovalPercentage.into());
+    }
+
+    // Store the voting weights, if the votes are weighted.
+    let mut weights = state_builder.new_map();
+    for (account, weight) in param.weights.iter().flatten() {
+        weights.insert(*account, *weight);
+    }
+
+    let mut contract_voters = state_builder.new_set();
+    for contract in param.contract_voters {
+        contract_voters.insert(contract);
+    }
+
+    // Every voting option starts without votes.
+    let tally = (0..param.options.len())
+        .map(|index| (index as VoteIndex, 0))
+        .collect();
+
+    // Announce the election to indexers.
+    logger
+        .log(&VotingEvent::ElectionCreated {
+            description_hash: crypto_primitives.hash_sha2_256(param.description.as_bytes()),
+            option_count: param.options.len() as u32,
+            end_time: param.end_time,
+            verifier_keys_hash: crypto_primitives
+                .hash_sha2_256(&to_bytes(&param.verifier_public_keys)),
+        })
+        .map_err(VotingError::from)?;
+
+    // Set the state.
+    Ok(State {
+        admin: ctx.init_origin(),
+        description: param.description,
+        election_id: param.election_id,
+        verifier_public_keys: param.verifier_public_keys,
+        verifier_threshold: param.verifier_threshold,
+        ballots: state_builder.new_map(),
+        ballot_order: state_builder.new_map(),
+        ballot_positions: state_builder.new_map(),
+        next_ballot_position: 0,
+        contract_voters,
+        contract_ballots: state_builder.new_map(),
+        receipts: state_builder.new_map(),
+        eligibility: state_builder.new_map(),
+        nonces: state_builder.new_map(),
+        voter_count: 0,
+        max_voters: param.max_voters,
+        tally,
+        start_time: param.start_time,
+        end_time: param.end_time,
+        registration_end_time: param.registration_end_time,
+        options: param.options,
+        removed_options: BTreeSet::new(),
+        extension_approval_percentage: param.extension_approval_percentage,
+        extension_proposal: None,
+        blocs: param.blocs,
+        min_balance: param.min_balance,
+        allow_vote_change: param.allow_vote_change,
+        change_cooldown: param.change_cooldown,
+        last_vote_times: state_builder.new_map(),
+        max_changes: param.max_changes,
+        change_counts: state_builder.new_map(),
+        max_votes_per_option: param.max_votes_per_option,
+        quorum: param.quorum,
+        tie_breaking: param.tie_breaking,
+        vote_sequence: 0,
+        count_reached: state_builder.new_map(),
+        weighted: param.weights.is_some(),
+        weights,
+        token_weights: param.token_weights,
+        quadratic_voting: param.quadratic_voting,
+        deposits: state_builder.new_map(),
+        total_deposits: Amount::zero(),
+        funds_recipient: param.funds_recipient,
+        retention_period: param.retention_period,
+        expected_voters: param.expected_voters.filter(|expected| *expected > 0),
+        record_vote_history: param.record_vote_history,
+        vote_history: state_builder.new_map(),
+        logs_only: param.logs_only,
+        certification_key: param.certification_key,
+        certified_result: None,
+        badge_contract: param.badge_contract,
+        badge_holders: state_builder.new_set(),
+        time_weighted: param.time_weighted,
+        initialized_at: ctx.metadata().slot_time(),
+        ballot_counts: state_builder.new_map(),
+        statement_hash: param.statement_hash,
+        delegations: state_builder.new_map(),
+        received_weights: state_builder.new_map(),
+        delegator_depths: state_builder.new_map(),
+        ballot_weights: state_builder.new_map(),
+        pending_write_ins: state_builder.new_map(),
+        tally_authority: param.tally_authority,
+        encrypted_ballots: state_builder.new_map(),
+        tally_proof: None,
+        allow_list_root: param.allow_list_root,
+        allow_listed: state_builder.new_set(),
+        blacklist: state_builder.new_set(),
+        credential_registry: param.credential_registry,
+        credentials: state_builder.new_map(),
+        credential_holders: state_builder.new_map(),
+        result: None,
+        first_round: None,
+        recurrence: param.recurrence,
+        round: 0,
+        history: state_builder.new_map(),
+        paused: false,
+        cancelled: false,
+    })
+}
+
+/// Enables accounts to register as eligible for a specific voting option, by
+/// providing signatures from the verifiers. Each registration consumes the
+/// signatures, and an account can register for several voting options.
+///
+/// This "advanced" version requires signatures from the verifiers on the voting option, which is a country code.
+/// It will only register the account if the voting option is present in the list of voting options AND enough signatures are valid, which means that the sender does *not* live in the country.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The voting option does not exist.
+/// - The voting option is neither an ISO 3166-1 alpha-2 country code nor a
+///   bloc.
+/// - A contract tries to register.
+/// - It is past the `end_time` or the `registration_end_time`.
+/// - Fewer than `verifier_threshold` signatures are valid.
+/// - The signatures have expired.
+/// - The signatures do not include the current nonce of the account, e.g.
+///   because they have already been used.
+/// - The election is cancelled.
+/// - The election is closed and the account has not proven that it is on the
+///   allow-list with `proveAllowListed`.
+/// - The eligibility is checked with credentials, which requires
+///   `registerCredential`.
+#[receive(
+    contract = "voting",
+    name = "register",
+    mutable,
+    parameter = "RegisterParameter",
+    error = "VotingError",
+    crypto_primitives
+)]
+fn register<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> VotingResult<()> {
+    // Ensure that the sender is an account.
+    let acc = match ctx.sender() {
+        Address::Account(acc) => acc,
+        Address::Contract(_) => return Err(VotingError::ContractVoter),
+    };
+
+    // Parse the parameter.
+    let registration: RegisterParameter = ctx.parameter_cursor().get()?;
+    register_account(ctx, host, crypto_primitives, acc, registration)
+}
+
+/// Helper function that registers the account `acc` for the voting option of
+/// the `registration`, for `register` and `permit`.
+fn register_account<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+    acc: AccountAddress,
+    registration: RegisterParameter,
+) -> VotingResult<()> {
+    // Check that the election hasn't finished yet, and that registration is still open.
+    if ctx.metadata().slot_time() > host.state().end_time {
+        return Err(VotingError::VotingFinished);
+    }
+    if ctx.metadata().slot_time() > host.state().registration_end_time {
+        return Err(VotingError::RegistrationFinished);
+    }
+
+    // Check that the admin hasn't cancelled the election.
+    if host.state().cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+
+    host.state()
+        .ensure_well_formed(&registration.country_code)?;
+    // Find the vote index in state.options. Or return an error, if it doesn't exist.
+    let vote_index = match host.state().option_index(&registration.country_code) {
+        Some(vote_index) => vote_index,
+        _ => {
+            return Err(VotingError::InvalidVotingOption {
+                option: registration.country_code,
+            })
+        }
+    };
+
+    // Check the signatures and consume them.
+    consume_signatures(ctx, host, crypto_primitives, acc, registration)?;
+
+    // Add the voting option to the options the account is eligible for.
+    host.state_mut().add_eligible_option(acc, vote_index);
+
+    Ok(())
+}
+
+/// Enables accounts to register as eligible for all voting options with a
+/// credential from the CIS-4 registry of the election, instead of signatures
+/// from the verifiers. The holder of the credential signs the
+/// `CredentialMessage` to bind the credential to the account, and each
+/// credential can only be bound to one account. The status of the credential
+/// is checked again on every vote, so revoking the credential also revokes
+/// the eligibility.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The election does not check credentials.
+/// - A contract tries to register.
+/// - It is past the `end_time` or the `registration_end_time`.
+/// - The signature of the holder is invalid.
+/// - The credential is already bound to another account, or the account is
+///   already bound to another credential.
+/// - The credential is not active, or querying its status fails.
+/// - The election is closed and the account has not proven that it is on the
+///   allow-list.
+/// - The election is cancelled.
+#[receive(
+    contract = "voting",
+    name = "registerCredential",
+    mutable,
+    parameter = "CredentialRegistration",
+    error = "VotingError",
+    crypto_primitives
+)]
+fn register_credential<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> VotingResult<()> {
+    if host.state().credential_registry.is_none() {
+        return Err(VotingError::NoCredentialRegistry);
+    }
+
+    // Check that the election hasn't finished yet, and that registration is still open.
+    if ctx.metadata().slot_time() > host.state().end_time {
+        return Err(VotingError::VotingFinished);
+    }
+    if ctx.metadata().slot_time() > host.state().registration_end_time {
+        return Err(VotingError::RegistrationFinished);
+    }
+
+    // Check that the admin hasn't cancelled the election.
+    if host.state().cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+
+    // Ensure that the sender is an account.
+    let acc = match ctx.sender() {
+        Address::Account(acc) => acc,
+        Address::Contract(_) => return Err(VotingError::ContractVoter),
+    };
+    ensure_allow_listed(host, acc)?;
+
+    // Parse the parameter.
+    let registration: CredentialRegistration = ctx.parameter_cursor().get()?;
+
+    // Check that the holder of the credential has bound it to the account.
+    let message = CredentialMessage {
+        contract_address: ctx.self_address(),
+        account: acc,
+    };
+    if !crypto_primitives.verify_ed25519_signature(
+        registration.holder_id,
+        registration.signature,
+        &to_bytes(&message),
+    ) {
+        return Err(VotingError::InvalidSignature {
+            check: SignatureCheck::Message,
+        });
+    }
+    let holder_id = registration.holder_id;
+    let bound_account = host
+        .state()
+        .credential_holders
+        .get(&holder_id)
+        .map(|acc| *acc);
+    let bound_holder = host.state().credentials.get(&acc).map(|holder| *holder);
+    if bound_account.is_some_and(|bound| bound != acc)
+        || bound_holder.is_some_and(|bound| bound != holder_id)
+    {
+        return Err(VotingError::CredentialInUse);
+    }
+
+    // Check that the credential is active.
+    let registry = host.state().credential_registry.unwrap_abort();
+    ensure_credential_active(host, registry, holder_id)?;
+
+    // The credential makes the account eligible for all voting options.
+    let state = host.state_mut();
+    state.credentials.insert(acc, holder_id);
+    state.credential_holders.insert(holder_id, acc);
+    for vote_index in 0..state.options.len() as VoteIndex {
+        state.add_eligible_option(acc, vote_index);
+    }
+
+    Ok(())
+}
+
+/// Helper function that checks that the credential of `holder_id` is active in
+/// the CIS-4 `registry`.
+///
+/// It rejects if the status of the credential is not `Active`, or querying it
+/// fails.
+fn ensure_credential_active<S: HasStateApi>(
+    host: &impl HasHost<State<S>, StateApiType = S>,
+    registry: ContractAddress,
+    holder_id: PublicKeyEd25519,
+) -> VotingResult<()> {
+    let mut return_value = host
+        .invoke_contract_read_only(
+            &registry,
+            &holder_id,
+            EntrypointName::new_unchecked("credentialStatus"),
+            Amount::zero(),
+        )
+        .map_err(|_| VotingError::CredentialQueryFailed)?
+        .ok_or(VotingError::CredentialQueryFailed)?;
+    let status: CredentialStatus = return_value
+        .get()
+        .map_err(|_| VotingError::CredentialQueryFailed)?;
+    if status != CredentialStatus::Active {
+        return Err(VotingError::CredentialNotActive);
+    }
+    Ok(())
+}
+
+/// Helper function that checks that the account `acc` still holds an active
+/// credential, if the eligibility is checked with credentials. Accounts
+/// without a credential are not eligible for any voting option, which the
+/// caller reports.
+fn ensure_voter_credential<S: HasStateApi>(
+    host: &impl HasHost<State<S>, StateApiType = S>,
+    acc: AccountAddress,
+) -> VotingResult<()> {
+    let registry = match host.state().credential_registry {
+        Some(registry) => registry,
+        None => return Ok(()),
+    };
+    let holder_id = match host.state().credentials.get(&acc) {
+        Some(holder_id) => *holder_id,
+        None => return Ok(()),
+    };
+    ensure_credential_active(host, registry, holder_id)
+}
+
+/// Helper function that gets the voting weight of the account `acc`, including
+/// the weight delegated to it.
+///
+/// It rejects if:
+/// - Querying the own voting weight of the account fails.
+/// - The sum of the weights overflows.
+fn voting_weight<S: HasStateApi>(
+    host: &impl HasHost<State<S>, StateApiType = S>,
+    acc: AccountAddress,
+) -> VotingResult<u64> {
+    let own_weight = own_voting_weight(host, acc)?;
+    let received_weight = host
+        .state()
+        .received_weights
+        .get(&acc)
+        .map_or(0, |weight| *weight);
+    own_weight
+        .checked_add(received_weight)
+        .ok_or(VotingError::WeightOverflow)
+}
+
+/// Helper function that gets the own voting weight of the account `acc`, i.e.
+/// without the weight delegated to it. If the votes are weighted by a token, it queries the token balance of the account
+/// from the CIS-2 contract. If the votes are quadratic, it is the square root
+/// of the deposit of the account in microCCD.
+///
+/// It rejects if the votes are weighted by a token and the CIS-2 contract does
+/// not return exactly one token amount that fits in an `u64`.
+fn own_voting_weight<S: HasStateApi>(
+    host: &impl HasHost<State<S>, StateApiType = S>,
+    acc: AccountAddress,
+) -> VotingResult<u64> {
+    if host.state().quadratic_voting {
+        let deposit = host
+            .state()
+            .deposits
+            .get(&acc)
+            .map_or(0, |deposit| deposit.micro_ccd);
+        return Ok(integer_sqrt(deposit));
+    }
+
+    let token_weights = match &host.state().token_weights {
+        Some(token_weights) => token_weights,
+        None => return Ok(host.state().fixed_voting_weight(&acc)),
+    };
+
+    // Query the token balance of the account.
+    let parameter = BalanceOfQueryParams {
+        queries: vec![BalanceOfQuery {
+            token_id: token_weights.token_id.clone(),
+            address: Address::Account(acc),
+        }],
+    };
+    let mut return_value = host
+        .invoke_contract_read_only(
+            &token_weights.contract,
+            &parameter,
+            EntrypointName::new_unchecked("balanceOf"),
+            Amount::zero(),
+        )
+        .map_err(|_| VotingError::TokenBalanceQueryFailed)?
+        .ok_or(VotingError::TokenBalanceQueryFailed)?;
+    let response: BalanceOfQueryResponse = return_value
+        .get()
+        .map_err(|_| VotingError::TokenBalanceQueryFailed)?;
+    match response.0.as_slice() {
+        [amount] => Ok(amount.0),
+        _ => Err(VotingError::TokenBalanceQueryFailed),
+    }
+}
+
+/// Helper function that computes the integer square root of `n`, i.e. the
+/// largest number whose square is at most `n`.
+fn integer_sqrt(n: u64) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+    // Newton's method converges from above, and 2^32 is above the square root
+    // of any `u64`.
+    let mut x: u64 = 1 << 32;
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// Helper function that checks the signatures from the verifiers on a
+/// registration of the account `acc`, and increments the nonce of the account,
+/// so the signatures cannot be submitted again.
+///
+/// It rejects if:
+/// - The eligibility is checked with credentials instead.
+/// - The election is closed and the account has not proven that it is on the
+///   allow-list.
+/// - The signatures have expired.
+/// - Fewer than `verifier_threshold` signatures are valid, e.g. because they do
+///   not include the current nonce of the account.
+fn consume_signatures<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+    acc: AccountAddress,
+    registration: RegisterParameter,
+) -> VotingResult<()> {
+    if host.state().credential_registry.is_some() {
+        return Err(VotingError::CredentialRequired);
+    }
+    ensure_allow_listed(host, acc)?;
+    ensure_not_blacklisted(host, acc)?;
+
+    // Check that the signatures are still valid. The expiry is part of the signed message, so it cannot be changed.
+    if registration.expiry < ctx.metadata().slot_time() {
+        return Err(VotingError::InvalidSignature {
+            check: SignatureCheck::Expiry,
+        });
+    }
+
+    // Look up the nonce of the account, which is 0 before its first registration.
+    let nonce = host.state().nonces.get(&acc).map_or(0, |nonce| *nonce);
+
+    // Construct the message (account_address, contract_address, election_id, expiry, nonce, statement_hash, country_code) and then use it to the check the signatures.
+    // The message includes the address of this instance and the election identifier, so signatures for other elections are rejected.
+    // It also includes the hash of the statement pinned at initialization, so signatures for other statements are rejected.
+    // For a bloc, the `country_code` is the identifier of the bloc, so the signatures must be issued for the bloc.
+    let message_data = SignatureMessageData {
+        account_address: acc,
+        contract_address: ctx.self_address(),
+        election_id: host.state().election_id.clone(),
+        expiry: registration.expiry,
+        nonce,
+        statement_hash: host.state().statement_hash,
+        country_code: registration.country_code,
+    };
+    let message = to_bytes(&message_data);
+    // The signatures are keyed by the index of the verifier, so each verifier is counted at most once.
+    // If too few signatures are valid, the error reports the last check that failed, or the key check if
+    // all the provided signatures are valid.
+    let verifier_public_keys = &host.state().verifier_public_keys;
+    let mut failed_check = SignatureCheck::Key;
+    let valid_signatures = registration
+        .signatures
+        .iter()
+        .filter(|(index, signature)| {
+            let result = match verifier_public_keys.get(usize::from(**index)) {
+                Some(public_key) => {
+                    verify_signature(crypto_primitives, public_key, signature, &message)
+                }
+                None => Err(SignatureCheck::Key),
+            };
+            if let Err(check) = result {
+                failed_check = check;
+            }
+            result.is_ok()
+        })
+        .count();
+    if valid_signatures < usize::from(host.state().verifier_threshold) {
+        return Err(VotingError::InvalidSignature {
+            check: failed_check,
+        });
+    }
+
+    // Increment the nonce, so the signatures cannot be submitted again.
+    host.state_mut().nonces.insert(acc, nonce + 1);
+
+    Ok(())
+}
+
+/// Enables accounts to vote for a specific voting option. Each account can
+/// change its selected voting option with this function as often as it desires
+/// until the `end_time` is reached, if the election allows vote changes.
+///
+/// The account must have registered for the voting option with `register`
+/// first, so only the voting option has to be provided when voting. Changing
+/// the vote to another registered voting option does not require new
+/// signatures from the verifiers.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The voting option does not exist.
+/// - The voting option is neither an ISO 3166-1 alpha-2 country code nor a
+///   bloc.
+/// - A contract that is not one of the `contract_voters` tries to vote.
+/// - It is before the `start_time` or past the `end_time`.
+/// - The account has not registered for the voting option, and the election
+///   has verifiers.
+/// - The account has already voted or written in, and the election does not
+///   allow vote changes.
+/// - The account changes its vote before the `change_cooldown` has passed.
+/// - The account has already changed its vote `max_changes` times.
+/// - The voting option has reached `max_votes_per_option`.
+/// - The votes are weighted and the account has no voting weight.
+/// - The votes are weighted by a token and querying the token balance of the
+///   account fails.
+/// - The balance of the voter is below the minimum balance.
+/// - The election is paused.
+/// - The election is cancelled.
+/// - The account delegates its voting power.
+/// - The votes are quadratic, which requires `voteWithDeposit`.
+/// - The ballots are encrypted, which requires `voteEncrypted`.
+/// - The eligibility is checked with credentials, and the credential of the
+///   account is no longer active.
+/// - CCD are attached. The contract only holds CCD as deposits of a quadratic
+///   election, which are attached to `voteWithDeposit`.
+///
+/// The `end_time` itself is inclusive, i.e. a vote with a slot time equal to
+/// the `end_time` is still accepted.
+#[receive(
+    contract = "voting",
+    name = "vote",
+    mutable,
+    payable,
+    parameter = "VotingOption",
+    return_value = "HashSha2256",
+    error = "VotingError",
+    enable_logger,
+    crypto_primitives
+)]
+fn vote<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    amount: Amount,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> VotingResult<HashSha2256> {
+    // The entrypoint is payable only to reject CCD sent by accident with a
+    // dedicated error, instead of the generic rejection of the host.
+    if amount > Amount::zero() {
+        return Err(VotingError::UnexpectedFunds);
+    }
+    if host.state().quadratic_voting {
+        return Err(VotingError::DepositRequired);
+    }
+    vote_for_option(ctx, host, logger, crypto_primitives)
+}
+
+/// Enables accounts to vote for a specific voting option in a quadratic
+/// election. The attached CCD are added to the deposit of the account, which
+/// the contract holds, and the vote is weighted by the square root of the
+/// total deposit in microCCD. An account that has already deposited can
+/// change its vote without attaching more CCD.
+///
+/// It rejects if:
+/// - The votes are not quadratic.
+/// - The total deposit of the account is zero.
+/// - Any of the reasons that `vote` rejects for, apart from the votes being
+///   quadratic.
+#[receive(
+    contract = "voting",
+    name = "voteWithDeposit",
+    mutable,
+    payable,
+    parameter = "VotingOption",
+    return_value = "HashSha2256",
+    error = "VotingError",
+    enable_logger,
+    crypto_primitives
+)]
+fn vote_with_deposit<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    amount: Amount,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> VotingResult<HashSha2256> {
+    if !host.state().quadratic_voting {
+        return Err(VotingError::DepositsNotAccepted);
+    }
+
+    // Lock the deposit of the account. A contract cannot vote in a quadratic
+    // election, so the transaction is rejected and the CCD returned in that
+    // case.
+    if let Address::Account(acc) = ctx.sender() {
+        let state = host.state_mut();
+        let deposit = state
+            .deposits
+            .get(&acc)
+            .map_or(Amount::zero(), |deposit| *deposit);
+        state.deposits.insert(acc, deposit + amount);
+        state.total_deposits += amount;
+    }
+
+    vote_for_option(ctx, host, logger, crypto_primitives)
+}
+
+/// Helper function that casts the vote of the sender for the voting option in
+/// the parameter, for `vote` and `voteWithDeposit`, and returns the receipt.
+fn vote_for_option<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> VotingResult<HashSha2256> {
+    ensure_voting_open(ctx, host)?;
+
+    // Parse the parameter.
+    let new_vote: VotingOption = ctx.parameter_cursor().get()?;
+
+    // Ensure that the sender is an account, or one of the contract voters.
+    match ctx.sender() {
+        Address::Account(acc) => cast_vote_of(ctx, host, acc, new_vote.clone(), logger)?,
+        Address::Contract(contract) => host
+            .state_mut()
+            .cast_contract_vote(contract, &new_vote, logger)?,
+    }
+
+    Ok(record_receipt(
+        ctx,
+        host,
+        crypto_primitives,
+        ctx.sender(),
+        &new_vote,
+    ))
+}
+
+/// Helper function that computes the receipt of a vote of `voter` for the
+/// voting option `option` in the current block, and stores it as the latest
+/// receipt of the voter.
+///
+/// The receipt is the SHA2-256 hash of the serialized `voter` address,
+/// followed by the bytes of the voting option and the slot time in
+/// milliseconds as 8 little-endian bytes.
+fn record_receipt<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+    voter: Address,
+    option: &VotingOption,
+) -> HashSha2256 {
+    let mut message = to_bytes(&voter);
+    message.extend_from_slice(option.as_bytes());
+    message.extend_from_slice(&ctx.metadata().slot_time().timestamp_millis().to_le_bytes());
+    let receipt = crypto_primitives.hash_sha2_256(&message);
+    let state = host.state_mut();
+    if !state.logs_only {
+        state.receipts.insert(voter, receipt);
+    }
+    receipt
+}
+
+/// Helper function that ensures that the election has started, hasn't
+/// finished yet, and is neither cancelled nor paused.
+fn ensure_voting_open<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> VotingResult<()> {
+    // Check that the election has started and hasn't finished yet. The slot
+    // time is the block time agreed on by the chain, not the clock of the
+    // voter, so there is no clock skew to tolerate.
+    if ctx.metadata().slot_time() > host.state().end_time {
+        return Err(VotingError::VotingFinished);
+    }
+    if let Some(start_time) = host.state().start_time {
+        if ctx.metadata().slot_time() < start_time {
+            return Err(VotingError::VotingNotStarted);
+        }
+    }
+
+    // Check that the admin hasn't cancelled the election or paused voting.
+    if host.state().cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+    if host.state().paused {
+        return Err(VotingError::ElectionPaused);
+    }
+
+    Ok(())
+}
+
+/// Helper function that casts the vote of the account `acc` for the voting
+/// option `new_vote`, once the election is known to be open.
+fn cast_vote_of<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    acc: AccountAddress,
+    new_vote: VotingOption,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    // The votes in an election with encrypted ballots must not be revealed.
+    if host.state().tally_authority.is_some() {
+        return Err(VotingError::EncryptedElection);
+    }
+
+    // The voting power of a delegating account is used by its representative.
+    if host.state().delegations.get(&acc).is_some() {
+        return Err(VotingError::VotesDelegated);
+    }
+
+    ensure_not_blacklisted(host, acc)?;
+    ensure_min_balance(host, acc)?;
+
+    // Check that the account has a voting weight, if the votes are weighted.
+    let weight = voting_weight(host, acc)?;
+    if weight == 0 {
+        return Err(VotingError::NoVotingWeight);
+    }
+
+    // Find the vote index in state.options. Or return an error, if it doesn't exist.
+    host.state().ensure_well_formed(&new_vote)?;
+    let new_vote_index = match host.state().option_index(&new_vote) {
+        Some(vote_index) => vote_index,
+        _ => return Err(VotingError::InvalidVotingOption { option: new_vote }),
+    };
+
+    // Check that the account has registered for the voting option. Without
+    // verifiers, any account can vote for any voting option.
+    if host.state().requires_registration() {
+        let registered = match host.state().eligibility.get(&acc) {
+            Some(eligible_options) => eligible_options.contains(&new_vote_index),
+            None => false,
+        };
+        if !registered {
+            return Err(VotingError::NotRegistered);
+        }
+    } else {
+        ensure_allow_listed(host, acc)?;
+    }
+    ensure_voter_credential(host, acc)?;
+
+    // Check that the account may change its vote, and record the change.
+    let now = ctx.metadata().slot_time();
+    host.state_mut().record_vote_change(acc, now)?;
+
+    // An earlier vote weighs more in a time-weighted election.
+    let weight = host.state().time_weight(weight, now);
+
+    // A vote for a voting option replaces a pending write-in of the account.
+    let state = host.state_mut();
+    state.pending_write_ins.remove(&acc);
+
+    // Insert or replace the vote for the account.
+    state.cast_vote(acc, new_vote_index, weight, logger)?;
+
+    if state.record_vote_history {
+        state
+            .vote_history
+            .entry(acc)
+            .or_insert_with(Vec::new)
+            .modify(|history| {
+                history.push(VoteRecord {
+                    timestamp: now,
+                    option: new_vote,
+                })
+            });
+    }
+
+    mint_badge(host, acc)
+}
+
+/// Helper function that mints an "I voted" badge to the account `acc` with the
+/// `badge_contract`, if it is configured and the account has not received a
+/// badge yet.
+///
+/// It rejects if minting the badge fails.
+fn mint_badge<S: HasStateApi>(
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    acc: AccountAddress,
+) -> VotingResult<()> {
+    let badge_contract = match host.state().badge_contract {
+        Some(badge_contract) => badge_contract,
+        None => return Ok(()),
+    };
+    if !host.state_mut().badge_holders.insert(acc) {
+        return Ok(());
+    }
+
+    host.invoke_contract(
+        &badge_contract,
+        &MintBadgeParams {
+            owner: Address::Account(acc),
+        },
+        EntrypointName::new_unchecked("mint"),
+        Amount::zero(),
+    )
+    .map_err(|_| VotingError::BadgeMintFailed)?;
+    Ok(())
+}
+
+/// Check the signatures of an account on a message, which are on the SHA2-256
+/// hash of the account address, 8 zero bytes, and the serialized message, like
+/// messages signed with `signMessage` in the wallets.
+///
+/// It rejects if the signatures are invalid, or the account does not exist.
+fn check_account_message_signature<S: HasStateApi>(
+    host: &impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+    account: AccountAddress,
+    signatures: &AccountSignatures,
+    message: &impl Serial,
+) -> VotingResult<()> {
+    let mut signed_bytes = to_bytes(&account);
+    signed_bytes.extend_from_slice(&[0; 8]);
+    signed_bytes.extend_from_slice(&to_bytes(message));
+    let message_hash = crypto_primitives.hash_sha2_256(&signed_bytes);
+    let valid = host
+        .check_account_signature(account, signatures, &message_hash.0)
+        .map_err(|_| VotingError::InvalidVoterSignature)?;
+    if !valid {
+        return Err(VotingError::InvalidVoterSignature);
+    }
+    Ok(())
+}
+
+/// Enables anyone, e.g. a relayer paying for the transaction, to submit a vote
+/// on behalf of a voter. The voter signs the `VoteForMessage` with the keys of
+/// its account, like messages signed with `signMessage` in the wallets, i.e.
+/// the signature is on the SHA2-256 hash of the account address, 8 zero
+/// bytes, and the serialized message. The vote is attributed to the voter.
+/// Like `register`, the vote includes signatures from the verifiers, so the
+/// voter is registered for the voting option and votes for it in one go.
+///
+/// The signature of the voter includes its nonce, which is the same nonce the
+/// verifiers sign and which is incremented when the vote is accepted, so the
+/// vote cannot be replayed.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The signature of the voter is invalid, or the voter account does not
+///   exist.
+/// - The votes are quadratic, which requires the voter to deposit CCD with
+///   `voteWithDeposit`.
+/// - It is past the `registration_end_time`.
+/// - Any of the reasons that `register` or `vote` reject for.
+#[receive(
+    contract = "voting",
+    name = "voteFor",
+    mutable,
+    parameter = "VoteForParameter",
+    return_value = "HashSha2256",
+    error = "VotingError",
+    enable_logger,
+    crypto_primitives
+)]
+fn vote_for<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> VotingResult<HashSha2256> {
+    if host.state().quadratic_voting {
+        return Err(VotingError::DepositRequired);
+    }
+    ensure_voting_open(ctx, host)?;
+    if ctx.metadata().slot_time() > host.state().registration_end_time {
+        return Err(VotingError::RegistrationFinished);
+    }
+
+    // Parse the parameter.
+    let param: VoteForParameter = ctx.parameter_cursor().get()?;
+    let voter = param.voter;
+
+    // Check the signature of the voter on the message (contract_address,
+    // nonce, expiry, country_code).
+    let nonce = host.state().nonces.get(&voter).map_or(0, |nonce| *nonce);
+    let message = VoteForMessage {
+        contract_address: ctx.self_address(),
+        nonce,
+        expiry: param.vote.expiry,
+        country_code: param.vote.country_code.clone(),
+    };
+    check_account_message_signature(
+        host,
+        crypto_primitives,
+        voter,
+        &param.voter_signature,
+        &message,
+    )?;
+
+    // Register the voter for the voting option. This consumes the nonce, so
+    // neither the signatures from the verifiers nor the signature of the voter
+    // can be submitted again.
+    let new_vote = param.vote.country_code.clone();
+    host.state().ensure_well_formed(&new_vote)?;
+    let vote_index =
+        host.state()
+            .option_index(&new_vote)
+            .ok_or_else(|| VotingError::InvalidVotingOption {
+                option: new_vote.clone(),
+            })?;
+    consume_signatures(ctx, host, crypto_primitives, voter, param.vote)?;
+    host.state_mut().add_eligible_option(voter, vote_index);
+
+    cast_vote_of(ctx, host, voter, new_vote.clone(), logger)?;
+    Ok(record_receipt(
+        ctx,
+        host,
+        crypto_primitives,
+        Address::Account(voter),
+        &new_vote,
+    ))
+}
+
+/// Enables anyone, e.g. a sponsor paying for the transaction, to invoke
+/// `register` or `vote` on behalf of an account, following the CIS-3
+/// standard. The account signs the `PermitMessage` with the keys of its
+/// account, like messages signed with `signMessage` in the wallets, i.e. the
+/// signature is on the SHA2-256 hash of the account address, 8 zero bytes,
+/// and the serialized message.
+///
+/// The payload of `register` is a `RegisterParameter`, whose signatures from
+/// the verifiers must include the same nonce as the permit, and the payload of
+/// `vote` is a `VotingOption`. A permitted vote increments the nonce, while a
+/// permitted registration consumes it like `register`, so the permit cannot
+/// be replayed.
+///
+/// It rejects if:
+/// - It fails to parse the parameter or the payload.
+/// - The message is for another contract instance.
+/// - The message has expired.
+/// - The message does not include the current nonce of the signer.
+/// - The signature is invalid, or the signer account does not exist.
+/// - The entrypoint cannot be invoked with `permit`.
+/// - The votes are quadratic, which requires the voter to deposit CCD with
+///   `voteWithDeposit`.
+/// - Any of the reasons that `register` or `vote` reject for.
+#[receive(
+    contract = "voting",
+    name = "permit",
+    mutable,
+    parameter = "PermitParam",
+    error = "VotingError",
+    enable_logger,
+    crypto_primitives
+)]
+fn permit<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> VotingResult<()> {
+    // Parse the parameter.
+    let param: PermitParam = ctx.parameter_cursor().get()?;
+    let signer = param.signer;
+    let message = param.message;
+
+    if message.contract_address != ctx.self_address() {
+        return Err(VotingError::WrongContract);
+    }
+    if message.timestamp < ctx.metadata().slot_time() {
+        return Err(VotingError::PermitExpired);
+    }
+    let nonce = host.state().nonces.get(&signer).map_or(0, |nonce| *nonce);
+    if message.nonce != nonce {
+        return Err(VotingError::NonceMismatch);
+    }
+
+    // Check the signature of the signer on the message.
+    check_account_message_signature(host, crypto_primitives, signer, &param.signature, &message)?;
+
+    // Invoke the entrypoint on behalf of the signer.
+    if message.entry_point == "register" {
+        let registration: RegisterParameter = from_bytes(&message.payload)?;
+        // The registration consumes the nonce together with the signatures from the verifiers.
+        register_account(ctx, host, crypto_primitives, signer, registration)?;
+    } else if message.entry_point == "vote" {
+        let new_vote: VotingOption = from_bytes(&message.payload)?;
+        if host.state().quadratic_voting {
+            return Err(VotingError::DepositRequired);
+        }
+        ensure_voting_open(ctx, host)?;
+        host.state_mut().nonces.insert(signer, nonce + 1);
+        cast_vote_of(ctx, host, signer, new_vote.clone(), logger)?;
+        record_receipt(
+            ctx,
+            host,
+            crypto_primitives,
+            Address::Account(signer),
+            &new_vote,
+        );
+    } else {
+        return Err(VotingError::UnsupportedPermit);
+    }
+
+    logger.log(&VotingEvent::Nonce(NonceEvent {
+        nonce,
+        sponsoree: signer,
+    }))?;
+    Ok(())
+}
+
+/// Check which entrypoints can be invoked with `permit`, as defined by the
+/// CIS-3 standard.
+///
+/// It rejects if it fails to parse the parameter.
+#[receive(
+    contract = "voting",
+    name = "supportsPermit",
+    parameter = "SupportsPermitQueryParams",
+    return_value = "SupportsQueryResponse"
+)]
+fn supports_permit<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<SupportsQueryResponse> {
+    // Parse the parameter.
+    let param: SupportsPermitQueryParams = ctx.parameter_cursor().get()?;
+
+    let results = param
+        .queries
+        .iter()
+        .map(|entrypoint| {
+            if PERMIT_ENTRYPOINTS.iter().any(|name| entrypoint == name) {
+                SupportResult::Support
+            } else {
+                SupportResult::NoSupport
+            }
+        })
+        .collect();
+    Ok(SupportsQueryResponse { results })
+}
+
+/// Helper function that checks that the voter `acc` holds the minimum balance,
+/// if required.
+fn ensure_min_balance<S: HasStateApi>(
+    host: &impl HasHost<State<S>, StateApiType = S>,
+    acc: AccountAddress,
+) -> VotingResult<()> {
+    if let Some(min_balance) = host.state().min_balance {
+        // The voter is either the sender of the transaction or has signed the
+        // vote, so it is always an existing account.
+        let balance = host.account_balance(acc).unwrap_abort();
+        if balance.total < min_balance {
+            return Err(VotingError::InsufficientStake);
+        }
+    }
+    Ok(())
+}
+
+/// Helper function that checks that the account `acc` has proven that it is
+/// on the allow-list, if the election is closed.
+fn ensure_allow_listed<S: HasStateApi>(
+    host: &impl HasHost<State<S>, StateApiType = S>,
+    acc: AccountAddress,
+) -> VotingResult<()> {
+    if host.state().allow_list_root.is_some() && !host.state().allow_listed.contains(&acc) {
+        return Err(VotingError::NotAllowListed);
+    }
+    Ok(())
+}
+
+/// Helper function that checks that the admin has not blacklisted the account
+/// `acc`.
+fn ensure_not_blacklisted<S: HasStateApi>(
+    host: &impl HasHost<State<S>, StateApiType = S>,
+    acc: AccountAddress,
+) -> VotingResult<()> {
+    if host.state().blacklist.contains(&acc) {
+        return Err(VotingError::Blacklisted);
+    }
+    Ok(())
+}
+
+/// Enables anyone to prove that an account is on the allow-list of a closed
+/// election, with a Merkle proof of its membership in the tree whose root was
+/// provided at initialization. The proof is checked once, after which the
+/// account can register and vote like in an open election. The allow-list is
+/// public, so the proof does not have to be submitted by the account itself,
+/// e.g. a relayer can submit it for a voter that votes with `voteFor`.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The election has no allow-list.
+/// - The proof is invalid.
+/// - It is past the `end_time`.
+/// - The election is cancelled.
+#[receive(
+    contract = "voting",
+    name = "proveAllowListed",
+    mutable,
+    parameter = "AllowListProof",
+    error = "VotingError",
+    crypto_primitives
+)]
+fn prove_allow_listed<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> VotingResult<()> {
+    let root = host
+        .state()
+        .allow_list_root
+        .ok_or(VotingError::NoAllowList)?;
+
+    // Check that the election hasn't finished yet.
+    if ctx.metadata().slot_time() > host.state().end_time {
+        return Err(VotingError::VotingFinished);
+    }
+
+    // Check that the admin hasn't cancelled the election.
+    if host.state().cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+
+    // Parse the parameter.
+    let param: AllowListProof = ctx.parameter_cursor().get()?;
+    if !verify_merkle_proof(crypto_primitives, root, param.account, &param.proof) {
+        return Err(VotingError::InvalidMerkleProof);
+    }
+
+    host.state_mut().allow_listed.insert(param.account);
+
+    Ok(())
+}
+
+/// Enables accounts to cast an encrypted ballot in an election with encrypted
+/// ballots. The ballot contains one ElGamal ciphertext per voting option, in
+/// the order of their vote indices including removed options. Each ciphertext
+/// encrypts an indicator under the key of the tally authority, which is 1 for
+/// the voted-for option and 0 for the others. A new ballot replaces the
+/// previous ballot of the account.
+///
+/// The contract cannot see which voting option a ballot is for. It only checks
+/// that the account has registered for a voting option, and the tally
+/// authority is trusted to only count ballots that encrypt a single 1 for a
+/// voting option the voter has registered for.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The ballots are not encrypted.
+/// - The ballot does not contain one ciphertext per voting option.
+/// - A contract tries to vote.
+/// - It is before the `start_time` or past the `end_time`.
+/// - The account has not registered for any voting option.
+/// - The account has already voted, and the election does not allow vote
+///   changes.
+/// - The account changes its vote before the `change_cooldown` has passed.
+/// - The account has already changed its vote `max_changes` times.
+/// - The balance of the voter is below the minimum balance.
+/// - The election is paused.
+/// - The election is cancelled.
+/// - The eligibility is checked with credentials, and the credential of the
+///   account is no longer active.
+#[receive(
+    contract = "voting",
+    name = "voteEncrypted",
+    mutable,
+    parameter = "Vec<ElGamalCiphertext>",
+    error = "VotingError",
+    enable_logger
+)]
+fn vote_encrypted<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    if host.state().tally_authority.is_none() {
+        return Err(VotingError::NotEncryptedElection);
+    }
+    ensure_voting_open(ctx, host)?;
+
+    // Ensure that the sender is an account.
+    let acc = match ctx.sender() {
+        Address::Account(acc) => acc,
+        Address::Contract(_) => return Err(VotingError::ContractVoter),
+    };
+
+    ensure_not_blacklisted(host, acc)?;
+    ensure_min_balance(host, acc)?;
+
+    // Parse the parameter.
+    let ballot: Vec<ElGamalCiphertext> = ctx.parameter_cursor().get()?;
+    if ballot.len() != host.state().options.len() {
+        return Err(VotingError::InvalidBallot);
+    }
+
+    // Check that the account has registered for a voting option, unless the
+    // election has no verifiers.
+    if host.state().requires_registration() {
+        let registered = match host.state().eligibility.get(&acc) {
+            Some(eligible_options) => !eligible_options.is_empty(),
+            None => false,
+        };
+        if !registered {
+            return Err(VotingError::NotRegistered);
+        }
+    } else {
+        ensure_allow_listed(host, acc)?;
+    }
+    ensure_voter_credential(host, acc)?;
+
+    // Check that the account may change its vote, and record the change.
+    let now = ctx.metadata().slot_time();
+    let state = host.state_mut();
+    state.record_vote_change(acc, now)?;
+
+    // Insert or replace the ballot of the account.
+    if state.encrypted_ballots.insert(acc, ballot).is_none() {
+        state.count_new_voter()?;
+    }
+    logger.log(&VotingEvent::EncryptedVote { voter: acc })?;
+
+    Ok(())
+}
+
+/// Enables accounts to vote for a country that is not a voting option, i.e. a
+/// write-in. Like `register`, it requires signatures from the verifiers that
+/// the account does *not* live in the country. The vote is pending until the
+/// admin approves the write-in with `approveWriteIn`. A new write-in replaces
+/// the pending write-in of the account.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The country is already a voting option, or is not an ISO 3166-1 alpha-2
+///   country code.
+/// - A contract tries to vote.
+/// - It is past the `end_time` or the `registration_end_time`.
+/// - The account has already voted or written in, and the election does not
+///   allow vote changes.
+/// - The account changes its vote before the `change_cooldown` has passed.
+/// - The account has already changed its vote `max_changes` times.
+/// - Fewer than `verifier_threshold` signatures are valid.
+/// - The signatures have expired.
+/// - The signatures do not include the current nonce of the account.
+/// - The votes are weighted and the account has no voting weight.
+/// - The election is paused.
+/// - The election is cancelled.
+/// - The ballots are encrypted.
+/// - The election is closed and the account has not proven that it is on the
+///   allow-list.
+#[receive(
+    contract = "voting",
+    name = "voteWriteIn",
+    mutable,
+    parameter = "RegisterParameter",
+    error = "VotingError",
+    enable_logger,
+    crypto_primitives
+)]
+fn vote_write_in<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> VotingResult<()> {
+    // A write-in reveals the vote, so it cannot be cast with encrypted ballots.
+    if host.state().tally_authority.is_some() {
+        return Err(VotingError::EncryptedElection);
+    }
+
+    // Check that the election hasn't finished yet, and that registration is still open.
+    if ctx.metadata().slot_time() > host.state().end_time {
+        return Err(VotingError::VotingFinished);
+    }
+    if ctx.metadata().slot_time() > host.state().registration_end_time {
+        return Err(VotingError::RegistrationFinished);
+    }
+
+    // Check that the admin hasn't cancelled the election or paused voting.
+    if host.state().cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+    if host.state().paused {
+        return Err(VotingError::ElectionPaused);
+    }
+
+    // Ensure that the sender is an account.
+    let acc = match ctx.sender() {
+        Address::Account(acc) => acc,
+        Address::Contract(_) => return Err(VotingError::ContractVoter),
+    };
+
+    // The voting power of a delegating account is used by its representative.
+    if host.state().delegations.get(&acc).is_some() {
+        return Err(VotingError::VotesDelegated);
+    }
+
+    // Check that the account has a voting weight, if the votes are weighted.
+    if voting_weight(host, acc)? == 0 {
+        return Err(VotingError::NoVotingWeight);
+    }
+
+    // Parse the parameter.
+    let write_in: RegisterParameter = ctx.parameter_cursor().get()?;
+    // Voting options must be voted for with `vote`, and only countries can be written in.
+    if host.state().option_index(&write_in.country_code).is_some() {
+        return Err(VotingError::DuplicateOption);
+    }
+    if !is_country_code(&write_in.country_code) {
+        return Err(VotingError::MalformedCountryCode);
+    }
+    let option = write_in.country_code.clone();
+
+    // Check that the account may change its vote, and record the change.
+    let now = ctx.metadata().slot_time();
+    host.state_mut().record_vote_change(acc, now)?;
+
+    // Check the signatures and consume them.
+    consume_signatures(ctx, host, crypto_primitives, acc, write_in)?;
+
+    host.state_mut()
+        .pending_write_ins
+        .insert(acc, option.clone());
+    logger.log(&VotingEvent::WriteInSubmitted { voter: acc, option })?;
+
+    Ok(())
+}
+
+/// Enables accounts to withdraw their ballot entirely before the `end_time`.
+/// The vote is removed from the tally, and the account no longer counts as a
+/// voter, so also its approval of a pending extension proposal is withdrawn.
+/// The account can vote again later.
+///
+/// It rejects if:
+/// - A contract tries to retract a vote.
+/// - It is past the `end_time`.
+/// - The account has not voted.
+/// - The election does not allow vote changes.
+/// - The election is paused.
+/// - The election is cancelled.
+/// - The ballots are encrypted. An encrypted ballot can only be replaced.
+#[receive(
+    contract = "voting",
+    name = "retractVote",
+    mutable,
+    error = "VotingError",
+    enable_logger
+)]
+fn retract_vote<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    if host.state().tally_authority.is_some() {
+        return Err(VotingError::EncryptedElection);
+    }
+
+    // Check that the election hasn't finished yet.
+    if ctx.metadata().slot_time() > host.state().end_time {
+        return Err(VotingError::VotingFinished);
+    }
+
+    // Check that the admin hasn't cancelled the election or paused voting.
+    if host.state().cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+    if host.state().paused {
+        return Err(VotingError::ElectionPaused);
+    }
+
+    // Ensure that the sender is an account.
+    let acc = match ctx.sender() {
+        Address::Account(acc) => acc,
+        Address::Contract(_) => return Err(VotingError::ContractVoter),
+    };
+
+    // Retracting and voting again would circumvent a one-shot election.
+    if !host.state().allow_vote_change {
+        return Err(VotingError::VoteChangeNotAllowed);
+    }
+
+    let state = host.state_mut();
+    if !state.remove_ballot(acc, logger)? {
+        return Err(VotingError::NotAVoter);
+    }
+    // With fewer voters, the pending extension proposal may now be approved.
+    state.try_apply_extension(logger)
+}
+
+/// Enables accounts to withdraw their deposit of CCD from a quadratic election
+/// after the `end_time`, or once the election has been cancelled. The deposit
+/// is transferred back to the account, and its vote remains counted.
+///
+/// It rejects if:
+/// - A contract tries to withdraw.
+/// - It is not past the `end_time` and the election is not cancelled.
+/// - The account has no deposit.
+#[receive(
+    contract = "voting",
+    name = "withdraw",
+    mutable,
+    error = "VotingError",
+    enable_logger
+)]
+fn withdraw<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    // Ensure that the sender is an account.
+    let acc = match ctx.sender() {
+        Address::Account(acc) => acc,
+        Address::Contract(_) => return Err(VotingError::ContractVoter),
+    };
+
+    // The deposits are locked while the election is running.
+    if ctx.metadata().slot_time() <= host.state().end_time && !host.state().cancelled {
+        return Err(VotingError::VotingNotFinished);
+    }
+
+    let state = host.state_mut();
+    let amount = state
+        .deposits
+        .remove_and_get(&acc)
+        .ok_or(VotingError::NothingToWithdraw)?;
+    state.total_deposits -= amount;
+
+    // The contract holds all deposits, and the sender of a transaction is always
+    // an existing account, so the transfer cannot fail.
+    host.invoke_transfer(&acc, amount).unwrap_abort();
+    logger.log(&VotingEvent::DepositWithdrawn {
+        account: acc,
+        amount,
+    })?;
+
+    Ok(())
+}
+
+/// Enables accounts to delegate their voting power to another account. The
+/// weight of the account, including the weight delegated to it, is added to
+/// every account in the delegation chain, and to the vote of the
+/// representative at the end of the chain if it has voted. A pending write-in
+/// of the account is withdrawn.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - A contract tries to delegate.
+/// - It is past the `end_time`.
+/// - The account has voted, or already delegates its voting power.
+/// - The delegation would form a cycle, e.g. an account delegating to itself.
+/// - The delegation would create a chain of more than `MAX_DELEGATION_DEPTH`
+///   delegations.
+/// - The vote of the representative would exceed `max_votes_per_option`.
+/// - The delegated voting power would overflow.
+/// - The votes are weighted by a token and querying the token balance of the
+///   account fails.
+/// - The election is paused.
+/// - The election is cancelled.
+/// - The ballots are encrypted, which cannot carry delegated voting power.
+/// - The votes are time-weighted, so the delegated voting power would depend
+///   on the time of the delegation.
+/// - The election is closed and the account has not proven that it is on the
+///   allow-list.
+#[receive(
+    contract = "voting",
+    name = "delegate",
+    mutable,
+    parameter = "AccountAddress",
+    error = "VotingError",
+    enable_logger
+)]
+fn delegate<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    if host.state().tally_authority.is_some() {
+        return Err(VotingError::EncryptedElection);
+    }
+    if host.state().time_weighted {
+        return Err(VotingError::TimeWeightedElection);
+    }
+
+    // Check that the election hasn't finished yet.
+    if ctx.metadata().slot_time() > host.state().end_time {
+        return Err(VotingError::VotingFinished);
+    }
+
+    // Check that the admin hasn't cancelled the election or paused voting.
+    if host.state().cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+    if host.state().paused {
+        return Err(VotingError::ElectionPaused);
+    }
+
+    // Ensure that the sender is an account.
+    let acc = match ctx.sender() {
+        Address::Account(acc) => acc,
+        Address::Contract(_) => return Err(VotingError::ContractVoter),
+    };
+
+    // Parse the parameter.
+    let delegate: AccountAddress = ctx.parameter_cursor().get()?;
+
+    // Only accounts that could vote themselves have voting power to delegate,
+    // and only to accounts that may vote.
+    ensure_allow_listed(host, acc)?;
+    ensure_not_blacklisted(host, acc)?;
+    ensure_not_blacklisted(host, delegate)?;
+    if host.state().delegations.get(&acc).is_some() {
+        return Err(VotingError::VotesDelegated);
+    }
+    // The vote has to be retracted first, so it is not counted twice.
+    if host.state().ballots.get(&acc).is_some() {
+        return Err(VotingError::AlreadyVoted);
+    }
+    let delegation_chain = host.state().delegation_chain(delegate);
+    if delegation_chain.contains(&acc) {
+        return Err(VotingError::DelegationCycle);
+    }
+    // The longest chain through the new delegation consists of the chain
+    // ending at the account, the delegation itself, and the chain of the
+    // delegate.
+    let depth = host.state().delegation_depth(acc);
+    if depth + delegation_chain.len() as u32 > MAX_DELEGATION_DEPTH {
+        return Err(VotingError::DelegationTooDeep);
+    }
+
+    // Snapshot the own voting weight of the account, and pass it on together
+    // with the weight delegated to the account.
+    let own_weight = own_voting_weight(host, acc)?;
+    let state = host.state_mut();
+    let received_weight = state.received_weights.get(&acc).map_or(0, |weight| *weight);
+    let weight = own_weight
+        .checked_add(received_weight)
+        .ok_or(VotingError::WeightOverflow)?;
+    state.add_delegated_weight(delegate, weight)?;
+    state.delegations.insert(
+        acc,
+        Delegation {
+            delegate,
+            weight: own_weight,
+        },
+    );
+    state.update_delegation_depths(delegate, None, Some(depth + 1));
+    state.pending_write_ins.remove(&acc);
+
+    logger.log(&VotingEvent::Delegated {
+        delegator: acc,
+        delegate,
+    })?;
+
+    Ok(())
+}
+
+/// Enables accounts to take back the voting power they have delegated. The
+/// weight is removed from every account in the delegation chain, and from the
+/// vote of the representative. Afterwards, the account can vote itself.
+///
+/// It rejects if:
+/// - A contract tries to undelegate.
+/// - It is past the `end_time`.
+/// - The account does not delegate its voting power.
+/// - The election is paused.
+/// - The election is cancelled.
+#[receive(
+    contract = "voting",
+    name = "undelegate",
+    mutable,
+    error = "VotingError",
+    enable_logger
+)]
+fn undelegate<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    // Check that the election hasn't finished yet.
+    if ctx.metadata().slot_time() > host.state().end_time {
+        return Err(VotingError::VotingFinished);
+    }
+
+    // Check that the admin hasn't cancelled the election or paused voting.
+    if host.state().cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+    if host.state().paused {
+        return Err(VotingError::ElectionPaused);
+    }
+
+    // Ensure that the sender is an account.
+    let acc = match ctx.sender() {
+        Address::Account(acc) => acc,
+        Address::Contract(_) => return Err(VotingError::ContractVoter),
+    };
+
+    let delegate = host
+        .state_mut()
+        .remove_delegation(acc)
+        .ok_or(VotingError::NotDelegated)?;
+
+    logger.log(&VotingEvent::Undelegated {
+        delegator: acc,
+        delegate,
+    })?;
+
+    Ok(())
+}
+
+/// Helper function that checks a signature from a verifier on a message. The
+/// signature must use the same scheme as the public key of the verifier.
+/// Returns the check that failed if the signature is invalid.
+fn verify_signature(
+    crypto_primitives: &impl HasCryptoPrimitives,
+    public_key: &VerifierPublicKey,
+    signature: &VerifierSignature,
+    message: &[u8],
+) -> Result<(), SignatureCheck> {
+    let valid = match (public_key, signature) {
+        (VerifierPublicKey::Ed25519(public_key), VerifierSignature::Ed25519(signature)) => {
+            crypto_primitives.verify_ed25519_signature(*public_key, *signature, message)
+        }
+        (
+            VerifierPublicKey::EcdsaSecp256k1(public_key),
+            VerifierSignature::EcdsaSecp256k1(signature),
+        ) => {
+            // ECDSA signatures are created on the hash of the message.
+            let message_hash = crypto_primitives.hash_sha2_256(message).0;
+            crypto_primitives.verify_ecdsa_secp256k1_signature(
+                *public_key,
+                *signature,
+                message_hash,
+            )
+        }
+        // The signature is made with another scheme than the key.
+        _ => return Err(SignatureCheck::Key),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(SignatureCheck::Message)
+    }
+}
+
+/// Helper function that checks a Merkle proof that the account `acc` is a leaf
+/// of the tree with the given `root`. A leaf is the hash of `0x00` followed by
+/// the account address, and an inner node is the hash of `0x01` followed by
+/// its two children in ascending order. The prefixes prevent passing off an
+/// inner node as a leaf, and sorting the children means the proof does not
+/// have to say on which side each sibling is.
+fn verify_merkle_proof(
+    crypto_primitives: &impl HasCryptoPrimitives,
+    root: MerkleNode,
+    acc: AccountAddress,
+    proof: &[MerkleNode],
+) -> bool {
+    let mut leaf = vec![0u8];
+    leaf.extend_from_slice(&acc.0);
+    let mut node = crypto_primitives.hash_sha2_256(&leaf).0;
+    for sibling in proof {
+        let (left, right) = if node <= *sibling {
+            (node, *sibling)
+        } else {
+            (*sibling, node)
+        };
+        let mut inner = vec![1u8];
+        inner.extend_from_slice(&left);
+        inner.extend_from_slice(&right);
+        node = crypto_primitives.hash_sha2_256(&inner).0;
+    }
+    node == root
+}
+
+/// Get the election information.
+#[receive(contract = "voting", name = "view", return_value = "VotingView")]
+fn view<'a, S: HasStateApi + 'a>(
+    ctx: &impl HasReceiveContext,
+    host: &'a impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<VotingViewRef<'a>> {
+    // Return the election information, borrowing the strings from the state.
+    let state = host.state();
+    Ok(VotingViewRef {
+        description: &state.description,
+        election_id: &state.election_id,
+        start_time: state.start_time,
+        end_time: state.end_time,
+        registration_end_time: state.registration_end_time,
+        tally: state.current_tally_ref(),
+        status: state.status(ctx.metadata().slot_time()),
+        certified_result: state.certified_result.as_ref(),
+        ballot_counts: state.ballot_counts(),
+    })
+}
+
+/// Get the number of votes of a single voting option, which is cheaper than
+/// `view` for frontends that only track one option. Like `view`, it serves
+/// the frozen tally once the election is finalized.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The voting option does not exist.
+#[receive(
+    contract = "voting",
+    name = "getTallyForOption",
+    parameter = "VotingOption",
+    return_value = "VoteCount",
+    error = "VotingError"
+)]
+fn get_tally_for_option<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> VotingResult<VoteCount> {
+    // Parse the parameter.
+    let option: VotingOption = ctx.parameter_cursor().get()?;
+
+    let state = host.state();
+    let count = match &state.result {
+        Some(result) => result.tally.get(&option).copied(),
+        None => state
+            .option_index(&option)
+            .map(|index| state.tally.get(&index).copied().unwrap_or(0)),
+    };
+    count.ok_or(VotingError::InvalidVotingOption { option })
+}
+
+/// Get the information about the election that does not depend on the votes,
+/// which is cheaper than `view` for frontends that do not show the tally.
+#[receive(
+    contract = "voting",
+    name = "viewMetadata",
+    return_value = "MetadataView"
+)]
+fn view_metadata<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<MetadataView> {
+    let state = host.state();
+    Ok(MetadataView {
+        description: state.description.clone(),
+        end_time: state.end_time,
+        status: state.status(ctx.metadata().slot_time()),
+        options: state
+            .active_options()
+            .map(|(_, option)| option.clone())
+            .collect(),
+    })
+}
+
+/// Get the tally of the votes, i.e. the `tally` of `view` without the other
+/// information about the election. Once the election is finalized, it serves
+/// the frozen tally.
+#[receive(
+    contract = "voting",
+    name = "viewTally",
+    return_value = "BTreeMap<VotingOption, VoteCount>"
+)]
+fn view_tally<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<BTreeMap<VotingOption, VoteCount>> {
+    Ok(host.state().current_tally())
+}
+
+/// Get the account that can call the admin entrypoints, which reflects any
+/// transfer with `transferAdmin`.
+#[receive(
+    contract = "voting",
+    name = "viewAdmin",
+    return_value = "AccountAddress"
+)]
+fn view_admin<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<AccountAddress> {
+    Ok(host.state().admin)
+}
+
+/// Get the voting option that an account has voted for, or `None` if the
+/// account has not voted.
+#[receive(
+    contract = "voting",
+    name = "getVote",
+    parameter = "AccountAddress",
+    return_value = "Option<VotingOption>"
+)]
+fn get_vote<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<Option<VotingOption>> {
+    // Parse the parameter.
+    let account: AccountAddress = ctx.parameter_cursor().get()?;
+
+    // Look up the ballot of the account.
+    Ok(host.state().vote_of(&account))
+}
+
+/// Get the voting options that a list of accounts have voted for, in the same
+/// order as the accounts, with `None` for the accounts that have not voted.
+/// Auditors can use it to look up the votes of many accounts in one invoke
+/// instead of calling `getVote` for each of them.
+#[receive(
+    contract = "voting",
+    name = "getVotes",
+    parameter = "Vec<AccountAddress>",
+    return_value = "Vec<Option<VotingOption>>"
+)]
+fn get_votes<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<Vec<Option<VotingOption>>> {
+    // Parse the parameter.
+    let accounts: Vec<AccountAddress> = ctx.parameter_cursor().get()?;
+
+    // Look up the ballots of the accounts.
+    let state = host.state();
+    Ok(accounts
+        .iter()
+        .map(|account| state.vote_of(account))
+        .collect())
+}
+
+/// Check whether a receipt is the receipt of the latest vote of a voter, i.e.
+/// whether the ballot it was issued for is the one recorded for the voter.
+/// Voters can use it to prove to third parties that their ballot was
+/// recorded.
+///
+/// It rejects if it fails to parse the parameter.
+#[receive(
+    contract = "voting",
+    name = "verifyReceipt",
+    parameter = "VerifyReceiptParameter",
+    return_value = "bool"
+)]
+fn verify_receipt<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<bool> {
+    // Parse the parameter.
+    let param: VerifyReceiptParameter = ctx.parameter_cursor().get()?;
+
+    Ok(host
+        .state()
+        .receipts
+        .get(&param.voter)
+        .is_some_and(|receipt| *receipt == param.receipt))
+}
+
+/// Check whether an account has voted.
+#[receive(
+    contract = "voting",
+    name = "hasVoted",
+    parameter = "AccountAddress",
+    return_value = "bool"
+)]
+fn has_voted<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<bool> {
+    // Parse the parameter.
+    let account: AccountAddress = ctx.parameter_cursor().get()?;
+
+    Ok(host.state().has_ballot(&account))
+}
+
+/// Get the voting options together with their indices.
+#[receive(
+    contract = "voting",
+    name = "viewOptions",
+    return_value = "Vec<(VoteIndex, VotingOption)>"
+)]
+fn view_options<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<Vec<(VoteIndex, VotingOption)>> {
+    Ok(host
+        .state()
+        .active_options()
+        .map(|(index, option)| (index, option.clone()))
+        .collect())
+}
+
+/// Get the metadata of the voting options, in the order they were listed.
+#[receive(
+    contract = "voting",
+    name = "viewOptionInfo",
+    return_value = "Vec<OptionInfo>"
+)]
+fn view_option_info<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<Vec<OptionInfo>> {
+    let state = host.state();
+    Ok(state
+        .options
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !state.removed_options.contains(&(*index as VoteIndex)))
+        .map(|(_, option)| option.clone())
+        .collect())
+}
+
+/// Get a page of the ballots, i.e. the voters together with the voting option
+/// they voted for.
+///
+/// Auditors can enumerate all ballots by requesting consecutive pages until a
+/// page with fewer than `limit` ballots is returned.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "voting",
+    name = "viewBallots",
+    parameter = "ViewBallotsParameter",
+    return_value = "Vec<(AccountAddress, VotingOption)>"
+)]
+fn view_ballots<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<Vec<(AccountAddress, VotingOption)>> {
+    // Parse the parameter.
+    let param: ViewBallotsParameter = ctx.parameter_cursor().get()?;
+
+    let state = host.state();
+    Ok(state
+        .ballots
+        .iter()
+        .skip(param.skip as usize)
+        .take(param.limit as usize)
+        .map(|(voter, vote_index)| (*voter, state.options[*vote_index as usize].code.clone()))
+        .collect())
+}
+
+/// Get a page of the accounts that currently vote for a voting option.
+///
+/// Auditors can enumerate all voters of the option by passing the last voter
+/// of a page as the `cursor` of the next, until a page with fewer than
+/// `limit` voters is returned. The ballots are filtered by the voting option
+/// from the start, so the energy used grows with the number of ballots before
+/// the `cursor`.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The voting option does not exist.
+#[receive(
+    contract = "voting",
+    name = "viewVotersForOption",
+    parameter = "ViewVotersForOptionParameter",
+    return_value = "Vec<AccountAddress>",
+    error = "VotingError"
+)]
+fn view_voters_for_option<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> VotingResult<Vec<AccountAddress>> {
+    // Parse the parameter.
+    let param: ViewVotersForOptionParameter = ctx.parameter_cursor().get()?;
+
+    let state = host.state();
+    let option_index =
+        state
+            .option_index(&param.option)
+            .ok_or_else(|| VotingError::InvalidVotingOption {
+                option: param.option.clone(),
+            })?;
+    // The ballots are ordered by the account address of the voter.
+    Ok(state
+        .ballots
+        .iter()
+        .filter(|(voter, vote_index)| {
+            **vote_index == option_index && param.cursor.is_none_or(|cursor| **voter > cursor)
+        })
+        .take(param.limit as usize)
+        .map(|(voter, _)| *voter)
+        .collect())
+}
+
+/// Tally a page of at most `limit` ballots, so the tally of a very large
+/// election can be recomputed across several invocations without hitting the
+/// energy limit. Summing the tallies of all pages gives the tally that the
+/// contract maintains. The votes of contract voters are tallied in the first
+/// page, since their number is fixed at initialization.
+///
+/// The ballots are read by their position in the order that the voters first
+/// voted, so the energy used depends on the `limit`, but not on the `cursor`.
+/// The position of a removed ballot is left empty, so a page can contain
+/// fewer than `limit` ballots even if it is not the last one.
+///
+/// It rejects if it fails to parse the parameter.
+#[receive(
+    contract = "voting",
+    name = "viewTallyPartial",
+    parameter = "ViewTallyPartialParameter",
+    return_value = "PartialTallyView"
+)]
+fn view_tally_partial<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<PartialTallyView> {
+    // Parse the parameter.
+    let param: ViewTallyPartialParameter = ctx.parameter_cursor().get()?;
+
+    let state = host.state();
+    let mut tally: BTreeMap<VotingOption, VoteCount> = BTreeMap::new();
+    let mut count_votes = |vote_index: VoteIndex, weight: VoteCount| {
+        let option = state.options[vote_index as usize].code.clone();
+        *tally.entry(option).or_insert(0) += weight;
+    };
+    if param.cursor.is_none() {
+        for (_, vote_index) in state.contract_ballots.iter() {
+            count_votes(*vote_index, 1);
+        }
+    }
+
+    // Read the ballots at the positions of the page.
+    let start = param.cursor.unwrap_or(0);
+    let end = start
+        .saturating_add(u64::from(param.limit))
+        .min(state.next_ballot_position);
+    for position in start..end {
+        // The position of a removed ballot is empty.
+        let voter = match state.ballot_order.get(&position) {
+            Some(voter) => *voter,
+            None => continue,
+        };
+        if let Some(vote_index) = state.ballots.get(&voter).map(|index| *index) {
+            let weight = state.ballot_weights.get(&voter).map_or(0, |weight| *weight);
+            count_votes(vote_index, weight);
+        }
+    }
+
+    let cursor = (end < state.next_ballot_position).then_some(end);
+    Ok(PartialTallyView { tally, cursor })
+}
+
+/// Get a page of the encrypted ballots, i.e. the voters together with their
+/// ciphertexts, so auditors can check the tally posted by the tally authority.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+#[receive(
+    contract = "voting",
+    name = "viewEncryptedBallots",
+    parameter = "ViewBallotsParameter",
+    return_value = "Vec<(AccountAddress, Vec<ElGamalCiphertext>)>"
+)]
+fn view_encrypted_ballots<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<Vec<(AccountAddress, Vec<ElGamalCiphertext>)>> {
+    // Parse the parameter.
+    let param: ViewBallotsParameter = ctx.parameter_cursor().get()?;
+
+    Ok(host
+        .state()
+        .encrypted_ballots
+        .iter()
+        .skip(param.skip as usize)
+        .take(param.limit as usize)
+        .map(|(voter, ballot)| (*voter, ballot.clone()))
+        .collect())
+}
+
+/// Get the tally authority, including the key that the ballots must be
+/// encrypted with, or `None` if the ballots are not encrypted.
+#[receive(
+    contract = "voting",
+    name = "viewTallyAuthority",
+    return_value = "Option<TallyAuthority>"
+)]
+fn view_tally_authority<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<Option<TallyAuthority>> {
+    Ok(host.state().tally_authority.clone())
+}
+
+/// Get the public keys of the verifiers that the election trusts, together
+/// with the number of them that must sign a registration. The keys reflect any
+/// rotation with `rotateVerifierKey`.
+#[receive(
+    contract = "voting",
+    name = "getVerifierKey",
+    return_value = "VerifierKeysView"
+)]
+fn get_verifier_key<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<VerifierKeysView> {
+    let state = host.state();
+    Ok(VerifierKeysView {
+        keys: state.verifier_public_keys.clone(),
+        threshold: state.verifier_threshold,
+    })
+}
+
+/// Get the artifact that the tally authority posted together with the tally,
+/// or `None` if no tally has been posted.
+#[receive(
+    contract = "voting",
+    name = "viewTallyProof",
+    return_value = "Option<Vec<u8>>"
+)]
+fn view_tally_proof<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<Option<Vec<u8>>> {
+    Ok(host.state().tally_proof.clone())
+}
+
+/// Get the number of distinct accounts that have voted.
+#[receive(contract = "voting", name = "voterCount", return_value = "u32")]
+fn voter_count<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<u32> {
+    Ok(host.state().voter_count)
+}
+
+/// Get the number of voters that have voted, together with the turnout
+/// relative to the expected number of voters, if it was provided at
+/// initialization.
+#[receive(
+    contract = "voting",
+    name = "viewTurnout",
+    return_value = "TurnoutView"
+)]
+fn view_turnout<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<TurnoutView> {
+    let state = host.state();
+    Ok(TurnoutView {
+        votes_cast: state.voter_count,
+        expected_voters: state.expected_voters,
+        permille: state
+            .expected_voters
+            .map(|expected| u64::from(state.voter_count) * 1000 / u64::from(expected)),
+    })
+}
+
+/// Get the votes of an account in the order they were cast, if the election
+/// records the vote history. The list is empty if the account has not voted,
+/// or if the history is not recorded.
+///
+/// It rejects if it fails to parse the parameter.
+#[receive(
+    contract = "voting",
+    name = "viewVoteHistory",
+    parameter = "AccountAddress",
+    return_value = "Vec<VoteRecord>"
+)]
+fn view_vote_history<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<Vec<VoteRecord>> {
+    // Parse the parameter.
+    let acc: AccountAddress = ctx.parameter_cursor().get()?;
+
+    Ok(host
+        .state()
+        .vote_history
+        .get(&acc)
+        .map(|history| history.clone())
+        .unwrap_or_default())
+}
+
+/// Get the nonce that the signatures for the next registration of an account
+/// must include. Verifiers sign the nonce, so frontends look it up before requesting
+/// a signature.
+#[receive(
+    contract = "voting",
+    name = "nonce",
+    parameter = "AccountAddress",
+    return_value = "u64"
+)]
+fn nonce<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<u64> {
+    // Parse the parameter.
+    let account: AccountAddress = ctx.parameter_cursor().get()?;
+
+    Ok(host.state().nonces.get(&account).map_or(0, |nonce| *nonce))
+}
+
+/// Finalize the election, which freezes the tally and determines the winner.
+/// Anyone can finalize the election once the `end_time` has passed.
+///
+/// It rejects if:
+/// - It is not past the `end_time`.
+/// - The election is already finalized.
+/// - The election is cancelled.
+/// - The ballots are encrypted, so only the tally authority can finalize the
+///   election with `postTally`.
+/// - The votes are only logged, so the contract has no tally to finalize.
+#[receive(
+    contract = "voting",
+    name = "finalize",
+    mutable,
+    error = "VotingError",
+    enable_logger
+)]
+fn finalize<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    // Check that the election has finished.
+    if ctx.metadata().slot_time() <= host.state().end_time {
+        return Err(VotingError::VotingNotFinished);
+    }
+
+    let state = host.state_mut();
+    if state.cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+    if state.result.is_some() {
+        return Err(VotingError::AlreadyFinalized);
+    }
+    if state.tally_authority.is_some() {
+        return Err(VotingError::EncryptedElection);
+    }
+    if state.logs_only {
+        return Err(VotingError::TallyNotStored);
+    }
+
+    state.store_result(ctx.metadata().slot_time(), logger)
+}
+
+/// Post the tally of an election with encrypted ballots, which the tally
+/// authority has decrypted off-chain, together with an artifact that lets
+/// auditors verify it. This finalizes the election like `finalize`.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The ballots are not encrypted.
+/// - The sender is not the tally authority.
+/// - It is not past the `end_time`.
+/// - The election is already finalized.
+/// - The election is cancelled.
+/// - The tally does not have one count per voting option, has votes for a
+///   removed voting option, or counts more votes than there are voters.
+#[receive(
+    contract = "voting",
+    name = "postTally",
+    mutable,
+    parameter = "PostTallyParameter",
+    error = "VotingError",
+    enable_logger
+)]
+fn post_tally<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    let tally_authority = host
+        .state()
+        .tally_authority
+        .as_ref()
+        .ok_or(VotingError::NotEncryptedElection)?;
+    if !ctx.sender().matches_account(&tally_authority.account) {
+        return Err(VotingError::NotTallyAuthority);
+    }
+
+    // Check that the election has finished.
+    if ctx.metadata().slot_time() <= host.state().end_time {
+        return Err(VotingError::VotingNotFinished);
+    }
+
+    // Parse the parameter.
+    let param: PostTallyParameter = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    if state.cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+    if state.result.is_some() {
+        return Err(VotingError::AlreadyFinalized);
+    }
+
+    // Every voter has voted for exactly one voting option, which has not been removed.
+    if param.tally.len() != state.options.len() {
+        return Err(VotingError::InvalidTally);
+    }
+    let mut total: VoteCount = 0;
+    for (index, count) in param.tally.iter().enumerate() {
+        if *count > 0 && state.removed_options.contains(&(index as VoteIndex)) {
+            return Err(VotingError::InvalidTally);
+        }
+        total = total.checked_add(*count).ok_or(VotingError::InvalidTally)?;
+    }
+    if total > u64::from(state.voter_count) {
+        return Err(VotingError::InvalidTally);
+    }
+
+    for (index, count) in param.tally.into_iter().enumerate() {
+        if !state.removed_options.contains(&(index as VoteIndex)) {
+            state.tally.insert(index as VoteIndex, count);
+        }
+    }
+    state.tally_proof = Some(param.proof);
+
+    state.store_result(ctx.metadata().slot_time(), logger)
+}
+
+/// Get the winner of the election, including the voting options that tied for
+/// the highest number of votes.
+///
+/// It rejects if:
+/// - The election has not been finalized.
+#[receive(
+    contract = "voting",
+    name = "getResult",
+    return_value = "ElectionOutcome",
+    error = "VotingError"
+)]
+fn get_result<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> VotingResult<ElectionOutcome> {
+    host.state()
+        .result
+        .as_ref()
+        .map(|result| result.outcome.clone())
+        .ok_or(VotingError::NotFinalized)
+}
+
+/// Start a runoff between the two voting options with the most votes, if no
+/// voting option has received more than half of the votes. The runoff is a
+/// second voting window that ends at the given timestamp, in which only the
+/// two options can be voted for. The ballots of the first round are
+/// discarded, but the registrations are kept, so accounts can vote for the
+/// options they have registered for without new signatures from the
+/// verifiers. The result of the first round can be seen with `viewFirstRound`.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender is not the admin.
+/// - The election is cancelled.
+/// - The election has not been finalized.
+/// - A runoff has already been started.
+/// - A voting option has received more than half of the votes, or there are
+///   fewer than two voting options.
+/// - The new `end_time` has already passed.
+#[receive(
+    contract = "voting",
+    name = "startRunoff",
+    mutable,
+    parameter = "Timestamp",
+    error = "VotingError",
+    enable_logger
+)]
+fn start_runoff<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    // Parse the parameter.
+    let end_time: Timestamp = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    if state.cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+    if end_time < ctx.metadata().slot_time() {
+        return Err(VotingError::EndTimeInPast);
+    }
+
+    let options = state.start_runoff(end_time)?;
+    logger.log(&VotingEvent::RunoffStarted { options, end_time })?;
+
+    Ok(())
+}
+
+/// Get the result of a finalized round of a recurring election, or `None` if
+/// the round has not been finalized yet. Rounds are numbered from 0.
+#[receive(
+    contract = "voting",
+    name = "viewHistory",
+    parameter = "u32",
+    return_value = "Option<ElectionResult>"
+)]
+fn view_history<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<Option<ElectionResult>> {
+    // Parse the parameter.
+    let round: u32 = ctx.parameter_cursor().get()?;
+
+    Ok(host
+        .state()
+        .history
+        .get(&round)
+        .map(|result| result.clone()))
+}
+
+/// Get the result of the first round, if a runoff has been started.
+#[receive(
+    contract = "voting",
+    name = "viewFirstRound",
+    return_value = "Option<ElectionResult>"
+)]
+fn view_first_round<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<Option<ElectionResult>> {
+    Ok(host.state().first_round.clone())
+}
+
+/// Helper function that gets the sender of a request to extend the election,
+/// ensuring that the election is still running and that the sender has voted.
+fn extension_voter<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> VotingResult<AccountAddress> {
+    // Check that the election hasn't finished yet.
+    if ctx.metadata().slot_time() > host.state().end_time {
+        return Err(VotingError::VotingFinished);
+    }
+    if host.state().cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+
+    // Ensure that the sender is an account.
+    let acc = match ctx.sender() {
+        Address::Account(acc) => acc,
+        Address::Contract(_) => return Err(VotingError::ContractVoter),
+    };
+
+    // Only voters can take part in extending the election.
+    if !host.state().has_ballot(&acc) {
+        return Err(VotingError::NotAVoter);
+    }
+
+    Ok(acc)
+}
+
+/// Propose to extend the `end_time` of the election to the given timestamp.
+/// The proposer automatically approves the proposal, and the extension is
+/// applied right away if that is already enough approvals.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - A contract tries to propose.
+/// - It is past the `end_time`.
+/// - The sender has not voted.
+/// - Another proposal is pending.
+/// - The proposed `end_time` is not later than the current `end_time`.
+#[receive(
+    contract = "voting",
+    name = "proposeExtension",
+    mutable,
+    parameter = "Timestamp",
+    error = "VotingError",
+    enable_logger
+)]
+fn propose_extension<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    let acc = extension_voter(ctx, host)?;
+
+    // Parse the parameter.
+    let new_end_time: Timestamp = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    if state.extension_proposal.is_some() {
+        return Err(VotingError::ExtensionAlreadyProposed);
+    }
+    if new_end_time <= state.end_time {
+        return Err(VotingError::InvalidExtension);
+    }
+
+    // Store the proposal with the approval of the proposer.
+    state.extension_proposal = Some(ExtensionProposal {
+        proposer: acc,
+        new_end_time,
+        approvals: BTreeSet::from([acc]),
+    });
+    logger.log(&VotingEvent::ExtensionProposed {
+        proposer: acc,
+        new_end_time,
+    })?;
+
+    state.try_apply_extension(logger)
+}
+
+/// Approve the pending proposal to extend the `end_time`. The extension is
+/// applied as soon as enough voters have approved it.
+///
+/// It rejects if:
+/// - A contract tries to approve.
+/// - It is past the `end_time`.
+/// - The sender has not voted.
+/// - No extension is proposed.
+/// - The sender has already approved the proposal.
+#[receive(
+    contract = "voting",
+    name = "voteOnExtension",
+    mutable,
+    error = "VotingError",
+    enable_logger
+)]
+fn vote_on_extension<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    let acc = extension_voter(ctx, host)?;
+
+    let state = host.state_mut();
+    let proposal = state
+        .extension_proposal
+        .as_mut()
+        .ok_or(VotingError::NoExtensionProposed)?;
+    if !proposal.approvals.insert(acc) {
+        return Err(VotingError::ExtensionAlreadyApproved);
+    }
+    logger.log(&VotingEvent::ExtensionApproved { voter: acc })?;
+
+    state.try_apply_extension(logger)
+}
+
+/// Get the pending proposal to extend the `end_time`, if any.
+#[receive(
+    contract = "voting",
+    name = "viewExtensionProposal",
+    return_value = "Option<ExtensionProposal>"
+)]
+fn view_extension_proposal<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<Option<ExtensionProposal>> {
+    Ok(host.state().extension_proposal.clone())
+}
+
+/// Get the member countries of the voting options that represent a bloc.
+#[receive(
+    contract = "voting",
+    name = "viewBlocs",
+    return_value = "BTreeMap<VotingOption, Vec<VotingOption>>"
+)]
+fn view_blocs<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<BTreeMap<VotingOption, Vec<VotingOption>>> {
+    Ok(host.state().blocs.clone())
+}
+
+/// Helper function that ensures that the sender is the admin of the election.
+fn ensure_admin<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> VotingResult<()> {
+    if !ctx.sender().matches_account(&host.state().admin) {
+        return Err(VotingError::NotAdmin);
+    }
+    Ok(())
+}
+
+/// Replace the code of the contract with the `module`, keeping the state and
+/// the balance. If `migrate` is provided, the entrypoint is called on the
+/// upgraded contract in the same transaction, and the upgrade is rolled back
+/// if it rejects.
+///
+/// The entrypoint is low-level, so the state is not written back after the
+/// migration has changed it.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender is not the admin.
+/// - The module does not exist, does not contain the contract, or has an
+///   unsupported version.
+/// - The migration rejects.
+#[receive(
+    contract = "voting",
+    name = "upgrade",
+    parameter = "UpgradeParameter",
+    error = "VotingError",
+    low_level
+)]
+fn upgrade<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<S, StateApiType = S>,
+) -> VotingResult<()> {
+    // Ensure that the sender is the admin. The state is read directly, as the
+    // entrypoint is low-level.
+    let state: State<S> = host.state().read_root()?;
+    if !ctx.sender().matches_account(&state.admin) {
+        return Err(VotingError::NotAdmin);
+    }
+
+    // Parse the parameter.
+    let param: UpgradeParameter = ctx.parameter_cursor().get()?;
+
+    host.upgrade(param.module)
+        .map_err(|_| VotingError::UpgradeFailed)?;
+    if let Some((entrypoint, parameter)) = param.migrate {
+        host.invoke_contract_raw(
+            &ctx.self_address(),
+            parameter.as_parameter(),
+            entrypoint.as_entrypoint_name(),
+            Amount::zero(),
+        )
+        .map_err(|_| VotingError::MigrationFailed)?;
+    }
+
+    Ok(())
+}
+
+/// Enables the admin to exclude an account from the election, e.g. when a
+/// verifier signature for it turns out to have been issued on a fraudulent
+/// proof. The ballot of the account is removed from the tally and its
+/// delegation is withdrawn.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender is not the admin.
+/// - The election has been finalized.
+/// - The account is already blacklisted.
+#[receive(
+    contract = "voting",
+    name = "blacklist",
+    mutable,
+    parameter = "AccountAddress",
+    error = "VotingError",
+    enable_logger
+)]
+fn blacklist<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    // Parse the parameter.
+    let account: AccountAddress = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    if state.result.is_some() {
+        return Err(VotingError::AlreadyFinalized);
+    }
+    if !state.blacklist.insert(account) {
+        return Err(VotingError::AlreadyBlacklisted);
+    }
+    logger.log(&VotingEvent::Blacklisted { account })?;
+
+    // Remove the ballot, which cannot be read if it is encrypted.
+    state.remove_ballot(account, logger)?;
+    if state.encrypted_ballots.remove_and_get(&account).is_some() {
+        state.voter_count -= 1;
+    }
+    state.pending_write_ins.remove(&account);
+    if let Some(delegate) = state.remove_delegation(account) {
+        logger.log(&VotingEvent::Undelegated {
+            delegator: account,
+            delegate,
+        })?;
+    }
+
+    // With fewer voters, the pending extension proposal may now be approved.
+    state.try_apply_extension(logger)
+}
+
+/// Enables the admin to lift the exclusion of a blacklisted account. The
+/// account keeps its registrations, so it can vote again right away.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender is not the admin.
+/// - The account is not blacklisted.
+#[receive(
+    contract = "voting",
+    name = "unblacklist",
+    mutable,
+    parameter = "AccountAddress",
+    error = "VotingError",
+    enable_logger
+)]
+fn unblacklist<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    // Parse the parameter.
+    let account: AccountAddress = ctx.parameter_cursor().get()?;
+
+    if !host.state_mut().blacklist.remove(&account) {
+        return Err(VotingError::NotBlacklisted);
+    }
+    logger.log(&VotingEvent::Unblacklisted { account })?;
+
+    Ok(())
+}
+
+/// Enables the admin to transfer the CCD on the contract that are not
+/// deposits of the voters to the `funds_recipient`, or to the admin if it is
+/// not set, so that they are not stranded.
+///
+/// It rejects if:
+/// - The sender is not the admin.
+/// - The contract holds no CCD beyond the deposits.
+/// - The balance of the contract is below the total of the deposits.
+/// - The transfer fails, e.g. because the `funds_recipient` does not exist.
+#[receive(
+    contract = "voting",
+    name = "withdrawFunds",
+    mutable,
+    error = "VotingError",
+    enable_logger
+)]
+fn withdraw_funds<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    let state = host.state();
+    let recipient = state.funds_recipient.unwrap_or(state.admin);
+    let amount = host
+        .self_balance()
+        .checked_sub(state.total_deposits)
+        .ok_or(VotingError::BalanceBelowDeposits)?;
+    if amount == Amount::zero() {
+        return Err(VotingError::NothingToWithdraw);
+    }
+
+    host.invoke_transfer(&recipient, amount)
+        .map_err(|_| VotingError::TransferFailed)?;
+    logger.log(&VotingEvent::FundsWithdrawn { recipient, amount })?;
+
+    Ok(())
+}
+
+/// Enables the admin to delete the ballots of a long finished election, to
+/// shrink the state of the instance. The frozen tally and the result are
+/// kept.
+///
+/// It rejects if:
+/// - The sender is not the admin.
+/// - The election is neither finalized nor cancelled.
+/// - The election has no `retention_period`, or it has not passed since the
+///   `end_time`.
+#[receive(
+    contract = "voting",
+    name = "cleanup",
+    mutable,
+    error = "VotingError",
+    enable_logger
+)]
+fn cleanup<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    let state = host.state_mut();
+    if state.result.is_none() && !state.cancelled {
+        return Err(VotingError::NotFinalized);
+    }
+    // A retention period that overflows the timestamp never passes.
+    let retention_end = state
+        .retention_period
+        .and_then(|retention_period| state.end_time.checked_add(retention_period));
+    match retention_end {
+        Some(retention_end) if ctx.metadata().slot_time() > retention_end => {}
+        _ => return Err(VotingError::CleanupNotAllowed),
+    }
+
+    state.clear_ballots();
+    logger.log(&VotingEvent::BallotsCleared)?;
+
+    Ok(())
+}
+
+/// Enables the admin to post the official result of a finalized election,
+/// certified with a signature of the election authority. The certified result
+/// is kept next to the result computed by the contract, and `view` returns
+/// both, so any discrepancy between them is visible on-chain.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender is not the admin.
+/// - The election has no `certification_key`.
+/// - The election is not finalized.
+/// - The result is already certified.
+/// - The signature of the election authority is invalid.
+#[receive(
+    contract = "voting",
+    name = "certifyResult",
+    mutable,
+    parameter = "CertifiedResult",
+    error = "VotingError",
+    enable_logger,
+    crypto_primitives
+)]
+fn certify_result<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    // Parse the parameter.
+    let certified: CertifiedResult = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    let certification_key = state.certification_key.ok_or(VotingError::NotCertifiable)?;
+    let computed_tally = match &state.result {
+        Some(result) => &result.tally,
+        None => return Err(VotingError::NotFinalized),
+    };
+    if state.certified_result.is_some() {
+        return Err(VotingError::AlreadyCertified);
+    }
+
+    // Check that the election authority has certified the tally.
+    let message = CertificationMessage {
+        contract_address: ctx.self_address(),
+        tally: certified.tally.clone(),
+    };
+    if !crypto_primitives.verify_ed25519_signature(
+        certification_key,
+        certified.signature,
+        &to_bytes(&message),
+    ) {
+        return Err(VotingError::InvalidSignature {
+            check: SignatureCheck::Message,
+        });
+    }
+
+    let matches_computed = certified.tally == *computed_tally;
+    state.certified_result = Some(certified);
+    logger.log(&VotingEvent::ResultCertified { matches_computed })?;
+
+    Ok(())
+}
+
+/// Transfer the admin role to another account.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender is not the admin.
+#[receive(
+    contract = "voting",
+    name = "transferAdmin",
+    mutable,
+    parameter = "AccountAddress",
+    error = "VotingError",
+    enable_logger
+)]
+fn transfer_admin<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    // Parse the parameter.
+    let new_admin: AccountAddress = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    let old_admin = state.admin;
+    state.admin = new_admin;
+    logger.log(&VotingEvent::AdminTransferred {
+        old_admin,
+        new_admin,
+    })?;
+
+    Ok(())
+}
+
+/// Extend the `end_time` of the election to the given timestamp.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender is not the admin.
+/// - The election is already finalized.
+/// - The election is cancelled.
+/// - The new `end_time` is not later than the current `end_time`.
+#[receive(
+    contract = "voting",
+    name = "extendEndTime",
+    mutable,
+    parameter = "Timestamp",
+    error = "VotingError",
+    enable_logger
+)]
+fn extend_end_time<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    // Parse the parameter.
+    let new_end_time: Timestamp = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    if state.cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+    if state.result.is_some() {
+        return Err(VotingError::AlreadyFinalized);
+    }
+    // The election can only be extended, never shortened.
+    if new_end_time <= state.end_time {
+        return Err(VotingError::InvalidExtension);
+    }
+
+    let old_end_time = state.end_time;
+    state.end_time = new_end_time;
+    logger.log(&VotingEvent::EndTimeExtended {
+        old_end_time,
+        new_end_time,
+    })?;
+
+    Ok(())
+}
+
+/// Pause voting until the admin calls `unpause`.
+///
+/// It rejects if:
+/// - The sender is not the admin.
+#[receive(
+    contract = "voting",
+    name = "pause",
+    mutable,
+    error = "VotingError",
+    enable_logger
+)]
+fn pause<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    host.state_mut().paused = true;
+    logger.log(&VotingEvent::Paused)?;
+
+    Ok(())
+}
+
+/// Resume voting after it was paused.
+///
+/// It rejects if:
+/// - The sender is not the admin.
+#[receive(
+    contract = "voting",
+    name = "unpause",
+    mutable,
+    error = "VotingError",
+    enable_logger
+)]
+fn unpause<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    host.state_mut().paused = false;
+    logger.log(&VotingEvent::Unpaused)?;
+
+    Ok(())
+}
+
+/// Cancel the election, which voids it for good. No votes can be cast, and the
+/// election cannot be finalized anymore.
+///
+/// It rejects if:
+/// - The sender is not the admin.
+/// - The election is already finalized.
+/// - The election is already cancelled.
+#[receive(
+    contract = "voting",
+    name = "cancel",
+    mutable,
+    error = "VotingError",
+    enable_logger
+)]
+fn cancel<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    let state = host.state_mut();
+    if state.cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+    if state.result.is_some() {
+        return Err(VotingError::AlreadyFinalized);
+    }
+
+    state.cancelled = true;
+    logger.log(&VotingEvent::Cancelled)?;
+
+    Ok(())
+}
+
+/// Add a voting option to the election. This is only possible before the
+/// first vote is cast, so all voters can choose from the same options.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender is not the admin.
+/// - The election is cancelled.
+/// - Votes have already been cast.
+/// - The voting option already exists.
+/// - The voting option is neither a country code nor a bloc.
+/// - The election already has `MAX_OPTIONS` voting options.
+#[receive(
+    contract = "voting",
+    name = "addOption",
+    mutable,
+    parameter = "OptionInfo",
+    error = "VotingError",
+    enable_logger
+)]
+fn add_option<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    // Parse the parameter.
+    let option: OptionInfo = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    if state.cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+    if state.voter_count > 0 {
+        return Err(VotingError::VotesAlreadyCast);
+    }
+    state.ensure_well_formed(&option.code)?;
+    if state.option_index(&option.code).is_some() {
+        return Err(VotingError::DuplicateOption);
+    }
+    if state.options.len() >= MAX_OPTIONS {
+        return Err(VotingError::TooManyOptions);
+    }
+
+    // The new option starts without votes.
+    state.tally.insert(state.options.len() as VoteIndex, 0);
+    let code = option.code.clone();
+    state.options.push(option);
+    logger.log(&VotingEvent::OptionAdded { option: code })?;
+
+    Ok(())
+}
+
+/// Remove a voting option from the election. The option keeps its index, so
+/// the indices of the remaining options stay valid. If the option represents
+/// a bloc, the bloc is removed as well.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender is not the admin.
+/// - The election is cancelled or finalized.
+/// - The voting option does not exist.
+/// - The voting option has votes.
+#[receive(
+    contract = "voting",
+    name = "removeOption",
+    mutable,
+    parameter = "VotingOption",
+    error = "VotingError",
+    enable_logger
+)]
+fn remove_option<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    // Parse the parameter.
+    let option: VotingOption = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    if state.cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+    if state.result.is_some() {
+        return Err(VotingError::AlreadyFinalized);
+    }
+    let index = state
+        .option_index(&option)
+        .ok_or_else(|| VotingError::InvalidVotingOption {
+            option: option.clone(),
+        })?;
+    // Removing an option with votes would invalidate the ballots.
+    if state.tally.get(&index).copied().unwrap_or(0) > 0 {
+        return Err(VotingError::OptionHasVotes);
+    }
+
+    state.removed_options.insert(index);
+    state.tally.remove(&index);
+    state.blocs.remove(&option);
+    logger.log(&VotingEvent::OptionRemoved { option })?;
+
+    Ok(())
+}
+
+/// Replace the description of the election.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender is not the admin.
+/// - The description is longer than `MAX_DESCRIPTION_LENGTH` bytes.
+#[receive(
+    contract = "voting",
+    name = "updateDescription",
+    mutable,
+    parameter = "String",
+    error = "VotingError",
+    enable_logger
+)]
+fn update_description<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    // Parse the parameter.
+    let description: String = ctx.parameter_cursor().get()?;
+    if description.len() > MAX_DESCRIPTION_LENGTH {
+        return Err(VotingError::DescriptionTooLong);
+    }
+
+    host.state_mut().description = description;
+    logger.log(&VotingEvent::DescriptionUpdated)?;
+
+    Ok(())
+}
+
+/// Replace the public key of a verifier. Votes signed with the old key are
+/// rejected from then on.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender is not the admin.
+/// - The verifier key does not exist.
+#[receive(
+    contract = "voting",
+    name = "rotateVerifierKey",
+    mutable,
+    parameter = "RotateVerifierKeyParameter",
+    error = "VotingError",
+    enable_logger
+)]
+fn rotate_verifier_key<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    // Parse the parameter.
+    let param: RotateVerifierKeyParameter = ctx.parameter_cursor().get()?;
+
+    let key = host
+        .state_mut()
+        .verifier_public_keys
+        .get_mut(usize::from(param.index))
+        .ok_or(VotingError::InvalidVerifierIndex)?;
+    let old_key = *key;
+    *key = param.new_key;
+    logger.log(&VotingEvent::VerifierKeyRotated {
+        index: param.index,
+        old_key,
+        new_key: param.new_key,
+    })?;
+
+    Ok(())
+}
+
+/// Approve a write-in, which adds the country as a voting option and counts
+/// the pending votes for it. The country code is used as the label of the new
+/// option. The voters become eligible to vote for the new
+/// option, and their previous votes are replaced.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - The sender is not the admin.
+/// - It is past the `end_time`.
+/// - The election is cancelled or finalized.
+/// - The country is already a voting option.
+/// - No votes for the country are pending.
+/// - More votes are pending than `max_votes_per_option`.
+/// - The election already has `MAX_OPTIONS` voting options.
+#[receive(
+    contract = "voting",
+    name = "approveWriteIn",
+    mutable,
+    parameter = "VotingOption",
+    error = "VotingError",
+    enable_logger
+)]
+fn approve_write_in<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> VotingResult<()> {
+    ensure_admin(ctx, host)?;
+
+    // Parse the parameter.
+    let option: VotingOption = ctx.parameter_cursor().get()?;
+
+    // The pending votes are counted as votes cast now, so the election must
+    // still be open.
+    if ctx.metadata().slot_time() > host.state().end_time {
+        return Err(VotingError::VotingFinished);
+    }
+
+    let state = host.state_mut();
+    if state.cancelled {
+        return Err(VotingError::ElectionCancelled);
+    }
+    if state.result.is_some() {
+        return Err(VotingError::AlreadyFinalized);
+    }
+    if state.option_index(&option).is_some() {
+        return Err(VotingError::DuplicateOption);
+    }
+
+    // Collect the voters that wrote in the country.
+    let voters: Vec<AccountAddress> = state
+        .pending_write_ins
+        .iter()
+        .filter(|(_, write_in)| **write_in == option)
+        .map(|(voter, _)| *voter)
+        .collect();
+    if voters.is_empty() {
+        return Err(VotingError::NoPendingWriteIn);
+    }
+    if state.options.len() >= MAX_OPTIONS {
+        return Err(VotingError::TooManyOptions);
+    }
+
+    // Add the country as a voting option.
+    let vote_index = state.options.len() as VoteIndex;
+    state.tally.insert(vote_index, 0);
+    state.options.push(OptionInfo {
+        code: option.clone(),
+        label: option.clone(),
+        url: String::new(),
+        image_hash: None,
+    });
+    logger.log(&VotingEvent::OptionAdded { option })?;
+
+    // Count the pending votes.
+    for voter in voters {
+        let weight = voting_weight(host, voter)?;
+        let state = host.state_mut();
+        state.pending_write_ins.remove(&voter);
+        state.add_eligible_option(voter, vote_index);
+        state.cast_vote(voter, vote_index, weight, logger)?;
+    }
+
+    Ok(())
+}
+
+/// Get which of the queried standards the contract implements, as defined by
+/// the CIS-0 standard, so that wallets and tooling can introspect the
+/// instance.
+///
+/// It rejects if it fails to parse the parameter.
+#[receive(
+    contract = "voting",
+    name = "supports",
+    parameter = "SupportsQueryParams",
+    return_value = "SupportsQueryResponse"
+)]
+fn supports<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    _host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<SupportsQueryResponse> {
+    // Parse the parameter.
+    let param: SupportsQueryParams = ctx.parameter_cursor().get()?;
+
+    let results = param
+        .queries
+        .iter()
+        .map(|standard| {
+            if SUPPORTED_STANDARDS.contains(&standard.0.as_str()) {
+                SupportResult::Support
+            } else {
+                SupportResult::NoSupport
+            }
+        })
+        .collect();
+    Ok(SupportsQueryResponse { results })
 }
@@ -2,25 +2,332 @@
 //!
 //! The best way to run these tests are with `cargo concordium test --out concordium-out/module.wasm.v1`
 //! as that will make sure to compile the module before running the tests.
+//!
+//! The differential tests additionally load the module of the basic version of the contract.
+//! Build it first by running the same command in the `../../smart-contract` folder.
 
 use concordium_smart_contract_testing::*;
-use concordium_std::Timestamp;
+use concordium_std::{
+    collections::BTreeMap, Deserial, Duration, HashSha2256, OwnedEntrypointName,
+    PublicKeyEcdsaSecp256k1, PublicKeyEd25519, Serial, SignatureEcdsaSecp256k1, SignatureEd25519,
+    Timestamp,
+};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer as _};
+use sha2::{Digest, Sha256};
 use voting_contract::*;
 
 /// An account address of all 0s.
 const ACC_0: AccountAddress = AccountAddress([0; 32]);
 /// An account address of all 1s.
 const ACC_1: AccountAddress = AccountAddress([1; 32]);
+/// An account address of all 2s, which only votes through `voteFor`.
+const ACC_2: AccountAddress = AccountAddress([2; 32]);
 /// A `Signer` used for signing the transactions while testing.
 const SIGNER: Signer = Signer::with_one_key();
 /// The unix epoch time in milliseconds for noon at Christmas eve 2023.
 const CHRISTMAS_EVE_EPOCH: u64 = 1701873444000;
+/// The identifier of the election in the tests.
+const ELECTION_ID: &str = "eurovision-2023";
 
-/// Helper function that sets up a chain, account, and initialized contract.
-/// The contract is initialized with:
-///  - `end_time` = `CHRISTMAS_EVE_EPOCH`
+/// The secret key used by the verifier in the tests.
+const VERIFIER_SECRET_KEY: [u8; 32] = [7; 32];
+/// The hash of the statement that the test verifier checks.
+const STATEMENT_HASH: HashSha2256 = HashSha2256([4; 32]);
+/// The secret key of the account `ACC_2`.
+const VOTER_SECRET_KEY: [u8; 32] = [9; 32];
+
+/// Helper function that creates a key pair from a secret key.
+fn keypair(secret_key: [u8; 32]) -> Keypair {
+    let secret = SecretKey::from_bytes(&secret_key).expect("Valid secret key");
+    let public = PublicKey::from(&secret);
+    Keypair { secret, public }
+}
+
+/// Helper function that gets the Ed25519 public key for a secret key.
+fn public_key(secret_key: [u8; 32]) -> VerifierPublicKey {
+    VerifierPublicKey::Ed25519(PublicKeyEd25519(keypair(secret_key).public.to_bytes()))
+}
+
+/// Helper function that gets the public key of the test verifier.
+fn verifier_public_key() -> VerifierPublicKey {
+    public_key(VERIFIER_SECRET_KEY)
+}
+
+/// Helper function that gets the ECDSA public key on the secp256k1 curve for a secret key.
+fn ecdsa_public_key(secret_key: [u8; 32]) -> VerifierPublicKey {
+    let secp = secp256k1::Secp256k1::new();
+    let secret_key = secp256k1::SecretKey::from_slice(&secret_key).expect("Valid secret key");
+    VerifierPublicKey::EcdsaSecp256k1(PublicKeyEcdsaSecp256k1(
+        secret_key.public_key(&secp).serialize(),
+    ))
+}
+
+/// Helper function that creates an ECDSA signature on the SHA2-256 hash of a message.
+fn ecdsa_sign(secret_key: [u8; 32], message: &[u8]) -> VerifierSignature {
+    let secp = secp256k1::Secp256k1::new();
+    let secret_key = secp256k1::SecretKey::from_slice(&secret_key).expect("Valid secret key");
+    let message_hash: [u8; 32] = Sha256::digest(message).into();
+    let message = secp256k1::Message::from_slice(&message_hash).expect("Valid message hash");
+    VerifierSignature::EcdsaSecp256k1(SignatureEcdsaSecp256k1(
+        secp.sign_ecdsa(&message, &secret_key).serialize_compact(),
+    ))
+}
+
+/// Helper function that creates a voting option for `code` without an image, labelled with the code.
+fn option_info(code: &str) -> OptionInfo {
+    OptionInfo {
+        code: String::from(code),
+        label: format!("Country {}", code),
+        url: format!("https://example.com/{}.svg", code),
+        image_hash: None,
+    }
+}
+
+/// Builder for the `InitParameter` used in the tests.
+/// The defaults are:
+///  - `description` = "Concordium EuroVision"
+///  - `election_id` = `ELECTION_ID`
 ///  - `options` = ["DK", "DE", "IT"]
+///  - `end_time` = `CHRISTMAS_EVE_EPOCH`
+///  - `verifier_public_keys` = the public key of the test verifier
+///  - `verifier_threshold` = 1
+///  - `extension_approval_percentage` = 50
+///  - `blocs` = no blocs
+///  - `min_balance` = no minimum balance
+struct InitParameterBuilder {
+    param: InitParameter,
+}
+
+impl Default for InitParameterBuilder {
+    fn default() -> Self {
+        Self {
+            param: InitParameter {
+                description: String::from("Concordium EuroVision"),
+                election_id: String::from(ELECTION_ID),
+                options: vec![option_info("DK"), option_info("DE"), option_info("IT")],
+                start_time: None,
+                end_time: Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH), // Noon on Christmas eve.
+                registration_end_time: Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH),
+                verifier_public_keys: vec![verifier_public_key()],
+                verifier_threshold: 1,
+                extension_approval_percentage: 50,
+                blocs: BTreeMap::new(),
+                min_balance: None,
+                allow_vote_change: true,
+                change_cooldown: None,
+                max_changes: None,
+                max_votes_per_option: None,
+                quorum: 0,
+                tie_breaking: TieBreakingPolicy::FirstListed,
+                weights: None,
+                token_weights: None,
+                quadratic_voting: false,
+                tally_authority: None,
+                allow_list_root: None,
+                credential_registry: None,
+                recurrence: None,
+                contract_voters: vec![],
+                max_voters: None,
+                funds_recipient: None,
+                retention_period: None,
+                expected_voters: None,
+                record_vote_history: false,
+                logs_only: false,
+                certification_key: None,
+                badge_contract: None,
+                time_weighted: false,
+                statement_hash: STATEMENT_HASH,
+            },
+        }
+    }
+}
+
+impl InitParameterBuilder {
+    /// Set the description of the election.
+    fn description(mut self, description: &str) -> Self {
+        self.param.description = String::from(description);
+        self
+    }
+
+    /// Set the voting options.
+    fn options(mut self, options: &[&str]) -> Self {
+        self.param.options = options.iter().map(|o| option_info(o)).collect();
+        self
+    }
+
+    /// Set the `start_time` of the election.
+    fn start_time(mut self, start_time: Timestamp) -> Self {
+        self.param.start_time = Some(start_time);
+        self
+    }
+
+    /// Set the `end_time` of the election.
+    fn end_time(mut self, end_time: Timestamp) -> Self {
+        self.param.end_time = end_time;
+        self
+    }
+
+    /// Set the `registration_end_time` of the election.
+    fn registration_end_time(mut self, registration_end_time: Timestamp) -> Self {
+        self.param.registration_end_time = registration_end_time;
+        self
+    }
+
+    /// Set the percentage of voters that must approve an extension.
+    fn extension_approval_percentage(mut self, extension_approval_percentage: u8) -> Self {
+        self.param.extension_approval_percentage = extension_approval_percentage;
+        self
+    }
+
+    /// Add a voting option that represents a bloc with the given members.
+    fn bloc(mut self, bloc: &str, members: &[&str]) -> Self {
+        self.param.options.push(option_info(bloc));
+        self.param.blocs.insert(
+            String::from(bloc),
+            members.iter().map(|m| String::from(*m)).collect(),
+        );
+        self
+    }
+
+    /// Set the verifiers by their secret keys, and the number of verifiers that must sign a vote.
+    fn verifiers(mut self, secret_keys: &[[u8; 32]], threshold: u8) -> Self {
+        self.param.verifier_public_keys = secret_keys.iter().map(|key| public_key(*key)).collect();
+        self.param.verifier_threshold = threshold;
+        self
+    }
+
+    /// Set the minimum balance required to vote.
+    fn min_balance(mut self, min_balance: Amount) -> Self {
+        self.param.min_balance = Some(min_balance);
+        self
+    }
+
+    /// Build the `InitParameter`.
+    fn build(self) -> InitParameter {
+        self.param
+    }
+}
+
+/// The address of the first contract instance on the test chain, which the votes are meant for by default.
+const FIRST_CONTRACT: ContractAddress = ContractAddress {
+    index: 0,
+    subindex: 0,
+};
+
+/// Builder for a `RegisterParameter` for an account.
+/// By default the registration is signed with the key of the test verifier, as the verifier with index 0,
+/// for the first contract instance on the test chain and the election `ELECTION_ID`,
+/// and expires at `CHRISTMAS_EVE_EPOCH`.
+struct RegisterParameterBuilder {
+    account_address: AccountAddress,
+    contract_address: ContractAddress,
+    election_id: String,
+    expiry: Timestamp,
+    nonce: u64,
+    country_code: String,
+    signing_keys: BTreeMap<u8, [u8; 32]>,
+}
+
+impl RegisterParameterBuilder {
+    /// Start building a registration of `account_address` for `country_code`.
+    fn new(account_address: AccountAddress, country_code: &str) -> Self {
+        Self {
+            account_address,
+            contract_address: FIRST_CONTRACT,
+            election_id: String::from(ELECTION_ID),
+            expiry: Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH),
+            nonce: 0,
+            country_code: String::from(country_code),
+            signing_keys: BTreeMap::from([(0, VERIFIER_SECRET_KEY)]),
+        }
+    }
+
+    /// Sign the registration for another contract instance than the first one on the test chain.
+    fn for_contract(mut self, contract_address: ContractAddress) -> Self {
+        self.contract_address = contract_address;
+        self
+    }
+
+    /// Sign the registration for another election than `ELECTION_ID`.
+    fn for_election(mut self, election_id: &str) -> Self {
+        self.election_id = String::from(election_id);
+        self
+    }
+
+    /// Let the signatures expire at another time than `CHRISTMAS_EVE_EPOCH`.
+    fn expires_at(mut self, expiry: Timestamp) -> Self {
+        self.expiry = expiry;
+        self
+    }
+
+    /// Sign the registration with another nonce than 0, i.e. for another than the first registration of the account.
+    fn with_nonce(mut self, nonce: u64) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    /// Sign the registration with another secret key than the one of the test verifier.
+    fn signed_by(mut self, signing_key: [u8; 32]) -> Self {
+        self.signing_keys = BTreeMap::from([(0, signing_key)]);
+        self
+    }
+
+    /// Sign the registration with the given secret keys, as the verifiers with the given indices.
+    fn signed_by_verifiers(mut self, signing_keys: &[(u8, [u8; 32])]) -> Self {
+        self.signing_keys = signing_keys.iter().copied().collect();
+        self
+    }
+
+    /// Build the `RegisterParameter` by signing the message
+    /// `(account_address, contract_address, election_id, expiry, nonce, statement_hash, country_code)`.
+    fn build(self) -> RegisterParameter {
+        let message = to_bytes(&SignatureMessageData {
+            account_address: self.account_address,
+            contract_address: self.contract_address,
+            election_id: self.election_id,
+            expiry: self.expiry,
+            nonce: self.nonce,
+            statement_hash: STATEMENT_HASH,
+            country_code: self.country_code.clone(),
+        });
+        let signatures = self
+            .signing_keys
+            .into_iter()
+            .map(|(index, signing_key)| {
+                let signature = keypair(signing_key).sign(&message);
+                (
+                    index,
+                    VerifierSignature::Ed25519(SignatureEd25519(signature.to_bytes())),
+                )
+            })
+            .collect();
+        RegisterParameter {
+            country_code: self.country_code,
+            expiry: self.expiry,
+            signatures,
+        }
+    }
+}
+
+/// Helper function that sets up a chain, account, and a contract initialized
+/// with the defaults of the `InitParameterBuilder`.
 fn setup_chain_and_contract(block_time: Timestamp) -> (Chain, ContractInitSuccess) {
+    setup_chain_and_contract_with(block_time, InitParameterBuilder::default().build())
+}
+
+/// Helper function that sets up a chain, account, and a contract initialized with `param`.
+fn setup_chain_and_contract_with(
+    block_time: Timestamp,
+    param: InitParameter,
+) -> (Chain, ContractInitSuccess) {
+    let (mut chain, module_reference) = setup_chain_and_module(block_time);
+    let initialization =
+        init_contract(&mut chain, module_reference, &param).expect("Initialization should succeed");
+    (chain, initialization)
+}
+
+/// Helper function that sets up a chain, accounts, and the deployed module.
+fn setup_chain_and_module(block_time: Timestamp) -> (Chain, ModuleReference) {
     // Setup the test chain struct.
     let mut chain = Chain::new_with_time(block_time);
 
@@ -37,59 +344,243 @@ fn setup_chain_and_contract(block_time: Timestamp) -> (Chain, ContractInitSucces
         .module_deploy_v1(SIGNER, ACC_0, module)
         .expect("Deploying valid module should succeed");
 
-    // Initialize the contract.
-    let initialization = chain
-        .contract_init(
-            SIGNER,
-            ACC_0,
-            Energy::from(10000),
-            InitContractPayload {
-                amount: Amount::zero(),
-                mod_ref: deployment.module_reference,
-                init_name: OwnedContractName::new_unchecked(String::from("init_voting")),
-                param: OwnedParameter::from_serial(&InitParameter {
-                    description: String::from("Concordium EuroVision"),
-                    options: vec![String::from("DK"), String::from("DE"), String::from("IT")],
-                    end_time: Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH), // Noon on Christmas eve.
-                })
-                .expect("Valid parameter size"),
-            },
-        )
-        .expect("Initialization should succeed");
+    (chain, deployment.module_reference)
+}
 
-    (chain, initialization)
+/// Helper function that initializes a contract from the module with `param`.
+fn init_contract(
+    chain: &mut Chain,
+    module_reference: ModuleReference,
+    param: &InitParameter,
+) -> Result<ContractInitSuccess, ContractInitError> {
+    chain.contract_init(
+        SIGNER,
+        ACC_0,
+        Energy::from(100000),
+        InitContractPayload {
+            amount: Amount::zero(),
+            mod_ref: module_reference,
+            init_name: OwnedContractName::new_unchecked(String::from("init_voting")),
+            param: OwnedParameter::from_serial(param).expect("Valid parameter size"),
+        },
+    )
 }
 
-/// Test that an account cannot vote if it is past the `end_time` of the election.
-#[test]
-fn test_vote_after_end_time() {
-    // Set up the chain with a block time later than `CHRISTMAS_EVE_EPOCH`, such voting is no longer permitted.
-    let (mut chain, initialization) =
-        setup_chain_and_contract(Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH + 1));
+/// Helper function that updates the `entrypoint` of the contract at `address` with the `parameter`.
+/// If the `sender` is a contract, the transaction is paid for by `ACC_0`.
+fn update(
+    chain: &mut Chain,
+    address: ContractAddress,
+    sender: Address,
+    entrypoint: &str,
+    parameter: &impl Serial,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    update_with_amount(
+        chain,
+        address,
+        sender,
+        entrypoint,
+        parameter,
+        Amount::zero(),
+    )
+}
 
-    // Try to vote
-    let update = chain
-        .contract_update(
-            SIGNER,
+/// Helper function that invokes the `entrypoint` of the contract with `parameter` and attaches `amount` to the call.
+fn update_with_amount(
+    chain: &mut Chain,
+    address: ContractAddress,
+    sender: Address,
+    entrypoint: &str,
+    parameter: &impl Serial,
+    amount: Amount,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    let invoker = match sender {
+        Address::Account(acc) => acc,
+        Address::Contract(_) => ACC_0,
+    };
+    chain.contract_update(
+        SIGNER,
+        invoker,
+        sender,
+        Energy::from(100000),
+        UpdateContractPayload {
+            amount,
+            address,
+            receive_name: OwnedReceiveName::new_unchecked(format!("voting.{}", entrypoint)),
+            message: OwnedParameter::from_serial(parameter).expect("Parameter has valid length"),
+        },
+    )
+}
+
+/// Helper function that registers the account `voter` with `param`.
+fn register(
+    chain: &mut Chain,
+    address: ContractAddress,
+    voter: AccountAddress,
+    param: &RegisterParameter,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    update(chain, address, Address::Account(voter), "register", param)
+}
+
+/// Helper function that votes on `country_code` from the account `voter`.
+fn vote(
+    chain: &mut Chain,
+    address: ContractAddress,
+    voter: AccountAddress,
+    country_code: &str,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    update(
+        chain,
+        address,
+        Address::Account(voter),
+        "vote",
+        &VotingOption::from(country_code),
+    )
+}
+
+/// Helper function that lets the account `voter` vote for the voting option `country_code` with a deposit of `amount`.
+fn vote_with_deposit(
+    chain: &mut Chain,
+    address: ContractAddress,
+    voter: AccountAddress,
+    country_code: &str,
+    amount: Amount,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    update_with_amount(
+        chain,
+        address,
+        Address::Account(voter),
+        "voteWithDeposit",
+        &VotingOption::from(country_code),
+        amount,
+    )
+}
+
+/// Helper function that registers the account `voter` with `param` and then votes on the registered voting option.
+/// Returns the result of the vote, or the error of the registration if it fails.
+fn register_and_vote(
+    chain: &mut Chain,
+    address: ContractAddress,
+    voter: AccountAddress,
+    param: &RegisterParameter,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    register(chain, address, voter, param)?;
+    vote(chain, address, voter, &param.country_code)
+}
+
+/// Helper function that invokes the `entrypoint` of the contract at `address` with the `parameter`
+/// and deserializes the return value.
+fn invoke<T: Deserial>(
+    chain: &Chain,
+    address: ContractAddress,
+    entrypoint: &str,
+    parameter: &impl Serial,
+) -> T {
+    let invoke = chain
+        .contract_invoke(
             ACC_0,
-            Address::Account(ACC_0),
-            Energy::from(10000),
+            Address::Account(ACC_0), // The account used here doesn't matter, as it is just an invoke, not an update.
+            Energy::from(100000),
             UpdateContractPayload {
                 amount: Amount::zero(),
-                address: initialization.contract_address,
-                receive_name: OwnedReceiveName::new_unchecked(String::from("voting.vote")),
-                message: OwnedParameter::from_serial(&VotingOption::from("DE"))
+                address,
+                receive_name: OwnedReceiveName::new_unchecked(format!("voting.{}", entrypoint)),
+                message: OwnedParameter::from_serial(parameter)
                     .expect("Parameter has valid length"),
             },
         )
-        .expect_err("Vote fails");
+        .expect("Invoke succeeds.");
+    from_bytes(&invoke.return_value).expect("Return value should have the expected type")
+}
+
+/// Helper function that invokes the `view` function of the contract at `address`.
+fn view(chain: &Chain, address: ContractAddress) -> VotingView {
+    invoke(chain, address, "view", &())
+}
+
+/// Helper function that invokes the `nonce` function of the contract at `address` for `account`.
+fn nonce(chain: &Chain, address: ContractAddress, account: AccountAddress) -> u64 {
+    invoke(chain, address, "nonce", &account)
+}
+
+/// Helper function that deserializes the events logged by a successful update.
+fn events(update: &ContractInvokeSuccess) -> Vec<VotingEvent> {
+    update
+        .events()
+        .flat_map(|(_, events)| events.iter())
+        .map(|e| from_bytes(e.as_ref()).expect("Event should be a VotingEvent"))
+        .collect()
+}
+
+/// Helper function that deserializes the `VotingError` returned by a failed update.
+fn voting_error(update: ContractInvokeError) -> VotingError {
     // Get the return value by unwrapping the `Option`.
     let return_value = update.return_value().expect("A value should be returned");
     // Deserialize it into a `VotingError`.
-    let error: VotingError =
-        from_bytes(&return_value).expect("Return value should be a VotingError.");
+    from_bytes(return_value).expect("Return value should be a VotingError.")
+}
+
+/// Helper function that advances the block time of the chain past `CHRISTMAS_EVE_EPOCH`,
+/// assuming that the chain was set up at time 0.
+fn finish_election(chain: &mut Chain) {
+    chain
+        .tick_block_time(Duration::from_millis(CHRISTMAS_EVE_EPOCH + 1))
+        .expect("Block time does not overflow");
+}
+
+/// Test that an account cannot vote if it is past the `end_time` of the election,
+/// already one millisecond after it.
+#[test]
+fn test_vote_after_end_time() {
+    // Move the block time past `CHRISTMAS_EVE_EPOCH`, such voting is no longer permitted.
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    finish_election(&mut chain);
+
+    // Try to vote
+    let update = register_and_vote(
+        &mut chain,
+        initialization.contract_address,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect_err("Vote fails");
     // Check that it failed for the right reason.
-    assert_eq!(error, VotingError::VotingFinished);
+    assert_eq!(voting_error(update), VotingError::VotingFinished);
+}
+
+/// Test that an account can still vote when the block time is exactly the `end_time`.
+/// The deadline is inclusive: the `end_time` is the last timestamp at which voting is possible.
+#[test]
+fn test_vote_at_end_time() {
+    // Set up the chain with a block time equal to `CHRISTMAS_EVE_EPOCH`.
+    let (mut chain, initialization) =
+        setup_chain_and_contract(Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH));
+
+    // Vote on Germany.
+    register_and_vote(
+        &mut chain,
+        initialization.contract_address,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting at exactly the `end_time` succeeds");
+}
+
+/// Test that an account can vote one millisecond before the `end_time`.
+#[test]
+fn test_vote_one_millisecond_before_end_time() {
+    // Set up the chain with a block time one millisecond before `CHRISTMAS_EVE_EPOCH`.
+    let (mut chain, initialization) =
+        setup_chain_and_contract(Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH - 1));
+
+    // Vote on Germany.
+    register_and_vote(
+        &mut chain,
+        initialization.contract_address,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting before the `end_time` succeeds");
 }
 
 /// Test that voting on an unknown option fails.
@@ -99,28 +590,90 @@ fn test_vote_on_unknown_option_fails() {
     let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
 
     // Try to vote on an invalid option.
-    let update = chain
-        .contract_update(
-            SIGNER,
+    let update = register_and_vote(
+        &mut chain,
+        initialization.contract_address,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "IN").build(), // India is not a valid option.
+    )
+    .expect_err("Vote fails");
+    // Check that it failed for the right reason.
+    assert_eq!(
+        voting_error(update),
+        VotingError::InvalidVotingOption {
+            option: String::from("IN")
+        }
+    );
+}
+
+/// Test that voting options and votes must be ISO 3166-1 alpha-2 country codes or blocs.
+#[test]
+fn test_malformed_country_code_fails() {
+    let (mut chain, module_reference) = setup_chain_and_module(Timestamp::from_timestamp_millis(0));
+    for options in [&["DE", "de"], &["DE", "XX"], &["DE", "DEU"]] {
+        init_contract(
+            &mut chain,
+            module_reference,
+            &InitParameterBuilder::default().options(options).build(),
+        )
+        .expect_err("Initialization with a malformed country code fails");
+    }
+
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    for country_code in ["de", "XX", "D"] {
+        let update_err = register(
+            &mut chain,
+            contract,
             ACC_0,
-            Address::Account(ACC_0),
-            Energy::from(10000),
-            UpdateContractPayload {
-                amount: Amount::zero(),
-                address: initialization.contract_address,
-                receive_name: OwnedReceiveName::new_unchecked(String::from("voting.vote")),
-                message: OwnedParameter::from_serial(&VotingOption::from("IN")) // India is a valid option.
-                    .expect("Parameter has valid length"),
-            },
+            &RegisterParameterBuilder::new(ACC_0, country_code).build(),
         )
-        .expect_err("Vote fails");
-    // Get the return value by unwrapping the `Option`.
-    let return_value = update.return_value().expect("A value should be returned");
-    // Deserialize it into a `VotingError`.
-    let error: VotingError =
-        from_bytes(&return_value).expect("Return value should be a VotingError.");
+        .expect_err("Registering fails");
+        assert_eq!(voting_error(update_err), VotingError::MalformedCountryCode);
+        let update_err = vote(&mut chain, contract, ACC_0, country_code).expect_err("Voting fails");
+        assert_eq!(voting_error(update_err), VotingError::MalformedCountryCode);
+    }
+}
+
+/// Test that voting with a signature that is not from the verifier fails.
+#[test]
+fn test_vote_with_invalid_signature_fails() {
+    // Set up the chain with a block time below the end time.
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+
+    // Try to vote with a signature from another key.
+    let update = register_and_vote(
+        &mut chain,
+        initialization.contract_address,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE")
+            .signed_by([8; 32])
+            .build(),
+    )
+    .expect_err("Vote fails");
     // Check that it failed for the right reason.
-    assert_eq!(error, VotingError::InvalidVotingOption);
+    assert_eq!(
+        voting_error(update),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Message
+        }
+    );
+
+    // Try to vote with a signature issued for another account.
+    let update = register_and_vote(
+        &mut chain,
+        initialization.contract_address,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_1, "DE").build(),
+    )
+    .expect_err("Vote fails");
+    // Check that it failed for the right reason.
+    assert_eq!(
+        voting_error(update),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Message
+        }
+    );
 }
 
 /// Test that voting works.
@@ -128,122 +681,4683 @@ fn test_vote_on_unknown_option_fails() {
 /// - That you can change your vote,
 /// - And that votes by multiple accounts are stored correctly.
 ///
-/// The test works by alternating between using `chain.contract_update` for updating the contract when voting,
-/// and using `chain.contract_invoke` when invoking the view function.
+/// The test works by alternating between updating the contract when voting,
+/// and invoking the view function.
 #[test]
 fn test_valid_voting_with_multiple_accounts() {
     // Set up the chain with a block time below the end time.
     let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
 
     // ACC_0 votes on Germany.
-    chain
-        .contract_update(
-            SIGNER,
-            ACC_0,
-            Address::Account(ACC_0), // ACC_0 is the sender.
-            Energy::from(10000),
-            UpdateContractPayload {
-                amount: Amount::zero(),
-                address: initialization.contract_address,
-                receive_name: OwnedReceiveName::new_unchecked(String::from("voting.vote")),
-                message: OwnedParameter::from_serial(&VotingOption::from("DE")) // Voting on Germany.
-                    .expect("Parameter has valid length"),
-            },
-        )
-        .expect("Voting succeeds");
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
 
-    // Use `contract_invoke` to get the `VotingView`.
-    let view_0 = chain
-        .contract_invoke(
-            ACC_0,
-            Address::Account(ACC_0),
-            Energy::from(10000),
-            UpdateContractPayload {
-                amount: Amount::zero(),
-                address: initialization.contract_address,
-                receive_name: OwnedReceiveName::new_unchecked(String::from("voting.view")),
-                message: OwnedParameter::empty(),
-            },
-        )
-        .expect("Invoke succeeds.");
-    let voting_view_0: VotingView =
-        from_bytes(&view_0.return_value).expect("Return value should be a VotingView");
-    // There is only a single entry.
-    assert_eq!(voting_view_0.tally.len(), 1);
+    let voting_view_0 = view(&chain, contract);
+    // All options are included.
+    assert_eq!(voting_view_0.tally.len(), 3);
     // There is one vote on Germany.
     assert_eq!(voting_view_0.tally.get("DE"), Some(&1));
+    // .. And none on Denmark.
+    assert_eq!(voting_view_0.tally.get("DK"), Some(&0));
 
     // ACC_1 votes on Denmark.
-    chain
-        .contract_update(
-            SIGNER,
-            ACC_1,
-            Address::Account(ACC_1), // ACC_1 is now the sender.
-            Energy::from(10000),
-            UpdateContractPayload {
-                amount: Amount::zero(),
-                address: initialization.contract_address,
-                receive_name: OwnedReceiveName::new_unchecked(String::from("voting.vote")),
-                message: OwnedParameter::from_serial(&VotingOption::from("DK")) // Voting on Denmark.
-                    .expect("Parameter has valid length"),
-            },
-        )
-        .expect("Voting succeeds");
-    let view_1 = chain
-        .contract_invoke(
-            ACC_1,
-            Address::Account(ACC_1), // The account used here doesn't matter, as it is just an invoke, not an update.
-            Energy::from(10000),
-            UpdateContractPayload {
-                amount: Amount::zero(),
-                address: initialization.contract_address,
-                receive_name: OwnedReceiveName::new_unchecked(String::from("voting.view")),
-                message: OwnedParameter::empty(),
-            },
-        )
-        .expect("Invoke succeeds.");
-    let voting_view_1: VotingView =
-        from_bytes(&view_1.return_value).expect("Return value should be a VotingView");
-    // There are now two entries.
-    assert_eq!(voting_view_1.tally.len(), 2);
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DK").build(),
+    )
+    .expect("Voting succeeds");
+
+    let voting_view_1 = view(&chain, contract);
+    // All options are still included.
+    assert_eq!(voting_view_1.tally.len(), 3);
     // There is one vote on Germany.
     assert_eq!(voting_view_1.tally.get("DE"), Some(&1));
     // .. And one vote on Denmark.
     assert_eq!(voting_view_1.tally.get("DK"), Some(&1));
 
     // ACC_0 changes votes to Denmark.
-    chain
-        .contract_update(
-            SIGNER,
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DK")
+            .with_nonce(1)
+            .build(),
+    )
+    .expect("Voting succeeds");
+
+    let voting_view_2 = view(&chain, contract);
+    // All options are still included.
+    assert_eq!(voting_view_2.tally.len(), 3);
+    // There are two votes on Denmark.
+    assert_eq!(voting_view_2.tally.get("DK"), Some(&2));
+    // .. And none on Germany anymore.
+    assert_eq!(voting_view_2.tally.get("DE"), Some(&0));
+}
+
+/// Test that casting and changing a vote logs an event.
+#[test]
+fn test_vote_events() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    // ACC_0 votes on Germany.
+    let update_0 = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+    assert_eq!(
+        events(&update_0),
+        [VotingEvent::Vote(VoteEvent {
+            voter: ACC_0,
+            old_option: None,
+            new_option: String::from("DE"),
+        })]
+    );
+
+    // ACC_0 changes the vote to Italy.
+    let update_1 = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "IT")
+            .with_nonce(1)
+            .build(),
+    )
+    .expect("Voting succeeds");
+    assert_eq!(
+        events(&update_1),
+        [VotingEvent::Vote(VoteEvent {
+            voter: ACC_0,
+            old_option: Some(String::from("DE")),
+            new_option: String::from("IT"),
+        })]
+    );
+}
+
+/// Test looking up the vote of a single account with `getVote`.
+#[test]
+fn test_get_vote() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    // ACC_0 votes on Italy.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "IT").build(),
+    )
+    .expect("Voting succeeds");
+
+    let vote_0: Option<VotingOption> = invoke(&chain, contract, "getVote", &ACC_0);
+    assert_eq!(vote_0, Some(String::from("IT")));
+    let vote_1: Option<VotingOption> = invoke(&chain, contract, "getVote", &ACC_1);
+    assert_eq!(vote_1, None);
+}
+
+/// Test looking up the votes of several accounts at once with `getVotes`.
+#[test]
+fn test_get_votes() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    // ACC_0 votes on Italy and ACC_1 on Germany.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "IT").build(),
+    )
+    .expect("Voting succeeds");
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DE").build(),
+    )
+    .expect("Voting succeeds");
+
+    // The votes are returned in the order of the accounts.
+    let votes: Vec<Option<VotingOption>> =
+        invoke(&chain, contract, "getVotes", &vec![ACC_1, ACC_2, ACC_0]);
+    assert_eq!(
+        votes,
+        [Some(String::from("DE")), None, Some(String::from("IT"))]
+    );
+    let votes: Vec<Option<VotingOption>> =
+        invoke(&chain, contract, "getVotes", &Vec::<AccountAddress>::new());
+    assert!(votes.is_empty());
+}
+
+/// Test checking whether an account has voted with `hasVoted`.
+#[test]
+fn test_has_voted() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    // ACC_0 votes on Italy.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "IT").build(),
+    )
+    .expect("Voting succeeds");
+
+    let has_voted_0: bool = invoke(&chain, contract, "hasVoted", &ACC_0);
+    assert!(has_voted_0);
+    let has_voted_1: bool = invoke(&chain, contract, "hasVoted", &ACC_1);
+    assert!(!has_voted_1);
+}
+
+/// Test that `viewOptionInfo` returns the metadata of the voting options.
+#[test]
+fn test_view_option_info() {
+    let mut param = InitParameterBuilder::default().build();
+    param.options[1].image_hash = Some(HashSha2256([1; 32]));
+    let (chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+
+    let options: Vec<OptionInfo> = invoke(
+        &chain,
+        initialization.contract_address,
+        "viewOptionInfo",
+        &(),
+    );
+    assert_eq!(options.len(), 3);
+    assert_eq!(options[0], option_info("DK"));
+    assert_eq!(options[1].label, "Country DE");
+    assert_eq!(options[1].image_hash, Some(HashSha2256([1; 32])));
+}
+
+/// Test that `viewOptions` returns the voting options with their indices.
+#[test]
+fn test_view_options() {
+    let (chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+
+    let options: Vec<(VoteIndex, VotingOption)> =
+        invoke(&chain, initialization.contract_address, "viewOptions", &());
+    assert_eq!(
+        options,
+        [
+            (0, String::from("DK")),
+            (1, String::from("DE")),
+            (2, String::from("IT"))
+        ]
+    );
+}
+
+/// Test that the ballots can be enumerated page by page.
+#[test]
+fn test_view_ballots() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    // ACC_0 votes on Italy and ACC_1 votes on Germany.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "IT").build(),
+    )
+    .expect("Voting succeeds");
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DE").build(),
+    )
+    .expect("Voting succeeds");
+
+    let page = |skip, limit| -> Vec<(AccountAddress, VotingOption)> {
+        invoke(
+            &chain,
+            contract,
+            "viewBallots",
+            &ViewBallotsParameter { skip, limit },
+        )
+    };
+
+    // All ballots fit in a single page.
+    assert_eq!(
+        page(0, 10),
+        [(ACC_0, String::from("IT")), (ACC_1, String::from("DE"))]
+    );
+    // The ballots can be requested one at a time.
+    assert_eq!(page(0, 1), [(ACC_0, String::from("IT"))]);
+    assert_eq!(page(1, 1), [(ACC_1, String::from("DE"))]);
+    // There are no more ballots.
+    assert!(page(2, 1).is_empty());
+}
+
+/// Test that `voterCount` counts every account once, also when it changes its
+/// vote.
+#[test]
+fn test_voter_count() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    let voter_count: u32 = invoke(&chain, contract, "voterCount", &());
+    assert_eq!(voter_count, 0);
+
+    // ACC_0 votes on Italy and then changes its vote to Germany.
+    for (nonce, country_code) in [(0, "IT"), (1, "DE")] {
+        register_and_vote(
+            &mut chain,
+            contract,
             ACC_0,
-            Address::Account(ACC_0), // ACC_0 is the sender.
-            Energy::from(10000),
-            UpdateContractPayload {
-                amount: Amount::zero(),
-                address: initialization.contract_address,
-                receive_name: OwnedReceiveName::new_unchecked(String::from("voting.vote")),
-                message: OwnedParameter::from_serial(&VotingOption::from("DK")) // Changing vote to Denmark.
-                    .expect("Parameter has valid length"),
-            },
+            &RegisterParameterBuilder::new(ACC_0, country_code)
+                .with_nonce(nonce)
+                .build(),
         )
         .expect("Voting succeeds");
-    let view_2 = chain
-        .contract_invoke(
-            ACC_1,
-            Address::Account(ACC_1), // The account used here doesn't matter, as it is just an invoke, not an update.
-            Energy::from(10000),
-            UpdateContractPayload {
+    }
+    let voter_count: u32 = invoke(&chain, contract, "voterCount", &());
+    assert_eq!(voter_count, 1);
+
+    // ACC_1 votes on Germany.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DE").build(),
+    )
+    .expect("Voting succeeds");
+    let voter_count: u32 = invoke(&chain, contract, "voterCount", &());
+    assert_eq!(voter_count, 2);
+}
+
+/// Test that the `InitParameter` is reflected in the `view`.
+#[test]
+fn test_view_reflects_init_parameter() {
+    let (chain, initialization) = setup_chain_and_contract_with(
+        Timestamp::from_timestamp_millis(0),
+        InitParameterBuilder::default()
+            .description("Nordic song contest")
+            .options(&["DK", "NO", "SE"])
+            .end_time(Timestamp::from_timestamp_millis(42))
+            .build(),
+    );
+
+    let voting_view = view(&chain, initialization.contract_address);
+    assert_eq!(voting_view.description, "Nordic song contest");
+    assert_eq!(voting_view.end_time, Timestamp::from_timestamp_millis(42));
+    // Every option is included in the tally without any votes.
+    assert_eq!(
+        voting_view.tally.into_iter().collect::<Vec<_>>(),
+        [
+            (String::from("DK"), 0),
+            (String::from("NO"), 0),
+            (String::from("SE"), 0)
+        ]
+    );
+}
+
+/// Test that `init` logs an `ElectionCreated` event describing the election.
+#[test]
+fn test_init_event() {
+    let (mut chain, module_reference) = setup_chain_and_module(Timestamp::from_timestamp_millis(0));
+    let param = InitParameterBuilder::default()
+        .description("Nordic song contest")
+        .options(&["DK", "NO", "SE"])
+        .end_time(Timestamp::from_timestamp_millis(42))
+        .build();
+    let initialization =
+        init_contract(&mut chain, module_reference, &param).expect("Initialization should succeed");
+
+    let events: Vec<VotingEvent> = initialization
+        .events
+        .iter()
+        .map(|e| from_bytes(e.as_ref()).expect("Event should be a VotingEvent"))
+        .collect();
+    assert_eq!(
+        events,
+        [VotingEvent::ElectionCreated {
+            description_hash: HashSha2256(Sha256::digest("Nordic song contest").into()),
+            option_count: 3,
+            end_time: Timestamp::from_timestamp_millis(42),
+            verifier_keys_hash: HashSha2256(
+                Sha256::digest(to_bytes(&param.verifier_public_keys)).into()
+            ),
+        }]
+    );
+}
+
+/// Test that the `extension_approval_percentage` must be from 1 to 100.
+#[test]
+fn test_init_with_invalid_approval_percentage_fails() {
+    let mut chain = Chain::new_with_time(Timestamp::from_timestamp_millis(0));
+    chain.create_account(Account::new(ACC_0, Amount::from_ccd(10000)));
+    let module =
+        module_load_v1("./concordium-out/module.wasm.v1").expect("Module file should exist");
+    let deployment = chain
+        .module_deploy_v1(SIGNER, ACC_0, module)
+        .expect("Deploying valid module should succeed");
+
+    for (percentage, valid) in [(0, false), (1, true), (100, true), (101, false)] {
+        let param = InitParameterBuilder::default()
+            .extension_approval_percentage(percentage)
+            .build();
+        let result = chain.contract_init(
+            SIGNER,
+            ACC_0,
+            Energy::from(100000),
+            InitContractPayload {
                 amount: Amount::zero(),
-                address: initialization.contract_address,
-                receive_name: OwnedReceiveName::new_unchecked(String::from("voting.view")),
-                message: OwnedParameter::empty(),
+                mod_ref: deployment.module_reference,
+                init_name: OwnedContractName::new_unchecked(String::from("init_voting")),
+                param: OwnedParameter::from_serial(&param).expect("Valid parameter size"),
             },
+        );
+        assert_eq!(result.is_ok(), valid, "Approval percentage {}", percentage);
+    }
+}
+
+/// Test that voters can extend the `end_time` once enough of them approve.
+#[test]
+fn test_extend_end_time() {
+    // Set up a contract where all voters have to approve an extension.
+    let (mut chain, initialization) = setup_chain_and_contract_with(
+        Timestamp::from_timestamp_millis(0),
+        InitParameterBuilder::default()
+            .extension_approval_percentage(100)
+            .build(),
+    );
+    let contract = initialization.contract_address;
+    let new_end_time = Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH + 1000);
+
+    // Accounts that have not voted cannot propose an extension.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "proposeExtension",
+        &new_end_time,
+    )
+    .expect_err("Proposing fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAVoter);
+
+    // Both accounts vote.
+    for acc in [ACC_0, ACC_1] {
+        register_and_vote(
+            &mut chain,
+            contract,
+            acc,
+            &RegisterParameterBuilder::new(acc, "DE").build(),
         )
-        .expect("Invoke succeeds.");
-    let voting_view_2: VotingView =
-        from_bytes(&view_2.return_value).expect("Return value should be a VotingView");
-    // There is only one entry again.
-    assert_eq!(voting_view_2.tally.len(), 1);
-    // There are two votes on Denmark.
-    assert_eq!(voting_view_2.tally.get("DK"), Some(&2));
+        .expect("Voting succeeds");
+    }
+
+    // The new `end_time` must be later than the current one.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "proposeExtension",
+        &Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH),
+    )
+    .expect_err("Proposing fails");
+    assert_eq!(voting_error(update_err), VotingError::InvalidExtension);
+
+    // ACC_0 proposes an extension, which is pending until ACC_1 approves it.
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "proposeExtension",
+        &new_end_time,
+    )
+    .expect("Proposing succeeds");
+    let proposal: Option<ExtensionProposal> =
+        invoke(&chain, contract, "viewExtensionProposal", &());
+    let proposal = proposal.expect("A proposal is pending");
+    assert_eq!(proposal.proposer, ACC_0);
+    assert_eq!(proposal.new_end_time, new_end_time);
+    assert_eq!(
+        view(&chain, contract).end_time,
+        Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH)
+    );
+
+    // A second proposal is rejected while the first is pending.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "proposeExtension",
+        &new_end_time,
+    )
+    .expect_err("Proposing fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::ExtensionAlreadyProposed
+    );
+
+    // The proposer cannot approve twice.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "voteOnExtension",
+        &(),
+    )
+    .expect_err("Approving fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::ExtensionAlreadyApproved
+    );
+
+    // ACC_1 approves, which applies the extension.
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "voteOnExtension",
+        &(),
+    )
+    .expect("Approving succeeds");
+    assert_eq!(view(&chain, contract).end_time, new_end_time);
+    let proposal: Option<ExtensionProposal> =
+        invoke(&chain, contract, "viewExtensionProposal", &());
+    assert!(proposal.is_none());
+}
+
+/// Test that the election can be finalized exactly once, after the `end_time`.
+#[test]
+fn test_finalize() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    // ACC_0 votes on Italy and ACC_1 votes on Germany, then ACC_0 changes to Germany.
+    for (voter, country_code, nonce) in [(ACC_0, "IT", 0), (ACC_1, "DE", 0), (ACC_0, "DE", 1)] {
+        register_and_vote(
+            &mut chain,
+            contract,
+            voter,
+            &RegisterParameterBuilder::new(voter, country_code)
+                .with_nonce(nonce)
+                .build(),
+        )
+        .expect("Voting succeeds");
+    }
+
+    // The election cannot be finalized while it is running.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "finalize",
+        &(),
+    )
+    .expect_err("Finalizing fails");
+    assert_eq!(voting_error(update_err), VotingError::VotingNotFinished);
+
+    // There is no result before the election is finalized.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "getResult",
+        &(),
+    )
+    .expect_err("Getting the result fails");
+    assert_eq!(voting_error(update_err), VotingError::NotFinalized);
+
+    finish_election(&mut chain);
+
+    // Anyone can finalize the election once it has finished.
+    let finalization = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "finalize",
+        &(),
+    )
+    .expect("Finalizing succeeds");
+    assert_eq!(
+        events(&finalization),
+        [VotingEvent::Finalized {
+            winner: Some(String::from("DE"))
+        }]
+    );
+
+    // The view serves the frozen tally.
+    let voting_view = view(&chain, contract);
+    assert_eq!(voting_view.tally.get("DE"), Some(&2));
+    assert_eq!(voting_view.tally.get("IT"), Some(&0));
+
+    // Germany won with both votes.
+    let outcome: ElectionOutcome = invoke(&chain, contract, "getResult", &());
+    assert_eq!(
+        outcome,
+        ElectionOutcome {
+            winner: Some(String::from("DE")),
+            winner_count: 2,
+            tied: Vec::new(),
+            quorum_reached: true,
+        }
+    );
+
+    // The election can only be finalized once.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "finalize",
+        &(),
+    )
+    .expect_err("Finalizing again fails");
+    assert_eq!(voting_error(update_err), VotingError::AlreadyFinalized);
+}
+
+/// Test that the option listed first wins a tie, and that there is no winner without votes.
+#[test]
+fn test_finalize_winner_on_tie_and_without_votes() {
+    let (mut chain, module_reference) = setup_chain_and_module(Timestamp::from_timestamp_millis(0));
+    let param = InitParameterBuilder::default().build();
+    let tied_contract = init_contract(&mut chain, module_reference, &param)
+        .expect("Initialization should succeed")
+        .contract_address;
+    let empty_contract = init_contract(&mut chain, module_reference, &param)
+        .expect("Initialization should succeed")
+        .contract_address;
+
+    // ACC_0 votes on Italy and ACC_1 votes on Denmark, which is listed before Italy.
+    for (voter, country_code) in [(ACC_0, "IT"), (ACC_1, "DK")] {
+        register_and_vote(
+            &mut chain,
+            tied_contract,
+            voter,
+            &RegisterParameterBuilder::new(voter, country_code).build(),
+        )
+        .expect("Voting succeeds");
+    }
+
+    finish_election(&mut chain);
+
+    let finalization = update(
+        &mut chain,
+        tied_contract,
+        Address::Account(ACC_0),
+        "finalize",
+        &(),
+    )
+    .expect("Finalizing succeeds");
+    assert_eq!(
+        events(&finalization),
+        [VotingEvent::Finalized {
+            winner: Some(String::from("DK"))
+        }]
+    );
+    let outcome: ElectionOutcome = invoke(&chain, tied_contract, "getResult", &());
+    assert_eq!(
+        outcome,
+        ElectionOutcome {
+            winner: Some(String::from("DK")),
+            winner_count: 1,
+            tied: vec![String::from("DK"), String::from("IT")],
+            quorum_reached: true,
+        }
+    );
+
+    let finalization = update(
+        &mut chain,
+        empty_contract,
+        Address::Account(ACC_0),
+        "finalize",
+        &(),
+    )
+    .expect("Finalizing succeeds");
+    assert_eq!(
+        events(&finalization),
+        [VotingEvent::Finalized { winner: None }]
+    );
+    let outcome: ElectionOutcome = invoke(&chain, empty_contract, "getResult", &());
+    assert_eq!(
+        outcome,
+        ElectionOutcome {
+            winner: None,
+            winner_count: 0,
+            tied: Vec::new(),
+            quorum_reached: true,
+        }
+    );
+}
+
+/// Test that a vote needs valid signatures from at least `verifier_threshold` distinct verifiers.
+#[test]
+fn test_vote_with_multiple_verifiers() {
+    let verifier_keys = [[7; 32], [8; 32], [9; 32]];
+    let (mut chain, initialization) = setup_chain_and_contract_with(
+        Timestamp::from_timestamp_millis(0),
+        InitParameterBuilder::default()
+            .verifiers(&verifier_keys, 2)
+            .build(),
+    );
+    let contract = initialization.contract_address;
+
+    // A single signature is not enough.
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE")
+            .signed_by_verifiers(&[(0, verifier_keys[0])])
+            .build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Key
+        }
+    );
+
+    // A signature only counts for the verifier whose key created it.
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE")
+            .signed_by_verifiers(&[(0, verifier_keys[0]), (1, verifier_keys[0])])
+            .build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Message
+        }
+    );
+
+    // Two distinct verifiers are enough.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE")
+            .signed_by_verifiers(&[(0, verifier_keys[0]), (2, verifier_keys[2])])
+            .build(),
+    )
+    .expect("Voting succeeds");
+}
+
+/// Test that a signature issued for one contract instance cannot be replayed on another one.
+#[test]
+fn test_vote_with_signature_for_other_contract_fails() {
+    let (mut chain, module_reference) = setup_chain_and_module(Timestamp::from_timestamp_millis(0));
+    let param = InitParameterBuilder::default().build();
+    let first_contract = init_contract(&mut chain, module_reference, &param)
+        .expect("Initialization should succeed")
+        .contract_address;
+    let second_contract = init_contract(&mut chain, module_reference, &param)
+        .expect("Initialization should succeed")
+        .contract_address;
+
+    // The registration is signed for the first contract.
+    let vote_param = RegisterParameterBuilder::new(ACC_0, "DE")
+        .for_contract(first_contract)
+        .build();
+    let update_err = register_and_vote(&mut chain, second_contract, ACC_0, &vote_param)
+        .expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Message
+        }
+    );
+    register_and_vote(&mut chain, first_contract, ACC_0, &vote_param).expect("Voting succeeds");
+
+    // A registration signed for the second contract is accepted there.
+    register_and_vote(
+        &mut chain,
+        second_contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE")
+            .for_contract(second_contract)
+            .build(),
+    )
+    .expect("Voting succeeds");
+}
+
+/// Test that a signature issued for another election cannot be used.
+#[test]
+fn test_vote_with_signature_for_other_election_fails() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    // The election identifier is visible in the view.
+    assert_eq!(view(&chain, contract).election_id, ELECTION_ID);
+
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE")
+            .for_election("eurovision-2024")
+            .build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Message
+        }
+    );
+}
+
+/// Test that expired signatures are rejected, and that a signature is valid until its expiry.
+#[test]
+fn test_vote_with_expired_signature_fails() {
+    let (mut chain, initialization) =
+        setup_chain_and_contract(Timestamp::from_timestamp_millis(1000));
+    let contract = initialization.contract_address;
+
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE")
+            .expires_at(Timestamp::from_timestamp_millis(999))
+            .build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Expiry
+        }
+    );
+
+    // The expiry cannot be changed without invalidating the signature.
+    let mut param = RegisterParameterBuilder::new(ACC_0, "DE")
+        .expires_at(Timestamp::from_timestamp_millis(999))
+        .build();
+    param.expiry = Timestamp::from_timestamp_millis(2000);
+    let update_err =
+        register_and_vote(&mut chain, contract, ACC_0, &param).expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Message
+        }
+    );
+
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE")
+            .expires_at(Timestamp::from_timestamp_millis(1000))
+            .build(),
+    )
+    .expect("Voting succeeds");
+}
+
+/// Test that the signatures must include the current nonce of the account, so they can only be used once.
+#[test]
+fn test_vote_with_used_signature_fails() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    assert_eq!(nonce(&chain, contract, ACC_0), 0);
+
+    // A signature for a later nonce cannot be used yet.
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE")
+            .with_nonce(1)
+            .build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Message
+        }
+    );
+
+    let param = RegisterParameterBuilder::new(ACC_0, "DE").build();
+    register_and_vote(&mut chain, contract, ACC_0, &param).expect("Voting succeeds");
+    assert_eq!(nonce(&chain, contract, ACC_0), 1);
+    // The nonce of other accounts is unaffected.
+    assert_eq!(nonce(&chain, contract, ACC_1), 0);
+
+    // Replaying the signature fails, as the nonce has been incremented.
+    let update_err =
+        register_and_vote(&mut chain, contract, ACC_0, &param).expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Message
+        }
+    );
+
+    // A signature for the new nonce can be used to vote for the same option again.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE")
+            .with_nonce(1)
+            .build(),
+    )
+    .expect("Voting succeeds");
+    assert_eq!(nonce(&chain, contract, ACC_0), 2);
+}
+
+/// Test that an account can only vote for the voting options it has registered for, and can change its vote
+/// between them without new signatures.
+#[test]
+fn test_register_and_vote() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    // Voting without registering fails.
+    let update_err = vote(&mut chain, contract, ACC_0, "DE").expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::NotRegistered);
+
+    // ACC_0 registers for Germany and Denmark. Registering does not cast a vote.
+    for (nonce, country_code) in [(0, "DE"), (1, "DK")] {
+        register(
+            &mut chain,
+            contract,
+            ACC_0,
+            &RegisterParameterBuilder::new(ACC_0, country_code)
+                .with_nonce(nonce)
+                .build(),
+        )
+        .expect("Registering succeeds");
+    }
+    let voter_count: u32 = invoke(&chain, contract, "voterCount", &());
+    assert_eq!(voter_count, 0);
+
+    // ACC_0 votes on Germany and changes the vote to Denmark, without new signatures.
+    for country_code in ["DE", "DK"] {
+        vote(&mut chain, contract, ACC_0, country_code).expect("Voting succeeds");
+    }
+    let voting_view = view(&chain, contract);
+    assert_eq!(voting_view.tally.get("DE"), Some(&0));
+    assert_eq!(voting_view.tally.get("DK"), Some(&1));
+
+    // ACC_0 has not registered for Italy.
+    let update_err = vote(&mut chain, contract, ACC_0, "IT").expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::NotRegistered);
+
+    // The registrations of ACC_0 do not apply to ACC_1.
+    let update_err = vote(&mut chain, contract, ACC_1, "DE").expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::NotRegistered);
+}
+
+/// Helper function that signs `message` with the account key `secret_key` of `account`, like
+/// messages signed in the wallets, i.e. on the hash of the account address, 8 zero bytes, and the
+/// serialized message.
+fn account_signatures<M: Serial>(
+    account: AccountAddress,
+    message: &M,
+    secret_key: [u8; 32],
+) -> concordium_std::AccountSignatures {
+    let mut signed_bytes = to_bytes(&account);
+    signed_bytes.extend_from_slice(&[0; 8]);
+    signed_bytes.extend_from_slice(&to_bytes(message));
+    let message_hash: [u8; 32] = Sha256::digest(signed_bytes).into();
+    let signature = keypair(secret_key).sign(&message_hash);
+    concordium_std::AccountSignatures {
+        sigs: BTreeMap::from([(
+            0,
+            concordium_std::CredentialSignatures {
+                sigs: BTreeMap::from([(
+                    0,
+                    concordium_std::Signature::Ed25519(SignatureEd25519(signature.to_bytes())),
+                )]),
+            },
+        )]),
+    }
+}
+
+/// Helper function that builds the parameter for `voteFor`, signing `vote` with the account key `voter_secret_key`.
+fn vote_for_parameter(
+    voter: AccountAddress,
+    vote: RegisterParameter,
+    nonce: u64,
+    voter_secret_key: [u8; 32],
+) -> VoteForParameter {
+    let message = VoteForMessage {
+        contract_address: FIRST_CONTRACT,
+        nonce,
+        expiry: vote.expiry,
+        country_code: vote.country_code.clone(),
+    };
+    VoteForParameter {
+        voter,
+        vote,
+        voter_signature: account_signatures(voter, &message, voter_secret_key),
+    }
+}
+
+/// Helper function that creates the parameter for `permit`, where the `signer` signs to invoke
+/// the `entry_point` with the `payload`.
+fn permit_parameter<P: Serial>(
+    signer: AccountAddress,
+    entry_point: &str,
+    payload: &P,
+    nonce: u64,
+    signer_secret_key: [u8; 32],
+) -> PermitParam {
+    let message = PermitMessage {
+        contract_address: FIRST_CONTRACT,
+        nonce,
+        timestamp: Timestamp::from_timestamp_millis(10_000),
+        entry_point: OwnedEntrypointName::new_unchecked(String::from(entry_point)),
+        payload: to_bytes(payload),
+    };
+    PermitParam {
+        signature: account_signatures(signer, &message, signer_secret_key),
+        signer,
+        message,
+    }
+}
+
+/// Test that anyone can submit a vote signed by the voter, which is attributed to the voter and cannot be replayed.
+#[test]
+fn test_vote_for() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    // ACC_2 holds no CCD, but has an account key to sign its vote with.
+    chain.create_account(Account::new_with_keys(
+        ACC_2,
+        AccountBalance::new(Amount::zero(), Amount::zero(), Amount::zero()).expect("Valid balance"),
+        AccountAccessStructure {
+            keys: BTreeMap::from([(
+                CredentialIndex { index: 0 },
+                CredentialPublicKeys {
+                    keys: BTreeMap::from([(
+                        KeyIndex(0),
+                        VerifyKey::Ed25519VerifyKey(keypair(VOTER_SECRET_KEY).public),
+                    )]),
+                    threshold: SignatureThreshold::ONE,
+                },
+            )]),
+            threshold: AccountThreshold::ONE,
+        },
+    ));
+
+    // A vote signed with another key is rejected.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "voteFor",
+        &vote_for_parameter(
+            ACC_2,
+            RegisterParameterBuilder::new(ACC_2, "DE").build(),
+            0,
+            [8; 32],
+        ),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::InvalidVoterSignature);
+
+    // ACC_1 relays the vote of ACC_2, which is attributed to ACC_2.
+    let param = vote_for_parameter(
+        ACC_2,
+        RegisterParameterBuilder::new(ACC_2, "DE").build(),
+        0,
+        VOTER_SECRET_KEY,
+    );
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "voteFor",
+        &param,
+    )
+    .expect("Voting succeeds");
+    let vote_of_acc_2: Option<VotingOption> = invoke(&chain, contract, "getVote", &ACC_2);
+    assert_eq!(vote_of_acc_2, Some(String::from("DE")));
+    let vote_of_acc_1: Option<VotingOption> = invoke(&chain, contract, "getVote", &ACC_1);
+    assert_eq!(vote_of_acc_1, None);
+    assert_eq!(nonce(&chain, contract, ACC_2), 1);
+
+    // Replaying the vote fails, as the nonce has been incremented.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "voteFor",
+        &param,
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::InvalidVoterSignature);
+
+    // A vote for the new nonce changes the vote of ACC_2.
+    let param = vote_for_parameter(
+        ACC_2,
+        RegisterParameterBuilder::new(ACC_2, "DK")
+            .with_nonce(1)
+            .build(),
+        1,
+        VOTER_SECRET_KEY,
+    );
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "voteFor",
+        &param,
+    )
+    .expect("Voting succeeds");
+    let voting_view = view(&chain, contract);
+    assert_eq!(voting_view.tally.get("DE"), Some(&0));
+    assert_eq!(voting_view.tally.get("DK"), Some(&1));
+}
+
+/// Test that anyone can submit a registration and a vote permitted by the signer, which are attributed
+/// to the signer and cannot be replayed.
+#[test]
+fn test_permit() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    // ACC_2 holds no CCD, but has an account key to sign its permits with.
+    chain.create_account(Account::new_with_keys(
+        ACC_2,
+        AccountBalance::new(Amount::zero(), Amount::zero(), Amount::zero()).expect("Valid balance"),
+        AccountAccessStructure {
+            keys: BTreeMap::from([(
+                CredentialIndex { index: 0 },
+                CredentialPublicKeys {
+                    keys: BTreeMap::from([(
+                        KeyIndex(0),
+                        VerifyKey::Ed25519VerifyKey(keypair(VOTER_SECRET_KEY).public),
+                    )]),
+                    threshold: SignatureThreshold::ONE,
+                },
+            )]),
+            threshold: AccountThreshold::ONE,
+        },
+    ));
+    let registration = RegisterParameterBuilder::new(ACC_2, "DE").build();
+
+    // A permit signed with another key is rejected.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "permit",
+        &permit_parameter(ACC_2, "register", &registration, 0, [8; 32]),
+    )
+    .expect_err("Permit fails");
+    assert_eq!(voting_error(update_err), VotingError::InvalidVoterSignature);
+
+    // A permit for an entrypoint that cannot be permitted is rejected.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "permit",
+        &permit_parameter(ACC_2, "delegate", &ACC_1, 0, VOTER_SECRET_KEY),
+    )
+    .expect_err("Permit fails");
+    assert_eq!(voting_error(update_err), VotingError::UnsupportedPermit);
+
+    // ACC_1 relays the registration of ACC_2.
+    let param = permit_parameter(ACC_2, "register", &registration, 0, VOTER_SECRET_KEY);
+    let success = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "permit",
+        &param,
+    )
+    .expect("Permit succeeds");
+    assert!(events(&success).contains(&VotingEvent::Nonce(NonceEvent {
+        nonce: 0,
+        sponsoree: ACC_2,
+    })));
+    assert_eq!(nonce(&chain, contract, ACC_2), 1);
+
+    // Replaying the registration fails, as the nonce has been incremented.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "permit",
+        &param,
+    )
+    .expect_err("Permit fails");
+    assert_eq!(voting_error(update_err), VotingError::NonceMismatch);
+
+    // ACC_0 relays the vote of ACC_2, which is attributed to ACC_2.
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "permit",
+        &permit_parameter(ACC_2, "vote", &String::from("DE"), 1, VOTER_SECRET_KEY),
+    )
+    .expect("Permit succeeds");
+    let vote_of_acc_2: Option<VotingOption> = invoke(&chain, contract, "getVote", &ACC_2);
+    assert_eq!(vote_of_acc_2, Some(String::from("DE")));
+    let vote_of_acc_0: Option<VotingOption> = invoke(&chain, contract, "getVote", &ACC_0);
+    assert_eq!(vote_of_acc_0, None);
+    assert_eq!(nonce(&chain, contract, ACC_2), 2);
+
+    // An expired permit is rejected.
+    chain
+        .tick_block_time(Duration::from_millis(20_000))
+        .expect("Block time does not overflow");
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "permit",
+        &permit_parameter(ACC_2, "vote", &String::from("DK"), 2, VOTER_SECRET_KEY),
+    )
+    .expect_err("Permit fails");
+    assert_eq!(voting_error(update_err), VotingError::PermitExpired);
+}
+
+/// Test that accounts can only register until the `registration_end_time`, but can vote until the `end_time`.
+#[test]
+fn test_register_after_registration_end_time_fails() {
+    let registration_end_time = Timestamp::from_timestamp_millis(1000);
+    let (mut chain, initialization) = setup_chain_and_contract_with(
+        Timestamp::from_timestamp_millis(0),
+        InitParameterBuilder::default()
+            .registration_end_time(registration_end_time)
+            .build(),
+    );
+    let contract = initialization.contract_address;
+    assert_eq!(
+        view(&chain, contract).registration_end_time,
+        registration_end_time
+    );
+
+    // ACC_0 registers in time.
+    register(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Registering succeeds");
+
+    chain
+        .tick_block_time(Duration::from_millis(1001))
+        .expect("Block time does not overflow");
+
+    // ACC_1 is too late to register.
+    let update_err = register(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DE").build(),
+    )
+    .expect_err("Registering fails");
+    assert_eq!(voting_error(update_err), VotingError::RegistrationFinished);
+
+    // ACC_0 can still vote.
+    vote(&mut chain, contract, ACC_0, "DE").expect("Voting succeeds");
+}
+
+/// Test that accounts can register, but not vote, before the `start_time`.
+#[test]
+fn test_vote_before_start_time_fails() {
+    let (mut chain, initialization) = setup_chain_and_contract_with(
+        Timestamp::from_timestamp_millis(0),
+        InitParameterBuilder::default()
+            .start_time(Timestamp::from_timestamp_millis(1000))
+            .build(),
+    );
+    let contract = initialization.contract_address;
+    assert_eq!(view(&chain, contract).status, ElectionStatus::NotStarted);
+
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::VotingNotStarted);
+
+    chain
+        .tick_block_time(Duration::from_millis(1000))
+        .expect("Block time does not overflow");
+    assert_eq!(view(&chain, contract).status, ElectionStatus::Open);
+
+    // The registration from before the `start_time` is valid.
+    vote(&mut chain, contract, ACC_0, "DE").expect("Voting succeeds");
+}
+
+/// Test that accounts can only vote once if the election does not allow vote changes.
+#[test]
+fn test_vote_change_not_allowed() {
+    let mut param = InitParameterBuilder::default().build();
+    param.allow_vote_change = false;
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+
+    // Neither voting for another option nor for the same option again is allowed.
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DK")
+            .with_nonce(1)
+            .build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::AlreadyVoted);
+    let update_err = vote(&mut chain, contract, ACC_0, "DE").expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::AlreadyVoted);
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&1));
+
+    // Retracting the vote is not allowed either.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "retractVote",
+        &(),
+    )
+    .expect_err("Retracting fails");
+    assert_eq!(voting_error(update_err), VotingError::VoteChangeNotAllowed);
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&1));
+
+    // Other accounts can still vote.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DK").build(),
+    )
+    .expect("Voting succeeds");
+}
+
+/// Test that accounts can only change their vote after the `change_cooldown` has passed.
+#[test]
+fn test_vote_change_cooldown() {
+    let mut param = InitParameterBuilder::default().build();
+    param.change_cooldown = Some(Duration::from_millis(1000));
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    // ACC_0 registers for Germany and Denmark and votes on Germany.
+    for (nonce, country_code) in [(0, "DE"), (1, "DK")] {
+        register(
+            &mut chain,
+            contract,
+            ACC_0,
+            &RegisterParameterBuilder::new(ACC_0, country_code)
+                .with_nonce(nonce)
+                .build(),
+        )
+        .expect("Registering succeeds");
+    }
+    vote(&mut chain, contract, ACC_0, "DE").expect("Voting succeeds");
+
+    // Changing the vote right away fails.
+    chain
+        .tick_block_time(Duration::from_millis(999))
+        .expect("Block time does not overflow");
+    let update_err = vote(&mut chain, contract, ACC_0, "DK").expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::VoteChangeTooSoon);
+
+    // Changing the vote after the cooldown succeeds, and starts a new cooldown.
+    chain
+        .tick_block_time(Duration::from_millis(1))
+        .expect("Block time does not overflow");
+    vote(&mut chain, contract, ACC_0, "DK").expect("Voting succeeds");
+    let update_err = vote(&mut chain, contract, ACC_0, "DE").expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::VoteChangeTooSoon);
+    assert_eq!(view(&chain, contract).tally.get("DK"), Some(&1));
+}
+
+/// Test that accounts can only change their vote `max_changes` times.
+#[test]
+fn test_max_vote_changes() {
+    let mut param = InitParameterBuilder::default().build();
+    param.max_changes = Some(2);
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    // ACC_0 registers for Germany and Denmark.
+    for (nonce, country_code) in [(0, "DE"), (1, "DK")] {
+        register(
+            &mut chain,
+            contract,
+            ACC_0,
+            &RegisterParameterBuilder::new(ACC_0, country_code)
+                .with_nonce(nonce)
+                .build(),
+        )
+        .expect("Registering succeeds");
+    }
+
+    // The first vote is not a change, so ACC_0 can vote three times.
+    for country_code in ["DE", "DK", "DE"] {
+        vote(&mut chain, contract, ACC_0, country_code).expect("Voting succeeds");
+    }
+    let update_err = vote(&mut chain, contract, ACC_0, "DK").expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::TooManyVoteChanges);
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&1));
+}
+
+/// Test that accounts can retract their vote, which removes it from the tally.
+#[test]
+fn test_retract_vote() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    // ACC_0 cannot retract a vote before voting.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "retractVote",
+        &(),
+    )
+    .expect_err("Retracting fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAVoter);
+
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+
+    let retraction = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "retractVote",
+        &(),
+    )
+    .expect("Retracting succeeds");
+    assert_eq!(
+        events(&retraction),
+        [VotingEvent::VoteRetracted {
+            voter: ACC_0,
+            option: String::from("DE"),
+        }]
+    );
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&0));
+    let voter_count: u32 = invoke(&chain, contract, "voterCount", &());
+    assert_eq!(voter_count, 0);
+    let has_voted: bool = invoke(&chain, contract, "hasVoted", &ACC_0);
+    assert!(!has_voted);
+
+    // The registration stays valid, so ACC_0 can vote again.
+    vote(&mut chain, contract, ACC_0, "DE").expect("Voting succeeds");
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&1));
+
+    // Votes cannot be retracted after the `end_time`.
+    finish_election(&mut chain);
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "retractVote",
+        &(),
+    )
+    .expect_err("Retracting fails");
+    assert_eq!(voting_error(update_err), VotingError::VotingFinished);
+}
+
+/// Test that write-ins are pending until the admin approves them, which counts the pending votes.
+#[test]
+fn test_write_in() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    // Voting options cannot be written in.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "voteWriteIn",
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect_err("Writing in fails");
+    assert_eq!(voting_error(update_err), VotingError::DuplicateOption);
+
+    // ACC_0 votes on Germany, and then both accounts write in Sweden.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+    for (voter, nonce) in [(ACC_0, 1), (ACC_1, 0)] {
+        let write_in = update(
+            &mut chain,
+            contract,
+            Address::Account(voter),
+            "voteWriteIn",
+            &RegisterParameterBuilder::new(voter, "SE")
+                .with_nonce(nonce)
+                .build(),
+        )
+        .expect("Writing in succeeds");
+        assert_eq!(
+            events(&write_in),
+            [VotingEvent::WriteInSubmitted {
+                voter,
+                option: String::from("SE"),
+            }]
+        );
+    }
+    // The write-ins are not counted yet.
+    let voting_view = view(&chain, contract);
+    assert_eq!(voting_view.tally.get("DE"), Some(&1));
+    assert_eq!(voting_view.tally.get("SE"), None);
+
+    // Only the admin can approve a write-in, and only if votes for it are pending.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "approveWriteIn",
+        &VotingOption::from("SE"),
+    )
+    .expect_err("Approving fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "approveWriteIn",
+        &VotingOption::from("NO"),
+    )
+    .expect_err("Approving fails");
+    assert_eq!(voting_error(update_err), VotingError::NoPendingWriteIn);
+
+    let approval = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "approveWriteIn",
+        &VotingOption::from("SE"),
+    )
+    .expect("Approving succeeds");
+    assert_eq!(
+        events(&approval)[0],
+        VotingEvent::OptionAdded {
+            option: String::from("SE")
+        }
+    );
+    let voting_view = view(&chain, contract);
+    assert_eq!(voting_view.tally.get("DE"), Some(&0));
+    assert_eq!(voting_view.tally.get("SE"), Some(&2));
+    let voter_count: u32 = invoke(&chain, contract, "voterCount", &());
+    assert_eq!(voter_count, 2);
+
+    // The voters are eligible for the new option, so ACC_0 can change back and forth.
+    vote(&mut chain, contract, ACC_0, "DE").expect("Voting succeeds");
+    vote(&mut chain, contract, ACC_0, "SE").expect("Voting succeeds");
+}
+
+/// Test that a voting option cannot receive more than `max_votes_per_option` votes.
+#[test]
+fn test_option_full() {
+    let mut param = InitParameterBuilder::default().build();
+    param.max_votes_per_option = Some(1);
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+
+    // Germany is full.
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DE").build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::OptionFull);
+
+    // ACC_0 can vote for Germany again, as its vote is already counted.
+    vote(&mut chain, contract, ACC_0, "DE").expect("Voting succeeds");
+
+    // Once ACC_0 moves its vote to Denmark, ACC_1 can vote for Germany.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DK")
+            .with_nonce(1)
+            .build(),
+    )
+    .expect("Voting succeeds");
+    vote(&mut chain, contract, ACC_1, "DE").expect("Voting succeeds");
+}
+
+/// Test that a runoff between the two options with the most votes can be started if no option has a majority.
+#[test]
+fn test_runoff() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    let runoff_end_time = Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH + 1000);
+
+    // ACC_0 votes for Germany and ACC_1 for Denmark, so neither has a majority.
+    for (account, country_code) in [(ACC_0, "DE"), (ACC_1, "DK")] {
+        register_and_vote(
+            &mut chain,
+            contract,
+            account,
+            &RegisterParameterBuilder::new(account, country_code).build(),
+        )
+        .expect("Voting succeeds");
+    }
+
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "startRunoff",
+        &runoff_end_time,
+    )
+    .expect_err("Starting a runoff fails");
+    assert_eq!(voting_error(update_err), VotingError::NotFinalized);
+
+    finish_election(&mut chain);
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "finalize",
+        &(),
+    )
+    .expect("Finalizing succeeds");
+
+    // Only the admin can start a runoff.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "startRunoff",
+        &runoff_end_time,
+    )
+    .expect_err("Starting a runoff fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+
+    // On a tie for the second place, the option listed first is in the runoff.
+    let runoff = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "startRunoff",
+        &runoff_end_time,
+    )
+    .expect("Starting a runoff succeeds");
+    assert_eq!(
+        events(&runoff),
+        [VotingEvent::RunoffStarted {
+            options: vec![String::from("DK"), String::from("DE")],
+            end_time: runoff_end_time,
+        }]
+    );
+    let first_round: Option<ElectionResult> = invoke(&chain, contract, "viewFirstRound", &());
+    assert_eq!(
+        first_round
+            .expect("The first round is archived")
+            .outcome
+            .winner,
+        Some(String::from("DK"))
+    );
+
+    // The ballots are discarded, and only the two options can be voted for.
+    let view = view(&chain, contract);
+    assert_eq!(
+        view.tally,
+        BTreeMap::from([(String::from("DK"), 0), (String::from("DE"), 0)])
+    );
+    assert_eq!(view.status, ElectionStatus::Open);
+    let voter_count: u32 = invoke(&chain, contract, "voterCount", &());
+    assert_eq!(voter_count, 0);
+    let update_err = vote(&mut chain, contract, ACC_0, "IT").expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidVotingOption {
+            option: String::from("IT")
+        }
+    );
+
+    // The registrations are kept, so the accounts can vote without new signatures.
+    vote(&mut chain, contract, ACC_0, "DE").expect("Voting succeeds");
+    vote(&mut chain, contract, ACC_1, "DE").expect("Voting succeeds");
+
+    chain
+        .tick_block_time(Duration::from_millis(1000))
+        .expect("Block time does not overflow");
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "finalize",
+        &(),
+    )
+    .expect("Finalizing succeeds");
+    let outcome: ElectionOutcome = invoke(&chain, contract, "getResult", &());
+    assert_eq!(outcome.winner, Some(String::from("DE")));
+    assert_eq!(outcome.winner_count, 2);
+
+    // There is at most one runoff.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "startRunoff",
+        &Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH + 2000),
+    )
+    .expect_err("Starting a runoff fails");
+    assert_eq!(voting_error(update_err), VotingError::RunoffAlreadyStarted);
+}
+
+/// Test that no runoff can be started if an option has a majority.
+#[test]
+fn test_runoff_not_needed() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+    finish_election(&mut chain);
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "finalize",
+        &(),
+    )
+    .expect("Finalizing succeeds");
+
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "startRunoff",
+        &Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH + 1000),
+    )
+    .expect_err("Starting a runoff fails");
+    assert_eq!(voting_error(update_err), VotingError::RunoffNotNeeded);
+}
+
+/// Test that a recurring election archives the result and starts the next round on finalization.
+#[test]
+fn test_recurring_election() {
+    let (mut chain, module_reference) = setup_chain_and_module(Timestamp::from_timestamp_millis(0));
+    let day = Duration::from_days(1);
+
+    // The recurrence must be positive, and cannot be combined with quadratic voting.
+    let mut param = InitParameterBuilder::default().build();
+    param.recurrence = Some(Duration::from_millis(0));
+    init_contract(&mut chain, module_reference, &param)
+        .expect_err("Initialization with a zero recurrence fails");
+    param.recurrence = Some(day);
+    param.quadratic_voting = true;
+    init_contract(&mut chain, module_reference, &param)
+        .expect_err("Initialization of a recurring quadratic election fails");
+
+    param.quadratic_voting = false;
+    let contract = init_contract(&mut chain, module_reference, &param)
+        .expect("Initialization should succeed")
+        .contract_address;
+
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+    finish_election(&mut chain);
+    let finalization = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "finalize",
+        &(),
+    )
+    .expect("Finalizing succeeds");
+    let next_end_time = Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH + day.millis());
+    assert_eq!(
+        events(&finalization),
+        [
+            VotingEvent::Finalized {
+                winner: Some(String::from("DE"))
+            },
+            VotingEvent::RoundStarted {
+                round: 1,
+                end_time: next_end_time,
+            },
+        ]
+    );
+
+    // The result of the first round is archived.
+    let first_round: Option<ElectionResult> = invoke(&chain, contract, "viewHistory", &0u32);
+    let first_round = first_round.expect("The first round is archived");
+    assert_eq!(first_round.outcome.winner, Some(String::from("DE")));
+    assert_eq!(first_round.tally.get("DE"), Some(&1));
+    let second_round: Option<ElectionResult> = invoke(&chain, contract, "viewHistory", &1u32);
+    assert_eq!(second_round, None);
+
+    // The next round is open with the same options and an empty tally.
+    let view = view(&chain, contract);
+    assert_eq!(view.end_time, next_end_time);
+    assert_eq!(view.status, ElectionStatus::Open);
+    assert_eq!(
+        view.tally,
+        BTreeMap::from([
+            (String::from("DK"), 0),
+            (String::from("DE"), 0),
+            (String::from("IT"), 0)
+        ])
+    );
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "getResult",
+        &(),
+    )
+    .expect_err("Getting the result fails");
+    assert_eq!(voting_error(update_err), VotingError::NotFinalized);
+
+    // The registration is kept, so the account can vote again.
+    vote(&mut chain, contract, ACC_0, "DK").expect("Voting succeeds");
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "finalize",
+        &(),
+    )
+    .expect_err("Finalizing fails");
+    assert_eq!(voting_error(update_err), VotingError::VotingNotFinished);
+}
+
+/// Test that the result reports whether the `quorum` was reached.
+#[test]
+fn test_finalize_quorum() {
+    let (mut chain, module_reference) = setup_chain_and_module(Timestamp::from_timestamp_millis(0));
+    let mut param = InitParameterBuilder::default().build();
+    param.quorum = 2;
+    let reached_contract = init_contract(&mut chain, module_reference, &param)
+        .expect("Initialization should succeed")
+        .contract_address;
+    let missed_contract = init_contract(&mut chain, module_reference, &param)
+        .expect("Initialization should succeed")
+        .contract_address;
+
+    // Both accounts vote in the first election, but only ACC_0 votes in the second.
+    for (contract, voter) in [
+        (reached_contract, ACC_0),
+        (reached_contract, ACC_1),
+        (missed_contract, ACC_0),
+    ] {
+        register_and_vote(
+            &mut chain,
+            contract,
+            voter,
+            &RegisterParameterBuilder::new(voter, "DE")
+                .for_contract(contract)
+                .build(),
+        )
+        .expect("Voting succeeds");
+    }
+
+    finish_election(&mut chain);
+
+    for (contract, quorum_reached) in [(reached_contract, true), (missed_contract, false)] {
+        update(
+            &mut chain,
+            contract,
+            Address::Account(ACC_0),
+            "finalize",
+            &(),
+        )
+        .expect("Finalizing succeeds");
+        let outcome: ElectionOutcome = invoke(&chain, contract, "getResult", &());
+        assert_eq!(outcome.quorum_reached, quorum_reached);
+        // The winner is still reported.
+        assert_eq!(outcome.winner, Some(String::from("DE")));
+    }
+}
+
+/// Test that ties are broken according to the `tie_breaking` policy.
+#[test]
+fn test_finalize_tie_breaking() {
+    for (tie_breaking, winner) in [
+        (TieBreakingPolicy::FirstListed, Some("DK")),
+        (TieBreakingPolicy::EarliestToReachCount, Some("IT")),
+        (TieBreakingPolicy::NoWinner, None),
+    ] {
+        let mut param = InitParameterBuilder::default().build();
+        param.tie_breaking = tie_breaking;
+        let (mut chain, initialization) =
+            setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+        let contract = initialization.contract_address;
+
+        // ACC_0 votes on Italy before ACC_1 votes on Denmark, which is listed before Italy.
+        for (voter, country_code) in [(ACC_0, "IT"), (ACC_1, "DK")] {
+            register_and_vote(
+                &mut chain,
+                contract,
+                voter,
+                &RegisterParameterBuilder::new(voter, country_code).build(),
+            )
+            .expect("Voting succeeds");
+        }
+
+        finish_election(&mut chain);
+        let finalization = update(
+            &mut chain,
+            contract,
+            Address::Account(ACC_0),
+            "finalize",
+            &(),
+        )
+        .expect("Finalizing succeeds");
+        assert_eq!(
+            events(&finalization),
+            [VotingEvent::Finalized {
+                winner: winner.map(String::from)
+            }]
+        );
+        let outcome: ElectionOutcome = invoke(&chain, contract, "getResult", &());
+        assert_eq!(outcome.winner, winner.map(String::from));
+        assert_eq!(outcome.tied, [String::from("DK"), String::from("IT")]);
+    }
+}
+
+/// Test that the tally sums the weights of the voters in a weighted election.
+#[test]
+fn test_weighted_voting() {
+    let mut param = InitParameterBuilder::default().build();
+    param.weights = Some(vec![(ACC_0, 10)]);
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    // ACC_0 votes on Germany and changes its vote to Denmark, which moves its whole weight.
+    for (nonce, country_code) in [(0, "DE"), (1, "DK")] {
+        register_and_vote(
+            &mut chain,
+            contract,
+            ACC_0,
+            &RegisterParameterBuilder::new(ACC_0, country_code)
+                .with_nonce(nonce)
+                .build(),
+        )
+        .expect("Voting succeeds");
+    }
+    let voting_view = view(&chain, contract);
+    assert_eq!(voting_view.tally.get("DE"), Some(&0));
+    assert_eq!(voting_view.tally.get("DK"), Some(&10));
+
+    // ACC_1 has no weight.
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DK").build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::NoVotingWeight);
+}
+
+/// Test that a vote is rejected if its weight would overflow the tally.
+#[test]
+fn test_weighted_voting_overflow_fails() {
+    let mut param = InitParameterBuilder::default().build();
+    param.weights = Some(vec![(ACC_0, u64::MAX), (ACC_1, 1)]);
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DE").build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::WeightOverflow);
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&u64::MAX));
+}
+
+/// Test that voting fails in an election weighted by a token if the token
+/// balance cannot be queried.
+#[test]
+fn test_token_weighted_voting_without_token_contract_fails() {
+    let mut param = InitParameterBuilder::default().build();
+    param.token_weights = Some(TokenWeights {
+        contract: ContractAddress::new(1000, 0),
+        token_id: TokenId(vec![1]),
+    });
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::TokenBalanceQueryFailed
+    );
+}
+
+/// Test that initializing with both fixed weights and token weights fails.
+#[test]
+fn test_init_with_conflicting_weights_fails() {
+    let (mut chain, module_reference) = setup_chain_and_module(Timestamp::from_timestamp_millis(0));
+    let mut param = InitParameterBuilder::default().build();
+    param.weights = Some(vec![(ACC_0, 10)]);
+    param.token_weights = Some(TokenWeights {
+        contract: ContractAddress::new(1000, 0),
+        token_id: TokenId(vec![1]),
+    });
+
+    init_contract(&mut chain, module_reference, &param)
+        .expect_err("Initialization with conflicting weights fails");
+}
+
+/// Test that the votes in a quadratic election are weighted by the square root of the deposit.
+#[test]
+fn test_quadratic_voting() {
+    let mut param = InitParameterBuilder::default().build();
+    param.quadratic_voting = true;
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+    register(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Registering succeeds");
+
+    // Voting requires a deposit.
+    let update_err = vote(&mut chain, contract, ACC_0, "DE").expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::DepositRequired);
+    let update_err = vote_with_deposit(&mut chain, contract, ACC_0, "DE", Amount::zero())
+        .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::NoVotingWeight);
+
+    // A deposit of 4 CCD, i.e. 4000000 microCCD, weighs 2000.
+    vote_with_deposit(&mut chain, contract, ACC_0, "DE", Amount::from_ccd(4))
+        .expect("Voting succeeds");
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&2000));
+
+    // Depositing 5 CCD more increases the weight to the square root of 9 CCD.
+    vote_with_deposit(&mut chain, contract, ACC_0, "DE", Amount::from_ccd(5))
+        .expect("Voting succeeds");
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&3000));
+    assert_eq!(chain.contract_balance(contract), Some(Amount::from_ccd(9)));
+}
+
+/// Test that voters can withdraw their deposit once the election has finished.
+#[test]
+fn test_withdraw_deposit() {
+    let mut param = InitParameterBuilder::default().build();
+    param.quadratic_voting = true;
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+    register(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Registering succeeds");
+    vote_with_deposit(&mut chain, contract, ACC_0, "DE", Amount::from_ccd(4))
+        .expect("Voting succeeds");
+
+    // The deposit is locked until the `end_time`.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "withdraw",
+        &(),
+    )
+    .expect_err("Withdrawing fails");
+    assert_eq!(voting_error(update_err), VotingError::VotingNotFinished);
+
+    finish_election(&mut chain);
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "withdraw",
+        &(),
+    )
+    .expect("Withdrawing succeeds");
+    assert_eq!(chain.contract_balance(contract), Some(Amount::zero()));
+    // The vote remains counted.
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&2000));
+
+    // The deposit can only be withdrawn once, and ACC_1 has not deposited.
+    for account in [ACC_0, ACC_1] {
+        let update_err = update(
+            &mut chain,
+            contract,
+            Address::Account(account),
+            "withdraw",
+            &(),
+        )
+        .expect_err("Withdrawing fails");
+        assert_eq!(voting_error(update_err), VotingError::NothingToWithdraw);
+    }
+}
+
+/// Test that the representative votes with the delegated voting power.
+#[test]
+fn test_delegation() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    // Delegations cannot form a cycle.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "delegate",
+        &ACC_1,
+    )
+    .expect_err("Delegating fails");
+    assert_eq!(voting_error(update_err), VotingError::DelegationCycle);
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "delegate",
+        &ACC_0,
+    )
+    .expect("Delegating succeeds");
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "delegate",
+        &ACC_1,
+    )
+    .expect_err("Delegating fails");
+    assert_eq!(voting_error(update_err), VotingError::DelegationCycle);
+
+    // ACC_0 votes with the voting power of both accounts.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&2));
+
+    // ACC_1 cannot vote while it delegates.
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DK").build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::VotesDelegated);
+
+    // After undelegating, ACC_1 votes itself.
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "undelegate",
+        &(),
+    )
+    .expect("Undelegating succeeds");
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&1));
+    vote(&mut chain, contract, ACC_1, "DK").expect("Voting succeeds");
+    assert_eq!(view(&chain, contract).tally.get("DK"), Some(&1));
+
+    // Accounts that have voted cannot delegate, and undelegating requires a delegation.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "delegate",
+        &ACC_0,
+    )
+    .expect_err("Delegating fails");
+    assert_eq!(voting_error(update_err), VotingError::AlreadyVoted);
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "undelegate",
+        &(),
+    )
+    .expect_err("Undelegating fails");
+    assert_eq!(voting_error(update_err), VotingError::NotDelegated);
+}
+
+/// Test that a delegation is rejected if the delegated weight would overflow
+/// the vote of the representative.
+#[test]
+fn test_delegation_overflow_fails() {
+    let mut param = InitParameterBuilder::default().build();
+    param.weights = Some(vec![(ACC_0, u64::MAX), (ACC_1, 1)]);
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "delegate",
+        &ACC_0,
+    )
+    .expect_err("Delegating fails");
+    assert_eq!(voting_error(update_err), VotingError::WeightOverflow);
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&u64::MAX));
+}
+
+/// Test that delegation chains are bounded by `MAX_DELEGATION_DEPTH`, from both ends.
+#[test]
+fn test_delegation_depth() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    let accounts: Vec<AccountAddress> = (0..MAX_DELEGATION_DEPTH as u8 + 3)
+        .map(|i| {
+            let account = AccountAddress([i + 2; 32]);
+            chain.create_account(Account::new(account, Amount::from_ccd(10000)));
+            account
+        })
+        .collect();
+    let delegate = |chain: &mut Chain, from: usize, to: usize| {
+        update(
+            chain,
+            contract,
+            Address::Account(accounts[from]),
+            "delegate",
+            &accounts[to],
+        )
+    };
+
+    // Build a chain of the maximum depth, adding the delegations at both ends.
+    let middle = MAX_DELEGATION_DEPTH as usize / 2;
+    for i in middle..MAX_DELEGATION_DEPTH as usize {
+        delegate(&mut chain, i, i + 1).expect("Delegating succeeds");
+    }
+    for i in (0..middle).rev() {
+        delegate(&mut chain, i, i + 1).expect("Delegating succeeds");
+    }
+
+    // Extending the chain at either end is too deep.
+    let last = MAX_DELEGATION_DEPTH as usize;
+    let update_err = delegate(&mut chain, last, last + 1).expect_err("Delegating fails");
+    assert_eq!(voting_error(update_err), VotingError::DelegationTooDeep);
+    let update_err = delegate(&mut chain, last + 2, 0).expect_err("Delegating fails");
+    assert_eq!(voting_error(update_err), VotingError::DelegationTooDeep);
+
+    // Undelegating shortens the chain, so it can be extended again.
+    update(
+        &mut chain,
+        contract,
+        Address::Account(accounts[0]),
+        "undelegate",
+        &(),
+    )
+    .expect("Undelegating succeeds");
+    delegate(&mut chain, last, last + 1).expect("Delegating succeeds");
+    let update_err = delegate(&mut chain, 0, 1).expect_err("Delegating fails");
+    assert_eq!(voting_error(update_err), VotingError::DelegationTooDeep);
+}
+
+/// Test that encrypted ballots are stored without being counted, and that the tally authority finalizes the
+/// election by posting the decrypted tally.
+#[test]
+fn test_encrypted_ballots() {
+    let mut param = InitParameterBuilder::default().build();
+    param.tally_authority = Some(TallyAuthority {
+        account: ACC_1,
+        encryption_key: [5; 32],
+    });
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+    // The contract does not interpret the ciphertexts, so any bytes will do.
+    let ballot = vec![
+        ElGamalCiphertext {
+            c1: [1; 32],
+            c2: [2; 32],
+        };
+        3
+    ];
+
+    register(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Registering succeeds");
+
+    // Public votes are rejected.
+    let update_err = vote(&mut chain, contract, ACC_0, "DE").expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::EncryptedElection);
+
+    // A ballot needs one ciphertext per voting option.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "voteEncrypted",
+        &ballot[..2].to_vec(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::InvalidBallot);
+
+    // ACC_1 has not registered for any voting option.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "voteEncrypted",
+        &ballot,
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::NotRegistered);
+
+    // ACC_0 casts an encrypted ballot, which is not counted in the tally.
+    let voting = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "voteEncrypted",
+        &ballot,
+    )
+    .expect("Voting succeeds");
+    assert_eq!(
+        events(&voting),
+        [VotingEvent::EncryptedVote { voter: ACC_0 }]
+    );
+    let has_voted: bool = invoke(&chain, contract, "hasVoted", &ACC_0);
+    assert!(has_voted);
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&0));
+    let ballots: Vec<(AccountAddress, Vec<ElGamalCiphertext>)> = invoke(
+        &chain,
+        contract,
+        "viewEncryptedBallots",
+        &ViewBallotsParameter { skip: 0, limit: 10 },
+    );
+    assert_eq!(ballots, [(ACC_0, ballot)]);
+
+    finish_election(&mut chain);
+
+    // Only the tally authority can finalize the election.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "finalize",
+        &(),
+    )
+    .expect_err("Finalizing fails");
+    assert_eq!(voting_error(update_err), VotingError::EncryptedElection);
+    let tally = PostTallyParameter {
+        tally: vec![0, 1, 0],
+        proof: vec![42],
+    };
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "postTally",
+        &tally,
+    )
+    .expect_err("Posting the tally fails");
+    assert_eq!(voting_error(update_err), VotingError::NotTallyAuthority);
+
+    // The tally cannot count more votes than there are voters.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "postTally",
+        &PostTallyParameter {
+            tally: vec![1, 1, 0],
+            proof: vec![42],
+        },
+    )
+    .expect_err("Posting the tally fails");
+    assert_eq!(voting_error(update_err), VotingError::InvalidTally);
+
+    // The tally authority posts the decrypted tally, which finalizes the election.
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "postTally",
+        &tally,
+    )
+    .expect("Posting the tally succeeds");
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&1));
+    let outcome: ElectionOutcome = invoke(&chain, contract, "getResult", &());
+    assert_eq!(outcome.winner, Some(String::from("DE")));
+    let proof: Option<Vec<u8>> = invoke(&chain, contract, "viewTallyProof", &());
+    assert_eq!(proof, Some(vec![42]));
+}
+
+/// Test that only accounts with a valid Merkle proof of their membership of the allow-list can register in a closed
+/// election.
+#[test]
+fn test_allow_list() {
+    // Build a tree over ACC_0, ACC_1, and two other accounts.
+    let leaf = |account: AccountAddress| -> MerkleNode {
+        Sha256::digest([&[0u8][..], &account.0].concat()).into()
+    };
+    let parent = |a: MerkleNode, b: MerkleNode| -> MerkleNode {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        Sha256::digest([&[1u8][..], &left, &right].concat()).into()
+    };
+    let leaves = [
+        leaf(ACC_0),
+        leaf(ACC_1),
+        leaf(AccountAddress([3; 32])),
+        leaf(AccountAddress([4; 32])),
+    ];
+    let left = parent(leaves[0], leaves[1]);
+    let right = parent(leaves[2], leaves[3]);
+    let root = parent(left, right);
+
+    // An open election has no allow-list.
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let proof_acc_0 = AllowListProof {
+        account: ACC_0,
+        proof: vec![leaves[1], right],
+    };
+    let update_err = update(
+        &mut chain,
+        initialization.contract_address,
+        Address::Account(ACC_0),
+        "proveAllowListed",
+        &proof_acc_0,
+    )
+    .expect_err("Proving fails");
+    assert_eq!(voting_error(update_err), VotingError::NoAllowList);
+
+    let mut param = InitParameterBuilder::default().build();
+    param.allow_list_root = Some(root);
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    // Registering without proving membership fails.
+    let update_err = register(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect_err("Registering fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAllowListed);
+
+    // The proof of ACC_0 does not prove the membership of ACC_2.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "proveAllowListed",
+        &AllowListProof {
+            account: ACC_2,
+            proof: proof_acc_0.proof.clone(),
+        },
+    )
+    .expect_err("Proving fails");
+    assert_eq!(voting_error(update_err), VotingError::InvalidMerkleProof);
+
+    // Anyone can submit the proof for ACC_0, after which it can register and vote.
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "proveAllowListed",
+        &proof_acc_0,
+    )
+    .expect("Proving succeeds");
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Registering and voting succeed");
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&1));
+
+    // ACC_1 has not proven its membership yet, so it cannot delegate.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "delegate",
+        &ACC_0,
+    )
+    .expect_err("Delegating fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAllowListed);
+}
+
+/// Test registering with a CIS-4 credential instead of signatures from the verifiers.
+#[test]
+fn test_register_credential() {
+    let credential_registration = |account: AccountAddress, contract: ContractAddress| {
+        let keypair = keypair(VOTER_SECRET_KEY);
+        let message = CredentialMessage {
+            contract_address: contract,
+            account,
+        };
+        let signature = keypair.sign(&to_bytes(&message));
+        CredentialRegistration {
+            holder_id: PublicKeyEd25519(keypair.public.to_bytes()),
+            signature: SignatureEd25519(signature.to_bytes()),
+        }
+    };
+
+    // An election without a credential registry does not accept credentials.
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "registerCredential",
+        &credential_registration(ACC_0, contract),
+    )
+    .expect_err("Registering fails");
+    assert_eq!(voting_error(update_err), VotingError::NoCredentialRegistry);
+
+    // The verifiers are not needed if the credentials are checked on-chain.
+    let mut param = InitParameterBuilder::default().build();
+    param.credential_registry = Some(ContractAddress::new(1000, 0));
+    param.verifier_public_keys = vec![];
+    param.verifier_threshold = 0;
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    // Signatures from the verifiers are not accepted.
+    let update_err = register(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect_err("Registering fails");
+    assert_eq!(voting_error(update_err), VotingError::CredentialRequired);
+
+    // The holder must have bound the credential to the sender.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "registerCredential",
+        &credential_registration(ACC_0, contract),
+    )
+    .expect_err("Registering fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Message
+        }
+    );
+
+    // The status of the credential cannot be queried without a registry contract.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "registerCredential",
+        &credential_registration(ACC_0, contract),
+    )
+    .expect_err("Registering fails");
+    assert_eq!(voting_error(update_err), VotingError::CredentialQueryFailed);
+}
+
+/// Test that write-ins follow the same rules for changing a vote as votes for a voting option.
+#[test]
+fn test_write_in_vote_changes() {
+    let write_in = |chain: &mut Chain, contract, nonce, country_code| {
+        update(
+            chain,
+            contract,
+            Address::Account(ACC_0),
+            "voteWriteIn",
+            &RegisterParameterBuilder::new(ACC_0, country_code)
+                .with_nonce(nonce)
+                .build(),
+        )
+    };
+
+    // A write-in is a vote change, so it is not allowed after voting in a one-shot election.
+    let mut param = InitParameterBuilder::default().build();
+    param.allow_vote_change = false;
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+    let update_err = write_in(&mut chain, contract, 1, "SE").expect_err("Writing in fails");
+    assert_eq!(voting_error(update_err), VotingError::AlreadyVoted);
+
+    // Every write-in after the first vote counts towards `max_changes`.
+    let mut param = InitParameterBuilder::default().build();
+    param.max_changes = Some(1);
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+    write_in(&mut chain, contract, 0, "SE").expect("Writing in succeeds");
+    write_in(&mut chain, contract, 1, "NO").expect("Writing in succeeds");
+    let update_err = write_in(&mut chain, contract, 2, "FI").expect_err("Writing in fails");
+    assert_eq!(voting_error(update_err), VotingError::TooManyVoteChanges);
+
+    // A write-in has to wait for the `change_cooldown` like any other vote.
+    let mut param = InitParameterBuilder::default().build();
+    param.change_cooldown = Some(Duration::from_millis(1000));
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+    let update_err = write_in(&mut chain, contract, 1, "SE").expect_err("Writing in fails");
+    assert_eq!(voting_error(update_err), VotingError::VoteChangeTooSoon);
+    chain
+        .tick_block_time(Duration::from_millis(1000))
+        .expect("Block time does not overflow");
+    write_in(&mut chain, contract, 1, "SE").expect("Writing in succeeds");
+}
+
+/// Test that a write-in cannot be approved after the `end_time`, when its votes could no longer be
+/// cast.
+#[test]
+fn test_approve_write_in_after_end_time() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "voteWriteIn",
+        &RegisterParameterBuilder::new(ACC_1, "SE").build(),
+    )
+    .expect("Writing in succeeds");
+
+    finish_election(&mut chain);
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "approveWriteIn",
+        &VotingOption::from("SE"),
+    )
+    .expect_err("Approving fails");
+    assert_eq!(voting_error(update_err), VotingError::VotingFinished);
+    assert_eq!(view(&chain, contract).tally.get("SE"), None);
+}
+
+/// Test voting with a signature from a verifier that uses ECDSA on the secp256k1 curve.
+#[test]
+fn test_vote_with_ecdsa_verifier() {
+    let verifier_key = [9; 32];
+    let mut param = InitParameterBuilder::default().build();
+    param.verifier_public_keys = vec![ecdsa_public_key(verifier_key)];
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    // An Ed25519 signature is not valid for an ECDSA key.
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Key
+        }
+    );
+
+    // An ECDSA signature on the message is.
+    let message = to_bytes(&SignatureMessageData {
+        account_address: ACC_0,
+        contract_address: contract,
+        election_id: String::from(ELECTION_ID),
+        expiry: Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH),
+        nonce: 0,
+        statement_hash: STATEMENT_HASH,
+        country_code: String::from("DE"),
+    });
+    let param = RegisterParameter {
+        country_code: String::from("DE"),
+        expiry: Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH),
+        signatures: BTreeMap::from([(0, ecdsa_sign(verifier_key, &message))]),
+    };
+    register_and_vote(&mut chain, contract, ACC_0, &param).expect("Voting succeeds");
+}
+
+/// Test that the initialization fails if the verifier threshold cannot be met.
+#[test]
+fn test_init_with_invalid_verifier_threshold_fails() {
+    let (mut chain, module_reference) = setup_chain_and_module(Timestamp::from_timestamp_millis(0));
+
+    for threshold in [0, 2] {
+        init_contract(
+            &mut chain,
+            module_reference,
+            &InitParameterBuilder::default()
+                .verifiers(&[VERIFIER_SECRET_KEY], threshold)
+                .build(),
+        )
+        .expect_err("Initialization with an invalid threshold fails");
+    }
+}
+
+/// Test that accounts can vote without registering in an election without verifiers.
+#[test]
+fn test_vote_without_verifiers() {
+    let (mut chain, initialization) = setup_chain_and_contract_with(
+        Timestamp::from_timestamp_millis(0),
+        InitParameterBuilder::default().verifiers(&[], 0).build(),
+    );
+    let contract = initialization.contract_address;
+
+    vote(&mut chain, contract, ACC_0, "DE").expect("Voting succeeds");
+    vote(&mut chain, contract, ACC_1, "DK").expect("Voting succeeds");
+    let update_err = vote(&mut chain, contract, ACC_1, "IN").expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidVotingOption {
+            option: String::from("IN")
+        }
+    );
+
+    let view = view(&chain, contract);
+    assert_eq!(view.tally.get("DE"), Some(&1));
+    assert_eq!(view.tally.get("DK"), Some(&1));
+}
+
+/// Test that initializing an election that cannot receive votes fails.
+#[test]
+fn test_init_with_invalid_options_or_end_time_fails() {
+    let (mut chain, module_reference) =
+        setup_chain_and_module(Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH));
+
+    for param in [
+        InitParameterBuilder::default().options(&[]).build(),
+        InitParameterBuilder::default()
+            .options(&["DE", "DK", "DE"])
+            .build(),
+        InitParameterBuilder::default()
+            .end_time(Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH - 1))
+            .build(),
+    ] {
+        init_contract(&mut chain, module_reference, &param)
+            .expect_err("Initialization with invalid parameters fails");
+    }
+
+    // The `end_time` is inclusive, so it can be the current block time.
+    init_contract(
+        &mut chain,
+        module_reference,
+        &InitParameterBuilder::default().build(),
+    )
+    .expect("Initialization succeeds");
+}
+
+/// Test voting on a voting option that represents a bloc.
+#[test]
+fn test_vote_on_bloc() {
+    let (mut chain, initialization) = setup_chain_and_contract_with(
+        Timestamp::from_timestamp_millis(0),
+        InitParameterBuilder::default()
+            .bloc("NORDICS", &["DK", "FI", "IS", "NO", "SE"])
+            .build(),
+    );
+    let contract = initialization.contract_address;
+
+    // The blocs are visible.
+    let blocs: BTreeMap<VotingOption, Vec<VotingOption>> =
+        invoke(&chain, contract, "viewBlocs", &());
+    assert_eq!(blocs.get("NORDICS").map(Vec::len), Some(5));
+
+    // A signature for a member country is not valid for the bloc.
+    let mut param = RegisterParameterBuilder::new(ACC_0, "NORDICS").build();
+    param.signatures = RegisterParameterBuilder::new(ACC_0, "DK")
+        .build()
+        .signatures;
+    let update_err =
+        register_and_vote(&mut chain, contract, ACC_0, &param).expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Message
+        }
+    );
+
+    // A signature for the bloc is.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "NORDICS").build(),
+    )
+    .expect("Voting succeeds");
+    assert_eq!(view(&chain, contract).tally.get("NORDICS"), Some(&1));
+}
+
+/// Test that initializing with a bloc that cannot be distinguished from a country code fails.
+#[test]
+fn test_init_with_invalid_bloc_fails() {
+    let (mut chain, module_reference) = setup_chain_and_module(Timestamp::from_timestamp_millis(0));
+
+    init_contract(
+        &mut chain,
+        module_reference,
+        &InitParameterBuilder::default()
+            .bloc("EU", &["DE", "FR"])
+            .build(),
+    )
+    .expect_err("Initialization with an invalid bloc fails");
+}
+
+/// Test that accounts below the minimum balance cannot vote.
+#[test]
+fn test_vote_with_insufficient_balance_fails() {
+    // The test accounts hold 10000 CCD.
+    let (mut chain, initialization) = setup_chain_and_contract_with(
+        Timestamp::from_timestamp_millis(0),
+        InitParameterBuilder::default()
+            .min_balance(Amount::from_ccd(10001))
+            .build(),
+    );
+
+    let update_err = register_and_vote(
+        &mut chain,
+        initialization.contract_address,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::InsufficientStake);
+}
+
+/// Test that accounts holding the minimum balance can vote.
+#[test]
+fn test_vote_with_sufficient_balance() {
+    // The test accounts hold 10000 CCD, part of which is spent on fees.
+    let (mut chain, initialization) = setup_chain_and_contract_with(
+        Timestamp::from_timestamp_millis(0),
+        InitParameterBuilder::default()
+            .min_balance(Amount::from_ccd(1000))
+            .build(),
+    );
+
+    register_and_vote(
+        &mut chain,
+        initialization.contract_address,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DE").build(),
+    )
+    .expect("Voting succeeds");
+}
+
+/// Test that only the admin can transfer the admin role.
+#[test]
+fn test_transfer_admin() {
+    // ACC_0 initializes the contract and is therefore the admin.
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    let admin: AccountAddress = invoke(&chain, contract, "viewAdmin", &());
+    assert_eq!(admin, ACC_0);
+
+    // ACC_1 cannot take over the admin role.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "transferAdmin",
+        &ACC_1,
+    )
+    .expect_err("Transferring the admin role fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+
+    // ACC_0 transfers the admin role to ACC_1.
+    let transfer = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "transferAdmin",
+        &ACC_1,
+    )
+    .expect("Transferring the admin role succeeds");
+    assert_eq!(
+        events(&transfer),
+        [VotingEvent::AdminTransferred {
+            old_admin: ACC_0,
+            new_admin: ACC_1,
+        }]
+    );
+
+    // ACC_0 is no longer the admin.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "transferAdmin",
+        &ACC_0,
+    )
+    .expect_err("Transferring the admin role fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+
+    // ACC_1 is the admin now.
+    let admin: AccountAddress = invoke(&chain, contract, "viewAdmin", &());
+    assert_eq!(admin, ACC_1);
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "transferAdmin",
+        &ACC_0,
+    )
+    .expect("Transferring the admin role succeeds");
+}
+
+/// Test that the admin can extend, but not shorten, the election.
+#[test]
+fn test_admin_extend_end_time() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    let new_end_time = Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH + 1000);
+
+    // Only the admin can extend the election.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "extendEndTime",
+        &new_end_time,
+    )
+    .expect_err("Extending fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+
+    // The election cannot be shortened.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "extendEndTime",
+        &Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH - 1),
+    )
+    .expect_err("Shortening fails");
+    assert_eq!(voting_error(update_err), VotingError::InvalidExtension);
+
+    let extension = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "extendEndTime",
+        &new_end_time,
+    )
+    .expect("Extending succeeds");
+    assert_eq!(
+        events(&extension),
+        [VotingEvent::EndTimeExtended {
+            old_end_time: Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH),
+            new_end_time,
+        }]
+    );
+    assert_eq!(view(&chain, contract).end_time, new_end_time);
+}
+
+/// Test that voting is rejected while the admin has paused the election.
+#[test]
+fn test_pause_and_unpause() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    // Only the admin can pause the election.
+    let update_err = update(&mut chain, contract, Address::Account(ACC_1), "pause", &())
+        .expect_err("Pausing fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+
+    let pausing = update(&mut chain, contract, Address::Account(ACC_0), "pause", &())
+        .expect("Pausing succeeds");
+    assert_eq!(events(&pausing), [VotingEvent::Paused]);
+
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DE").build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::ElectionPaused);
+
+    let unpausing = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "unpause",
+        &(),
+    )
+    .expect("Unpausing succeeds");
+    assert_eq!(events(&unpausing), [VotingEvent::Unpaused]);
+
+    // The registration from before the pause is still valid.
+    vote(&mut chain, contract, ACC_1, "DE").expect("Voting succeeds");
+}
+
+/// Test that a cancelled election rejects votes and finalization, and is reported as cancelled.
+#[test]
+fn test_cancel() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    assert_eq!(view(&chain, contract).status, ElectionStatus::Open);
+
+    // Only the admin can cancel the election.
+    let update_err = update(&mut chain, contract, Address::Account(ACC_1), "cancel", &())
+        .expect_err("Cancelling fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+
+    let cancellation = update(&mut chain, contract, Address::Account(ACC_0), "cancel", &())
+        .expect("Cancelling succeeds");
+    assert_eq!(events(&cancellation), [VotingEvent::Cancelled]);
+    assert_eq!(view(&chain, contract).status, ElectionStatus::Cancelled);
+
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DE").build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::ElectionCancelled);
+
+    finish_election(&mut chain);
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "finalize",
+        &(),
+    )
+    .expect_err("Finalizing fails");
+    assert_eq!(voting_error(update_err), VotingError::ElectionCancelled);
+    assert_eq!(view(&chain, contract).status, ElectionStatus::Cancelled);
+}
+
+/// Test that the admin can add voting options until the first vote is cast.
+#[test]
+fn test_add_option() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    let add_option = |chain: &mut Chain, sender, option: &str| {
+        update(
+            chain,
+            contract,
+            Address::Account(sender),
+            "addOption",
+            &option_info(option),
+        )
+    };
+
+    // Only the admin can add voting options.
+    let update_err = add_option(&mut chain, ACC_1, "FR").expect_err("Adding fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+
+    // Existing options cannot be added again.
+    let update_err = add_option(&mut chain, ACC_0, "DE").expect_err("Adding fails");
+    assert_eq!(voting_error(update_err), VotingError::DuplicateOption);
+
+    let addition = add_option(&mut chain, ACC_0, "FR").expect("Adding succeeds");
+    assert_eq!(
+        events(&addition),
+        [VotingEvent::OptionAdded {
+            option: String::from("FR")
+        }]
+    );
+    assert_eq!(view(&chain, contract).tally.get("FR"), Some(&0));
+
+    // The new option can be voted for.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "FR").build(),
+    )
+    .expect("Voting succeeds");
+    assert_eq!(view(&chain, contract).tally.get("FR"), Some(&1));
+
+    // No options can be added after the first vote.
+    let update_err = add_option(&mut chain, ACC_0, "ES").expect_err("Adding fails");
+    assert_eq!(voting_error(update_err), VotingError::VotesAlreadyCast);
+}
+
+/// Test that the admin can remove voting options without votes, and that the indices of the
+/// remaining options stay the same.
+#[test]
+fn test_remove_option() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    let remove_option = |chain: &mut Chain, sender, option: &str| {
+        update(
+            chain,
+            contract,
+            Address::Account(sender),
+            "removeOption",
+            &String::from(option),
+        )
+    };
+
+    // ACC_1 votes on Italy.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "IT").build(),
+    )
+    .expect("Voting succeeds");
+
+    // Only the admin can remove voting options.
+    let update_err = remove_option(&mut chain, ACC_1, "DE").expect_err("Removing fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+
+    // Options with votes cannot be removed.
+    let update_err = remove_option(&mut chain, ACC_0, "IT").expect_err("Removing fails");
+    assert_eq!(voting_error(update_err), VotingError::OptionHasVotes);
+
+    let removal = remove_option(&mut chain, ACC_0, "DE").expect("Removing succeeds");
+    assert_eq!(
+        events(&removal),
+        [VotingEvent::OptionRemoved {
+            option: String::from("DE")
+        }]
+    );
+
+    // The remaining options keep their indices, and the ballot is still valid.
+    let options: Vec<(VoteIndex, VotingOption)> = invoke(&chain, contract, "viewOptions", &());
+    assert_eq!(options, [(0, String::from("DK")), (2, String::from("IT"))]);
+    let tally = view(&chain, contract).tally;
+    assert_eq!(tally.get("DE"), None);
+    assert_eq!(tally.get("IT"), Some(&1));
+
+    // The removed option cannot be voted for, nor removed again.
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidVotingOption {
+            option: String::from("DE")
+        }
+    );
+    let update_err = remove_option(&mut chain, ACC_0, "DE").expect_err("Removing fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidVotingOption {
+            option: String::from("DE")
+        }
+    );
+}
+
+/// Test that the admin can update the description.
+#[test]
+fn test_update_description() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    let description = String::from("Where should the next workshop take place?");
+
+    // Only the admin can update the description.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "updateDescription",
+        &description,
+    )
+    .expect_err("Updating fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+
+    let description_update = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "updateDescription",
+        &description,
+    )
+    .expect("Updating succeeds");
+    assert_eq!(
+        events(&description_update),
+        [VotingEvent::DescriptionUpdated]
+    );
+    assert_eq!(view(&chain, contract).description, description);
+}
+
+/// Test that after rotating the verifier key, only signatures from the new key are accepted.
+#[test]
+fn test_rotate_verifier_key() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    let new_secret_key = [8; 32];
+
+    let param = RotateVerifierKeyParameter {
+        index: 0,
+        new_key: public_key(new_secret_key),
+    };
+
+    // Only the admin can rotate the verifier key.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "rotateVerifierKey",
+        &param,
+    )
+    .expect_err("Rotating fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+
+    // Only existing verifier keys can be rotated.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "rotateVerifierKey",
+        &RotateVerifierKeyParameter {
+            index: 1,
+            new_key: public_key(new_secret_key),
+        },
+    )
+    .expect_err("Rotating fails");
+    assert_eq!(voting_error(update_err), VotingError::InvalidVerifierIndex);
+
+    let rotation = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "rotateVerifierKey",
+        &param,
+    )
+    .expect("Rotating succeeds");
+    assert_eq!(
+        events(&rotation),
+        [VotingEvent::VerifierKeyRotated {
+            index: 0,
+            old_key: verifier_public_key(),
+            new_key: public_key(new_secret_key),
+        }]
+    );
+
+    // Signatures from the old key are rejected.
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DE").build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Message
+        }
+    );
+
+    // Signatures from the new key are accepted.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DE")
+            .signed_by(new_secret_key)
+            .build(),
+    )
+    .expect("Voting succeeds");
+}
+
+/// Test the permissions of every privileged entrypoint: the admin gets the
+/// listed result on a fresh election, while other accounts and contracts are
+/// rejected with `NotAdmin`.
+#[test]
+fn test_admin_permission_matrix() {
+    let matrix: Vec<(&str, Vec<u8>, Result<(), VotingError>)> = vec![
+        ("transferAdmin", to_bytes(&ACC_1), Ok(())),
+        (
+            "extendEndTime",
+            to_bytes(&Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH + 1)),
+            Ok(()),
+        ),
+        ("pause", Vec::new(), Ok(())),
+        ("unpause", Vec::new(), Ok(())),
+        ("cancel", Vec::new(), Ok(())),
+        ("addOption", to_bytes(&option_info("FR")), Ok(())),
+        ("removeOption", to_bytes(&String::from("IT")), Ok(())),
+        ("updateDescription", to_bytes(&String::from("New")), Ok(())),
+        (
+            "rotateVerifierKey",
+            to_bytes(&RotateVerifierKeyParameter {
+                index: 0,
+                new_key: public_key([8; 32]),
+            }),
+            Ok(()),
+        ),
+        (
+            "approveWriteIn",
+            to_bytes(&String::from("FR")),
+            Err(VotingError::NoPendingWriteIn),
+        ),
+        (
+            "startRunoff",
+            to_bytes(&Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH + 1)),
+            Err(VotingError::NotFinalized),
+        ),
+        (
+            "upgrade",
+            to_bytes(&UpgradeParameter {
+                module: ModuleReference::from([7; 32]),
+                migrate: None,
+            }),
+            Err(VotingError::UpgradeFailed),
+        ),
+        ("blacklist", to_bytes(&ACC_1), Ok(())),
+        (
+            "unblacklist",
+            to_bytes(&ACC_1),
+            Err(VotingError::NotBlacklisted),
+        ),
+        (
+            "withdrawFunds",
+            Vec::new(),
+            Err(VotingError::NothingToWithdraw),
+        ),
+        ("cleanup", Vec::new(), Err(VotingError::NotFinalized)),
+        (
+            "certifyResult",
+            to_bytes(&CertifiedResult {
+                tally: BTreeMap::new(),
+                signature: SignatureEd25519([0; 64]),
+            }),
+            Err(VotingError::NotCertifiable),
+        ),
+    ];
+
+    for (entrypoint, parameter, admin_result) in matrix {
+        let (mut chain, initialization) =
+            setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+        let contract = initialization.contract_address;
+        let mut call = |sender: Address| {
+            chain
+                .contract_update(
+                    SIGNER,
+                    ACC_0,
+                    sender,
+                    Energy::from(100000),
+                    UpdateContractPayload {
+                        amount: Amount::zero(),
+                        address: contract,
+                        receive_name: OwnedReceiveName::new_unchecked(format!(
+                            "voting.{}",
+                            entrypoint
+                        )),
+                        message: OwnedParameter::new_unchecked(parameter.clone()),
+                    },
+                )
+                .map(|_| ())
+                .map_err(voting_error)
+        };
+
+        assert_eq!(
+            call(Address::Account(ACC_1)),
+            Err(VotingError::NotAdmin),
+            "{} as another account",
+            entrypoint
+        );
+        assert_eq!(
+            call(Address::Contract(contract)),
+            Err(VotingError::NotAdmin),
+            "{} as a contract",
+            entrypoint
+        );
+        assert_eq!(
+            call(Address::Account(ACC_0)),
+            admin_result,
+            "{} as the admin",
+            entrypoint
+        );
+    }
+}
+
+/// The `return_value` type of the contract function `view` in the basic version of the contract,
+/// which has no election status and counts the votes with `u32`.
+#[derive(Deserial)]
+struct BasicVotingView {
+    description: String,
+    end_time: Timestamp,
+    tally: BTreeMap<VotingOption, u32>,
+}
+
+/// The parameter type for the contract function `init` in the basic version of the contract,
+/// which does not include the public key of the verifier.
+#[derive(Serial)]
+struct BasicInitParameter {
+    description: String,
+    options: Vec<VotingOption>,
+    end_time: Timestamp,
+}
+
+/// Helper function that gets the error code of an update that the contract rejected. Each version
+/// defines its own error type, so the errors are compared by their codes.
+fn reject_code(update: ContractInvokeError) -> i32 {
+    match update.kind {
+        ContractInvokeErrorKind::ExecutionError {
+            failure_kind: InvokeFailure::ContractReject { code, .. },
+        } => code,
+        kind => panic!(
+            "The contract should reject the update, but it failed with {:?}",
+            kind
+        ),
+    }
+}
+
+/// Helper function that deploys and initializes the basic version of the contract on the `chain`
+/// with the same description, options, and `end_time` as the `InitParameterBuilder` defaults.
+fn setup_basic_contract(chain: &mut Chain) -> ContractInitSuccess {
+    // Load the module of the basic version.
+    let module = module_load_v1("../../smart-contract/concordium-out/module.wasm.v1")
+        .expect("Module file of the basic version should exist");
+
+    // Deploy the module.
+    let deployment = chain
+        .module_deploy_v1(SIGNER, ACC_0, module)
+        .expect("Deploying valid module should succeed");
+
+    // Initialize the contract.
+    let param = InitParameterBuilder::default().build();
+    chain
+        .contract_init(
+            SIGNER,
+            ACC_0,
+            Energy::from(100000),
+            InitContractPayload {
+                amount: Amount::zero(),
+                mod_ref: deployment.module_reference,
+                init_name: OwnedContractName::new_unchecked(String::from("init_voting")),
+                param: OwnedParameter::from_serial(&BasicInitParameter {
+                    description: param.description,
+                    options: param
+                        .options
+                        .into_iter()
+                        .map(|option| option.code)
+                        .collect(),
+                    end_time: param.end_time,
+                })
+                .expect("Valid parameter size"),
+            },
+        )
+        .expect("Initialization should succeed")
+}
+
+/// Test that the basic and the advanced version of the contract behave identically when they
+/// receive the same sequence of votes, where the advanced version gets valid signatures.
+///
+/// After each vote, the outcome of the update and the result of `view` are compared.
+#[test]
+fn test_basic_and_advanced_versions_agree() {
+    // Set up the chain with both versions of the contract.
+    let (mut chain, advanced) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let basic = setup_basic_contract(&mut chain);
+
+    // The sequence of votes, including a change of vote and a vote on an unknown option.
+    let votes = [
+        (ACC_0, "DE"),
+        (ACC_1, "DK"),
+        (ACC_0, "DK"),
+        (ACC_1, "IN"),
+        (ACC_1, "IT"),
+    ];
+
+    for (account, country_code) in votes {
+        // Vote in the basic version with just the voting option.
+        let basic_update = update(
+            &mut chain,
+            basic.contract_address,
+            Address::Account(account),
+            "vote",
+            &VotingOption::from(country_code),
+        );
+        // Vote in the advanced version with a signature from the verifier on the current nonce.
+        let registration = RegisterParameterBuilder::new(account, country_code)
+            .with_nonce(nonce(&chain, advanced.contract_address, account))
+            .build();
+        let advanced_update = register_and_vote(
+            &mut chain,
+            advanced.contract_address,
+            account,
+            &registration,
+        );
+
+        // Both versions either accept or reject the vote, and reject it for the same reason.
+        match (basic_update, advanced_update) {
+            (Ok(_), Ok(_)) => {}
+            (Err(basic_error), Err(advanced_error)) => {
+                assert_eq!(reject_code(basic_error), reject_code(advanced_error));
+            }
+            (basic_update, advanced_update) => panic!(
+                "Versions disagree on vote ({:?}, {}): basic succeeded: {}, advanced succeeded: {}",
+                account,
+                country_code,
+                basic_update.is_ok(),
+                advanced_update.is_ok()
+            ),
+        }
+
+        // Both versions return the same election information.
+        let basic_view: BasicVotingView = invoke(&chain, basic.contract_address, "view", &());
+        let advanced_view = view(&chain, advanced.contract_address);
+        assert_eq!(basic_view.description, advanced_view.description);
+        assert_eq!(basic_view.end_time, advanced_view.end_time);
+        let basic_tally: BTreeMap<VotingOption, VoteCount> = basic_view
+            .tally
+            .into_iter()
+            .map(|(option, count)| (option, VoteCount::from(count)))
+            .collect();
+        assert_eq!(basic_tally, advanced_view.tally);
+    }
+}
+
+/// Test that `supports` advertises the CIS-0 and CIS-3 standards only.
+#[test]
+fn test_supports() {
+    let (chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+
+    let response: SupportsQueryResponse = invoke(
+        &chain,
+        initialization.contract_address,
+        "supports",
+        &SupportsQueryParams {
+            queries: vec![
+                StandardIdentifier(String::from("CIS-0")),
+                StandardIdentifier(String::from("CIS-2")),
+                StandardIdentifier(String::from("CIS-3")),
+            ],
+        },
+    );
+    assert_eq!(
+        response.results,
+        [
+            SupportResult::Support,
+            SupportResult::NoSupport,
+            SupportResult::Support
+        ]
+    );
+
+    let response: SupportsQueryResponse = invoke(
+        &chain,
+        initialization.contract_address,
+        "supportsPermit",
+        &SupportsPermitQueryParams {
+            queries: vec![
+                OwnedEntrypointName::new_unchecked(String::from("register")),
+                OwnedEntrypointName::new_unchecked(String::from("vote")),
+                OwnedEntrypointName::new_unchecked(String::from("delegate")),
+            ],
+        },
+    );
+    assert_eq!(
+        response.results,
+        [
+            SupportResult::Support,
+            SupportResult::Support,
+            SupportResult::NoSupport
+        ]
+    );
+}
+
+/// Test that the admin can upgrade the contract without losing the ballots.
+#[test]
+fn test_upgrade() {
+    let (mut chain, module_reference) = setup_chain_and_module(Timestamp::from_timestamp_millis(0));
+    let contract = init_contract(
+        &mut chain,
+        module_reference,
+        &InitParameterBuilder::default().build(),
+    )
+    .expect("Initialization should succeed")
+    .contract_address;
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+
+    let param = UpgradeParameter {
+        module: module_reference,
+        migrate: None,
+    };
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "upgrade",
+        &param,
+    )
+    .expect_err("Upgrading fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "upgrade",
+        &UpgradeParameter {
+            module: ModuleReference::from([7; 32]),
+            migrate: None,
+        },
+    )
+    .expect_err("Upgrading fails");
+    assert_eq!(voting_error(update_err), VotingError::UpgradeFailed);
+
+    // The migration is called by the contract itself, which is not the admin.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "upgrade",
+        &UpgradeParameter {
+            module: module_reference,
+            migrate: Some((
+                OwnedEntrypointName::new_unchecked(String::from("pause")),
+                OwnedParameter::empty(),
+            )),
+        },
+    )
+    .expect_err("Upgrading fails");
+    assert_eq!(voting_error(update_err), VotingError::MigrationFailed);
+
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "upgrade",
+        &param,
+    )
+    .expect("Upgrading succeeds");
+
+    // The ballots are kept.
+    let view = view(&chain, contract);
+    assert_eq!(view.tally.get("DE"), Some(&1));
+    vote(&mut chain, contract, ACC_0, "DK").expect("Voting succeeds");
+}
+
+/// Test that only the contract voters of an election can vote from a contract.
+#[test]
+fn test_contract_voters() {
+    let (mut chain, module_reference) = setup_chain_and_module(Timestamp::from_timestamp_millis(0));
+    // Another instance stands in for e.g. a multisig wallet contract.
+    let multisig = init_contract(
+        &mut chain,
+        module_reference,
+        &InitParameterBuilder::default().build(),
+    )
+    .expect("Initialization should succeed")
+    .contract_address;
+    let mut param = InitParameterBuilder::default().build();
+    param.contract_voters = vec![multisig];
+
+    // The votes of contract voters cannot be weighted.
+    param.weights = Some(vec![(ACC_0, 10)]);
+    init_contract(&mut chain, module_reference, &param)
+        .expect_err("Initialization with weighted contract voters fails");
+
+    param.weights = None;
+    let contract = init_contract(&mut chain, module_reference, &param)
+        .expect("Initialization should succeed")
+        .contract_address;
+
+    let vote_update = update(
+        &mut chain,
+        contract,
+        Address::Contract(multisig),
+        "vote",
+        &String::from("DE"),
+    )
+    .expect("Voting succeeds");
+    assert_eq!(
+        events(&vote_update),
+        [VotingEvent::ContractVote {
+            voter: multisig,
+            old_option: None,
+            new_option: String::from("DE"),
+        }]
+    );
+
+    // The contract voter can change its vote without registering.
+    update(
+        &mut chain,
+        contract,
+        Address::Contract(multisig),
+        "vote",
+        &String::from("DK"),
+    )
+    .expect("Voting succeeds");
+    let view = view(&chain, contract);
+    assert_eq!(view.tally.get("DK"), Some(&1));
+    assert_eq!(view.tally.get("DE"), Some(&0));
+    let voter_count: u32 = invoke(&chain, contract, "voterCount", &());
+    assert_eq!(voter_count, 1);
+
+    // Other contracts are still rejected.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Contract(contract),
+        "vote",
+        &String::from("DE"),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::ContractVoter);
+}
+
+/// Test that the admin can blacklist a voter, which removes its ballot, and lift it again.
+#[test]
+fn test_blacklist() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DE").build(),
+    )
+    .expect("Voting succeeds");
+
+    // Only the admin can blacklist.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "blacklist",
+        &ACC_1,
+    )
+    .expect_err("Blacklisting fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+
+    let blacklisting = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "blacklist",
+        &ACC_1,
+    )
+    .expect("Blacklisting succeeds");
+    assert_eq!(
+        events(&blacklisting),
+        [
+            VotingEvent::Blacklisted { account: ACC_1 },
+            VotingEvent::VoteRetracted {
+                voter: ACC_1,
+                option: String::from("DE"),
+            },
+        ]
+    );
+    let view = view(&chain, contract);
+    assert_eq!(view.tally.get("DE"), Some(&0));
+    let voter_count: u32 = invoke(&chain, contract, "voterCount", &());
+    assert_eq!(voter_count, 0);
+
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "blacklist",
+        &ACC_1,
+    )
+    .expect_err("Blacklisting fails");
+    assert_eq!(voting_error(update_err), VotingError::AlreadyBlacklisted);
+    let update_err = vote(&mut chain, contract, ACC_1, "DE").expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::Blacklisted);
+
+    // Once unblacklisted, the account can vote with its registration.
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "unblacklist",
+        &ACC_1,
+    )
+    .expect("Unblacklisting succeeds");
+    vote(&mut chain, contract, ACC_1, "DE").expect("Voting succeeds");
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "unblacklist",
+        &ACC_1,
+    )
+    .expect_err("Unblacklisting fails");
+    assert_eq!(voting_error(update_err), VotingError::NotBlacklisted);
+}
+
+/// Test that no new accounts can vote once `max_voters` accounts have voted.
+#[test]
+fn test_max_voters() {
+    let mut param = InitParameterBuilder::default().build();
+    param.max_voters = Some(1);
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DE").build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::VoterLimitReached);
+
+    // The existing voter can still vote again.
+    vote(&mut chain, contract, ACC_0, "DE").expect("Voting succeeds");
+
+    // A retracted ballot frees up its place.
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "retractVote",
+        &(),
+    )
+    .expect("Retracting succeeds");
+    vote(&mut chain, contract, ACC_1, "DE").expect("Voting succeeds");
+}
+
+/// Test that CCD attached to `vote` are rejected rather than kept by the contract.
+#[test]
+fn test_vote_with_funds_fails() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    register(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Registering succeeds");
+
+    let update_err = update_with_amount(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "vote",
+        &String::from("DE"),
+        Amount::from_ccd(1),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::UnexpectedFunds);
+    assert_eq!(chain.contract_balance(contract), Some(Amount::zero()));
+}
+
+/// Test that the admin cannot withdraw the deposits of the voters as stray funds.
+#[test]
+fn test_withdraw_funds_keeps_deposits() {
+    let mut param = InitParameterBuilder::default().build();
+    param.quadratic_voting = true;
+    param.funds_recipient = Some(ACC_1);
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+    register(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Registering succeeds");
+    vote_with_deposit(&mut chain, contract, ACC_0, "DE", Amount::from_ccd(4))
+        .expect("Voting succeeds");
+
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "withdrawFunds",
+        &(),
+    )
+    .expect_err("Withdrawing funds fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "withdrawFunds",
+        &(),
+    )
+    .expect_err("Withdrawing funds fails");
+    assert_eq!(voting_error(update_err), VotingError::NothingToWithdraw);
+    assert_eq!(chain.contract_balance(contract), Some(Amount::from_ccd(4)));
+}
+
+/// Test that the admin can delete the ballots once the retention period has passed, keeping the tally.
+#[test]
+fn test_cleanup() {
+    let mut param = InitParameterBuilder::default().build();
+    param.retention_period = Some(Duration::from_millis(1000));
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+
+    finish_election(&mut chain);
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "cleanup",
+        &(),
+    )
+    .expect_err("Cleaning up fails");
+    assert_eq!(voting_error(update_err), VotingError::NotFinalized);
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "finalize",
+        &(),
+    )
+    .expect("Finalizing succeeds");
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "cleanup",
+        &(),
+    )
+    .expect_err("Cleaning up fails");
+    assert_eq!(voting_error(update_err), VotingError::CleanupNotAllowed);
+
+    chain
+        .tick_block_time(Duration::from_millis(1000))
+        .expect("Block time does not overflow");
+    let cleanup = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "cleanup",
+        &(),
+    )
+    .expect("Cleaning up succeeds");
+    assert_eq!(events(&cleanup), [VotingEvent::BallotsCleared]);
+
+    // The ballot is gone, but the tally and the result are kept.
+    let has_voted: bool = invoke(&chain, contract, "hasVoted", &ACC_0);
+    assert!(!has_voted);
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&1));
+    let outcome: ElectionOutcome = invoke(&chain, contract, "getResult", &());
+    assert_eq!(outcome.winner, Some(String::from("DE")));
+}
+
+/// Test that `viewTurnout` reports the participation relative to the expected number of voters.
+#[test]
+fn test_view_turnout() {
+    let mut param = InitParameterBuilder::default().build();
+    param.expected_voters = Some(3);
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+    let turnout: TurnoutView = invoke(&chain, contract, "viewTurnout", &());
+    assert_eq!(
+        turnout,
+        TurnoutView {
+            votes_cast: 1,
+            expected_voters: Some(3),
+            permille: Some(333),
+        }
+    );
+
+    // Without an expected number of voters, only the votes cast are reported.
+    let (chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let turnout: TurnoutView = invoke(&chain, initialization.contract_address, "viewTurnout", &());
+    assert_eq!(turnout.votes_cast, 0);
+    assert_eq!(turnout.permille, None);
+}
+
+/// Test that `getTallyForOption` returns the votes of a single voting option.
+#[test]
+fn test_get_tally_for_option() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+
+    let count: VoteCount = invoke(&chain, contract, "getTallyForOption", &String::from("DE"));
+    assert_eq!(count, 1);
+    let count: VoteCount = invoke(&chain, contract, "getTallyForOption", &String::from("DK"));
+    assert_eq!(count, 0);
+
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "getTallyForOption",
+        &String::from("SE"),
+    )
+    .expect_err("Getting the tally fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidVotingOption {
+            option: String::from("SE")
+        }
+    );
+}
+
+/// Test that `viewMetadata` and `viewTally` split the information of `view`.
+#[test]
+fn test_view_metadata_and_tally() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+
+    let metadata: MetadataView = invoke(&chain, contract, "viewMetadata", &());
+    assert_eq!(
+        metadata,
+        MetadataView {
+            description: String::from("Concordium EuroVision"),
+            end_time: Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH),
+            status: ElectionStatus::Open,
+            options: vec![String::from("DK"), String::from("DE"), String::from("IT")],
+        }
+    );
+
+    let tally: BTreeMap<VotingOption, VoteCount> = invoke(&chain, contract, "viewTally", &());
+    assert_eq!(tally, view(&chain, contract).tally);
+    assert_eq!(tally.get("DE"), Some(&1));
+}
+
+/// Test that `viewVotersForOption` pages through the voters of a single voting option.
+#[test]
+fn test_view_voters_for_option() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    for account in [ACC_0, ACC_1] {
+        register_and_vote(
+            &mut chain,
+            contract,
+            account,
+            &RegisterParameterBuilder::new(account, "DE").build(),
+        )
+        .expect("Voting succeeds");
+    }
+
+    let first_page: Vec<AccountAddress> = invoke(
+        &chain,
+        contract,
+        "viewVotersForOption",
+        &ViewVotersForOptionParameter {
+            option: String::from("DE"),
+            cursor: None,
+            limit: 1,
+        },
+    );
+    assert_eq!(first_page, [ACC_0]);
+    let second_page: Vec<AccountAddress> = invoke(
+        &chain,
+        contract,
+        "viewVotersForOption",
+        &ViewVotersForOptionParameter {
+            option: String::from("DE"),
+            cursor: Some(ACC_0),
+            limit: 1,
+        },
+    );
+    assert_eq!(second_page, [ACC_1]);
+
+    let voters: Vec<AccountAddress> = invoke(
+        &chain,
+        contract,
+        "viewVotersForOption",
+        &ViewVotersForOptionParameter {
+            option: String::from("DK"),
+            cursor: None,
+            limit: 10,
+        },
+    );
+    assert!(voters.is_empty());
+}
+
+/// Test that a vote returns a receipt that can be verified until the ballot is retracted.
+#[test]
+fn test_verify_receipt() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    let success = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DE").build(),
+    )
+    .expect("Voting succeeds");
+    let receipt: HashSha2256 =
+        from_bytes(&success.return_value).expect("Return value should be a receipt");
+
+    // The receipt commits to the voter, the voting option, and the slot time.
+    let voter = Address::Account(ACC_1);
+    let message = [&to_bytes(&voter)[..], b"DE", &0u64.to_le_bytes()].concat();
+    assert_eq!(receipt, HashSha2256(Sha256::digest(message).into()));
+
+    let verified: bool = invoke(
+        &chain,
+        contract,
+        "verifyReceipt",
+        &VerifyReceiptParameter { voter, receipt },
+    );
+    assert!(verified);
+    let verified: bool = invoke(
+        &chain,
+        contract,
+        "verifyReceipt",
+        &VerifyReceiptParameter {
+            voter: Address::Account(ACC_0),
+            receipt,
+        },
+    );
+    assert!(!verified);
+
+    // Retracting the vote invalidates the receipt.
+    update(&mut chain, contract, voter, "retractVote", &()).expect("Retracting succeeds");
+    let verified: bool = invoke(
+        &chain,
+        contract,
+        "verifyReceipt",
+        &VerifyReceiptParameter { voter, receipt },
+    );
+    assert!(!verified);
+}
+
+/// Test that the votes of an account are recorded in its vote history, if enabled.
+#[test]
+fn test_view_vote_history() {
+    let mut param = InitParameterBuilder::default().build();
+    param.record_vote_history = true;
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    // ACC_0 registers for Germany and Denmark, votes on Germany, and later changes its mind.
+    for (nonce, country_code) in [(0, "DE"), (1, "DK")] {
+        register(
+            &mut chain,
+            contract,
+            ACC_0,
+            &RegisterParameterBuilder::new(ACC_0, country_code)
+                .with_nonce(nonce)
+                .build(),
+        )
+        .expect("Registering succeeds");
+    }
+    vote(&mut chain, contract, ACC_0, "DE").expect("Voting succeeds");
+    chain
+        .tick_block_time(Duration::from_millis(1000))
+        .expect("Block time does not overflow");
+    vote(&mut chain, contract, ACC_0, "DK").expect("Voting succeeds");
+
+    let history: Vec<VoteRecord> = invoke(&chain, contract, "viewVoteHistory", &ACC_0);
+    assert_eq!(
+        history,
+        [
+            VoteRecord {
+                timestamp: Timestamp::from_timestamp_millis(0),
+                option: String::from("DE"),
+            },
+            VoteRecord {
+                timestamp: Timestamp::from_timestamp_millis(1000),
+                option: String::from("DK"),
+            },
+        ]
+    );
+    let history: Vec<VoteRecord> = invoke(&chain, contract, "viewVoteHistory", &ACC_1);
+    assert!(history.is_empty());
+}
+
+/// Test that an election that only logs the votes emits them as events without storing them.
+#[test]
+fn test_logs_only() {
+    let (mut chain, module_reference) = setup_chain_and_module(Timestamp::from_timestamp_millis(0));
+    let mut param = InitParameterBuilder::default().build();
+    param.logs_only = true;
+    param.allow_vote_change = false;
+    init_contract(&mut chain, module_reference, &param)
+        .expect_err("Initialization of a logs-only election without vote changes fails");
+
+    param.allow_vote_change = true;
+    let contract = init_contract(&mut chain, module_reference, &param)
+        .expect("Initialization should succeed")
+        .contract_address;
+
+    let success = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+    assert_eq!(
+        events(&success),
+        [VotingEvent::VoteLogged {
+            voter: Address::Account(ACC_0),
+            option: String::from("DE"),
+            weight: 1,
+        }]
+    );
+
+    // Neither the ballot nor the tally is stored.
+    let has_voted: bool = invoke(&chain, contract, "hasVoted", &ACC_0);
+    assert!(!has_voted);
+    assert_eq!(view(&chain, contract).tally.get("DE"), Some(&0));
+
+    // The contract has no tally to finalize.
+    finish_election(&mut chain);
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "finalize",
+        &(),
+    )
+    .expect_err("Finalizing fails");
+    assert_eq!(voting_error(update_err), VotingError::TallyNotStored);
+}
+
+/// Test that the admin can post a certified result next to the computed one.
+#[test]
+fn test_certify_result() {
+    let authority = keypair(VERIFIER_SECRET_KEY);
+    let mut param = InitParameterBuilder::default().build();
+    param.certification_key = Some(PublicKeyEd25519(authority.public.to_bytes()));
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+
+    // The certified tally differs from the computed one.
+    let tally = BTreeMap::from([
+        (String::from("DK"), 0),
+        (String::from("DE"), 2),
+        (String::from("IT"), 0),
+    ]);
+    let certify = |secret_key: [u8; 32]| {
+        let message = CertificationMessage {
+            contract_address: contract,
+            tally: tally.clone(),
+        };
+        CertifiedResult {
+            tally: tally.clone(),
+            signature: SignatureEd25519(keypair(secret_key).sign(&to_bytes(&message)).to_bytes()),
+        }
+    };
+
+    // The result can only be certified once the election is finalized.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "certifyResult",
+        &certify(VERIFIER_SECRET_KEY),
+    )
+    .expect_err("Certifying fails");
+    assert_eq!(voting_error(update_err), VotingError::NotFinalized);
+
+    finish_election(&mut chain);
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "finalize",
+        &(),
+    )
+    .expect("Finalizing succeeds");
+
+    // Only the admin can post the result, which must be signed by the election authority.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_1),
+        "certifyResult",
+        &certify(VERIFIER_SECRET_KEY),
+    )
+    .expect_err("Certifying fails");
+    assert_eq!(voting_error(update_err), VotingError::NotAdmin);
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "certifyResult",
+        &certify(VOTER_SECRET_KEY),
+    )
+    .expect_err("Certifying fails");
+    assert_eq!(
+        voting_error(update_err),
+        VotingError::InvalidSignature {
+            check: SignatureCheck::Message
+        }
+    );
+
+    let success = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "certifyResult",
+        &certify(VERIFIER_SECRET_KEY),
+    )
+    .expect("Certifying succeeds");
+    assert_eq!(
+        events(&success),
+        [VotingEvent::ResultCertified {
+            matches_computed: false
+        }]
+    );
+
+    // Both the computed and the certified result are visible.
+    let voting_view = view(&chain, contract);
+    assert_eq!(voting_view.tally.get("DE"), Some(&1));
+    assert_eq!(
+        voting_view.certified_result,
+        Some(certify(VERIFIER_SECRET_KEY))
+    );
+
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "certifyResult",
+        &certify(VERIFIER_SECRET_KEY),
+    )
+    .expect_err("Certifying fails");
+    assert_eq!(voting_error(update_err), VotingError::AlreadyCertified);
+}
+
+/// Test that a vote is rejected if the "I voted" badge cannot be minted.
+#[test]
+fn test_vote_without_badge_contract_fails() {
+    let mut param = InitParameterBuilder::default().build();
+    param.badge_contract = Some(ContractAddress::new(1000, 0));
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    let update_err = register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect_err("Voting fails");
+    assert_eq!(voting_error(update_err), VotingError::BadgeMintFailed);
+}
+
+/// Test that the weight of a vote in a time-weighted election decays until the `end_time`.
+#[test]
+fn test_time_weighted_voting() {
+    let mut param = InitParameterBuilder::default().build();
+    param.time_weighted = true;
+    let (mut chain, initialization) =
+        setup_chain_and_contract_with(Timestamp::from_timestamp_millis(0), param);
+    let contract = initialization.contract_address;
+
+    // A vote at the start of the election has the full weight.
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_0,
+        &RegisterParameterBuilder::new(ACC_0, "DE").build(),
+    )
+    .expect("Voting succeeds");
+
+    // A vote halfway through the election has about half the weight.
+    chain
+        .tick_block_time(Duration::from_millis(CHRISTMAS_EVE_EPOCH / 2))
+        .expect("Block time does not overflow");
+    register_and_vote(
+        &mut chain,
+        contract,
+        ACC_1,
+        &RegisterParameterBuilder::new(ACC_1, "DK").build(),
+    )
+    .expect("Voting succeeds");
+
+    let voting_view = view(&chain, contract);
+    assert_eq!(voting_view.tally.get("DE"), Some(&TIME_WEIGHT_SCALE));
+    assert_eq!(voting_view.tally.get("DK"), Some(&500));
+    assert_eq!(voting_view.ballot_counts.get("DE"), Some(&1));
+    assert_eq!(voting_view.ballot_counts.get("DK"), Some(&1));
+
+    // The voting power cannot be delegated.
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_2),
+        "delegate",
+        &ACC_0,
+    )
+    .expect_err("Delegating fails");
+    assert_eq!(voting_error(update_err), VotingError::TimeWeightedElection);
+}
+
+/// Test that `getVerifierKey` returns the keys of the verifiers and the threshold.
+#[test]
+fn test_get_verifier_key() {
+    let (chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+
+    let verifier_keys: VerifierKeysView = invoke(
+        &chain,
+        initialization.contract_address,
+        "getVerifierKey",
+        &(),
+    );
+    assert_eq!(
+        verifier_keys,
+        VerifierKeysView {
+            keys: vec![verifier_public_key()],
+            threshold: 1,
+        }
+    );
+}
+
+/// Test that the tallies of the pages of `viewTallyPartial` add up to the tally.
+#[test]
+fn test_view_tally_partial() {
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+    for (account, country_code) in [(ACC_0, "DE"), (ACC_1, "DK"), (ACC_2, "DE")] {
+        register_and_vote(
+            &mut chain,
+            contract,
+            account,
+            &RegisterParameterBuilder::new(account, country_code).build(),
+        )
+        .expect("Voting succeeds");
+    }
+
+    let first_page: PartialTallyView = invoke(
+        &chain,
+        contract,
+        "viewTallyPartial",
+        &ViewTallyPartialParameter {
+            limit: 2,
+            cursor: None,
+        },
+    );
+    assert_eq!(
+        first_page,
+        PartialTallyView {
+            tally: BTreeMap::from([(String::from("DE"), 1), (String::from("DK"), 1)]),
+            cursor: Some(2),
+        }
+    );
+    let second_page: PartialTallyView = invoke(
+        &chain,
+        contract,
+        "viewTallyPartial",
+        &ViewTallyPartialParameter {
+            limit: 2,
+            cursor: first_page.cursor,
+        },
+    );
+    assert_eq!(
+        second_page,
+        PartialTallyView {
+            tally: BTreeMap::from([(String::from("DE"), 1)]),
+            cursor: None,
+        }
+    );
+
+    // A retracted ballot leaves its position empty, and a new vote is appended.
+    update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "retractVote",
+        &(),
+    )
+    .expect("Retracting succeeds");
+    vote(&mut chain, contract, ACC_0, "DE").expect("Voting succeeds");
+    let page: PartialTallyView = invoke(
+        &chain,
+        contract,
+        "viewTallyPartial",
+        &ViewTallyPartialParameter {
+            limit: 10,
+            cursor: None,
+        },
+    );
+    assert_eq!(
+        page,
+        PartialTallyView {
+            tally: BTreeMap::from([(String::from("DE"), 2), (String::from("DK"), 1)]),
+            cursor: None,
+        }
+    );
+}
+
+/// Test that the number of voting options and the length of the description are limited.
+#[test]
+fn test_size_limits() {
+    let (mut chain, module_reference) = setup_chain_and_module(Timestamp::from_timestamp_millis(0));
+    let mut param = InitParameterBuilder::default().build();
+    param.options = vec![param.options[0].clone(); MAX_OPTIONS + 1];
+    init_contract(&mut chain, module_reference, &param)
+        .expect_err("Initialization with too many options fails");
+
+    let mut param = InitParameterBuilder::default().build();
+    param.description = "a".repeat(MAX_DESCRIPTION_LENGTH + 1);
+    init_contract(&mut chain, module_reference, &param)
+        .expect_err("Initialization with a too long description fails");
+
+    let param = InitParameterBuilder::default().build();
+    let contract = init_contract(&mut chain, module_reference, &param)
+        .expect("Initialization should succeed")
+        .contract_address;
+    let update_err = update(
+        &mut chain,
+        contract,
+        Address::Account(ACC_0),
+        "updateDescription",
+        &"a".repeat(MAX_DESCRIPTION_LENGTH + 1),
+    )
+    .expect_err("Updating fails");
+    assert_eq!(voting_error(update_err), VotingError::DescriptionTooLong);
+}
+
+/// Test that the `view` starts with an `ElectionHeader`, which other contracts parse to identify the election.
+#[test]
+fn test_view_starts_with_election_header() {
+    let (chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+    let contract = initialization.contract_address;
+
+    let header: ElectionHeader = invoke(&chain, contract, "view", &());
+    let voting_view = view(&chain, contract);
+    assert_eq!(
+        header,
+        ElectionHeader {
+            description: voting_view.description,
+            election_id: voting_view.election_id,
+            start_time: voting_view.start_time,
+            end_time: voting_view.end_time,
+        }
+    );
+}
+
+/// Test that the energy used by `view` grows less with the length of the
+/// description than the energy used by `viewMetadata`, which clones the
+/// description, as `view` borrows the strings from the state instead.
+#[test]
+fn test_view_energy() {
+    let (mut chain, module_reference) = setup_chain_and_module(Timestamp::from_timestamp_millis(0));
+    let mut contract_with = |description: &str| {
+        let param = InitParameterBuilder::default()
+            .description(description)
+            .build();
+        init_contract(&mut chain, module_reference, &param)
+            .expect("Initialization should succeed")
+            .contract_address
+    };
+    let short_contract = contract_with("a");
+    let long_contract = contract_with(&"a".repeat(MAX_DESCRIPTION_LENGTH));
+    let energy = |contract: ContractAddress, entrypoint: &str| {
+        chain
+            .contract_invoke(
+                ACC_0,
+                Address::Account(ACC_0),
+                Energy::from(100000),
+                UpdateContractPayload {
+                    amount: Amount::zero(),
+                    address: contract,
+                    receive_name: OwnedReceiveName::new_unchecked(format!("voting.{entrypoint}")),
+                    message: OwnedParameter::empty(),
+                },
+            )
+            .expect("Invoke succeeds.")
+            .energy_used
+            .energy
+    };
+
+    // Both entrypoints read the description from the state and return it,
+    // so the difference in growth is the cost of cloning it.
+    let view_growth = energy(long_contract, "view") - energy(short_contract, "view");
+    let metadata_growth =
+        energy(long_contract, "viewMetadata") - energy(short_contract, "viewMetadata");
+    assert!(view_growth < metadata_growth);
+
+    // The return value is still a `VotingView`.
+    let voting_view = view(&chain, long_contract);
+    assert_eq!(voting_view.description, "a".repeat(MAX_DESCRIPTION_LENGTH));
 }
@@ -1,11 +1,13 @@
 mod handlers;
 mod types;
+mod verifier;
 use crate::handlers::*;
 use crate::types::*;
+use crate::verifier::*;
 
 use anyhow::Context;
 use clap::Parser;
-use concordium_rust_sdk::v2::BlockIdentifier;
+use concordium_rust_sdk::{types::ContractAddress, v2::BlockIdentifier};
 use ed25519_dalek::{PublicKey, SecretKey};
 use log::info;
 use std::fs;
@@ -48,6 +50,34 @@ struct IdVerifierConfig {
         help = "Location of the secret key in binary format."
     )]
     secret_key: PathBuf,
+    #[clap(
+        long = "network",
+        default_value = "testnet",
+        help = "The network the verifier is used on, published in the manifest."
+    )]
+    network: String,
+    #[clap(
+        long = "contract-index",
+        help = "Index of the voting contract instance, published in the manifest. If set, only votes for this instance are signed."
+    )]
+    contract_index: Option<u64>,
+    #[clap(
+        long = "contract-subindex",
+        default_value = "0",
+        help = "Subindex of the voting contract instance, published in the manifest."
+    )]
+    contract_subindex: u64,
+    #[clap(
+        long = "signature-validity",
+        default_value = "600",
+        help = "Number of seconds that the signatures are valid for."
+    )]
+    signature_validity: i64,
+    #[clap(
+        long = "blocs",
+        help = "Location of a JSON file mapping bloc identifiers to the country codes of their members."
+    )]
+    blocs: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -79,20 +109,76 @@ async fn main() -> anyhow::Result<()> {
     )
     .context("Could not deserialize secret key")?;
 
-    // Create the server state.
+    // Get the blocs, if configured.
+    let blocs: Blocs = match &app.blocs {
+        Some(path) => serde_json::from_slice(&fs::read(path).context("Could not read blocs file")?)
+            .context("Could not deserialize blocs file")?,
+        None => Blocs::new(),
+    };
+    for (bloc, members) in &blocs {
+        // Bloc identifiers must be distinguishable from country codes in the signed message.
+        anyhow::ensure!(
+            bloc.len() > 2,
+            "Bloc identifier {} must be longer than two bytes",
+            bloc
+        );
+        anyhow::ensure!(
+            members
+                .iter()
+                .all(|country| voting_types::is_country_code(country)),
+            "Members of bloc {} must be ISO 3166-1 alpha-2 country codes",
+            bloc
+        );
+    }
+
+    let signing_keypair = ed25519_dalek::Keypair {
+        secret: secret_key,
+        public: public_key,
+    };
+
+    // Only sign votes for the configured contract instance, if any.
+    let contract = app
+        .contract_index
+        .map(|index| ContractAddress::new(index, app.contract_subindex));
+
+    // Sign the manifest describing the configuration of the verifier.
+    let manifest = Manifest {
+        public_key: hex::encode(signing_keypair.public.as_bytes()),
+        statement_policy: STATEMENT_POLICY.into(),
+        statement_hash: hex::encode(statement_hash()),
+        blocs: blocs.clone(),
+        contract,
+        network: app.network,
+        version: env!("CARGO_PKG_VERSION").into(),
+    };
+    let manifest =
+        SignedManifest::new(&manifest, &signing_keypair).context("Could not sign the manifest")?;
+
+    // Create the server state with identity proofs as the verification backend.
+    let verifier = IdentityProofVerifier {
+        client,
+        global_context,
+        blocs,
+    };
     let state = Server {
-        signing_keypair: Arc::new(ed25519_dalek::Keypair {
-            secret: secret_key,
-            public: public_key,
-        }),
-        global_context: Arc::new(global_context),
+        signing_keypair: Arc::new(signing_keypair),
+        contract,
+        signature_validity: chrono::Duration::seconds(app.signature_validity),
+        manifest: Arc::new(manifest),
+        verifier: Arc::new(verifier),
     };
 
     // Allow CORS.
     let cors = warp::cors()
         .allow_any_origin()
         .allow_header("Content-Type")
-        .allow_method("POST");
+        .allow_methods(vec!["GET", "POST"]);
+
+    // Setup the handler for the `/api/info` endpoint.
+    let info_state = state.clone();
+    let info = warp::get()
+        .and(warp::path!("api" / "info"))
+        .and_then(move || handle_info(info_state.clone()));
 
     // Setup the handler for the the `/api/prove` endpoint.
     let provide_proof = warp::post()
@@ -101,13 +187,14 @@ async fn main() -> anyhow::Result<()> {
         .and(warp::body::json())
         .and_then(move |request: ProofRequest| {
             info!("Got a ProofRequest: {:?}", request);
-            handle_provide_proof(client.clone(), state.clone(), request)
+            handle_provide_proof(state.clone(), request)
         });
 
     info!("Starting up HTTP server. Listening on port {}.", app.port);
 
     // Run the server.
-    let server = provide_proof
+    let server = info
+        .or(provide_proof)
         .recover(handle_rejection)
         .with(cors)
         .with(warp::trace::request());
@@ -1,22 +1,36 @@
+use crate::verifier::ProofVerifier;
 use concordium_rust_sdk::{
-    common::{Serial, Versioned},
+    common::Versioned,
     endpoints::{QueryError, RPCError},
     id::{
         constants::{ArCurve, AttributeKind},
         id_proof_types::{Proof, Statement},
-        types::{AccountAddress, GlobalContext},
+        types::AccountAddress,
     },
-    types::CredentialRegistrationID,
+    types::{ContractAddress, CredentialRegistrationID},
 };
-use ed25519_dalek::Keypair;
+use ed25519_dalek::{Keypair, Signer};
 use serde_hex::{SerHex, Strict};
-use std::sync::Arc;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+/// A mapping from the identifier of a bloc, e.g. `NORDICS`, to the two-letter
+/// country codes of its member countries.
+pub type Blocs = BTreeMap<String, BTreeSet<String>>;
 
 /// Data needed for running the verifier server.
 #[derive(Clone)]
 pub struct Server {
     pub signing_keypair: Arc<Keypair>,
-    pub global_context: Arc<GlobalContext<ArCurve>>,
+    /// The voting contract instance that signatures can be requested for, if configured.
+    pub contract: Option<ContractAddress>,
+    /// How long the signatures are valid.
+    pub signature_validity: chrono::Duration,
+    pub manifest: Arc<SignedManifest>,
+    pub verifier: Arc<dyn ProofVerifier>,
 }
 
 /// An internal error type used by this server to manage error handling.
@@ -32,6 +46,8 @@ pub enum ProofError {
     Credential,
     #[error("Statement not allowed.")]
     StatementNotAllowed,
+    #[error("Contract not allowed.")]
+    ContractNotAllowed,
 }
 
 impl From<RPCError> for ProofError {
@@ -54,6 +70,13 @@ pub struct ErrorResponse {
 pub struct ProofRequest {
     pub statement: Statement<ArCurve, AttributeKind>,
     pub address: AccountAddress,
+    /// The voting contract instance that the signature is requested for.
+    pub contract: ContractAddress,
+    /// The identifier of the election that the signature is requested for.
+    pub election_id: String,
+    /// The current nonce of the account in the voting contract, which is
+    /// returned by its `nonce` entrypoint.
+    pub nonce: u64,
     pub proof: ProofWithContext,
 }
 
@@ -63,24 +86,68 @@ pub struct ProofWithContext {
     pub proof: Versioned<Proof<ArCurve, AttributeKind>>,
 }
 
-/// The data used for the signature message to be signed and returned after verifying a proof.
-pub struct SignatureMessageData {
-    /// The account address for which the proof was verified.
-    pub account_address: AccountAddress,
-    /// The country code for the country which the account does *not* have residency in.
-    pub country_code: String,
+/// A wrapper around the bytes from [`ed25519_dalek::Signature`] which implements [`serde::Serialize`] by converting to hex.
+#[derive(serde::Serialize)]
+pub struct HexSignature(#[serde(with = "SerHex::<Strict>")] pub [u8; 64]);
+
+/// The response of the `/api/prove` endpoint, which must be included when voting.
+#[derive(serde::Serialize)]
+pub struct SignedVote {
+    /// The signature of the verifier.
+    pub signature: HexSignature,
+    /// The time until which the signature is valid, in RFC 3339 format.
+    pub expiry: String,
 }
 
-impl Serial for SignatureMessageData {
-    fn serial<B: concordium_rust_sdk::common::Buffer>(&self, out: &mut B) {
-        // Write the 32 bytes for the account address.
-        self.account_address.serial(out);
-        // Write the two bytes for the country code.
-        out.write_all(self.country_code.as_bytes())
-            .expect("Writing to buffer should never fail.");
-    }
+/// A human readable description of the only statement the verifier accepts.
+pub const STATEMENT_POLICY: &str =
+    "The country of residence (attribute tag 4) is not in a set of exactly one two-letter country code, \
+     or not in a set of exactly the member countries of one of the blocs.";
+
+/// The SHA2-256 hash of the `STATEMENT_POLICY`, which is included in the signed
+/// message. The voting contract is initialized with the same hash, so it only
+/// accepts signatures from verifiers that check this exact statement.
+pub fn statement_hash() -> [u8; 32] {
+    Sha256::digest(STATEMENT_POLICY.as_bytes()).into()
 }
 
-/// A wrapper around the bytes from [`ed25519_dalek::Signature`] which implements [`serde::Serialize`] by converting to hex.
+/// The configuration the verifier is running with.
 #[derive(serde::Serialize)]
-pub struct HexSignature(#[serde(with = "SerHex::<Strict>")] pub [u8; 64]);
+pub struct Manifest {
+    /// The hex encoded public key of the verifier.
+    pub public_key: String,
+    /// A description of the statements that the verifier accepts.
+    pub statement_policy: String,
+    /// The hex encoded SHA2-256 hash of the `statement_policy`, which the
+    /// voting contract must be initialized with.
+    pub statement_hash: String,
+    /// The blocs that statements can exclude.
+    pub blocs: Blocs,
+    /// The address of the voting contract instance that the signatures are meant for, if configured.
+    pub contract: Option<ContractAddress>,
+    /// The network the verifier is used on, e.g. `testnet`.
+    pub network: String,
+    /// The version of the verifier software.
+    pub version: String,
+}
+
+/// The response of the `/api/info` endpoint.
+/// The `manifest` is a JSON encoded [`Manifest`], and the `signature` is the
+/// signature of the verifier on the bytes of that string.
+#[derive(serde::Serialize)]
+pub struct SignedManifest {
+    pub manifest: String,
+    pub signature: HexSignature,
+}
+
+impl SignedManifest {
+    /// Serialize the manifest and sign it with the key pair of the verifier.
+    pub fn new(manifest: &Manifest, keypair: &Keypair) -> serde_json::Result<Self> {
+        let manifest = serde_json::to_string(manifest)?;
+        let signature = keypair.sign(manifest.as_bytes());
+        Ok(Self {
+            manifest,
+            signature: HexSignature(signature.into()),
+        })
+    }
+}
@@ -0,0 +1,120 @@
+use crate::types::*;
+use concordium_rust_sdk::{
+    common::to_bytes,
+    id::{
+        constants::{ArCurve, AttributeKind},
+        id_proof_types::{AtomicStatement, AttributeNotInSetStatement},
+        types::{AccountCredentialWithoutProofs, AttributeTag, GlobalContext},
+    },
+    v2::BlockIdentifier,
+};
+use std::collections::BTreeSet;
+
+/// A backend that checks whether a request proves that an account is eligible to vote.
+///
+/// The HTTP and signing layers of the server are independent of the backend, so alternative
+/// eligibility checks, e.g. custom issuers or off-chain registries, can be plugged in by
+/// implementing this trait.
+#[async_trait::async_trait]
+pub trait ProofVerifier: Send + Sync {
+    /// Check the request and return the region, i.e. the country code or bloc identifier,
+    /// that the account has been proven *not* to reside in.
+    /// This region is signed together with the account address.
+    async fn verify(&self, request: &ProofRequest) -> Result<String, ProofError>;
+}
+
+/// The backend that checks identity proofs against the credentials on chain.
+pub struct IdentityProofVerifier {
+    /// The client for communicating with the node.
+    pub client: concordium_rust_sdk::v2::Client,
+    /// The cryptographic parameters of the chain.
+    pub global_context: GlobalContext<ArCurve>,
+    /// The blocs that statements can exclude.
+    pub blocs: Blocs,
+}
+
+#[async_trait::async_trait]
+impl ProofVerifier for IdentityProofVerifier {
+    /// Checks that the statement is valid and that the proof is correct.
+    async fn verify(&self, request: &ProofRequest) -> Result<String, ProofError> {
+        let mut client = self.client.clone();
+        let cred_id = &request.proof.credential;
+        let acc_info = client
+            .get_account_info(&request.address.into(), BlockIdentifier::LastFinal)
+            .await?;
+
+        // TODO The account may have more that one credential, check the remaining ones.
+        let credential = acc_info
+            .response
+            .account_credentials
+            .get(&0.into())
+            .ok_or(ProofError::Credential)?;
+
+        if to_bytes(credential.value.cred_id()) != to_bytes(cred_id) {
+            return Err(ProofError::Credential);
+        }
+
+        // Get the commitments from the credential.
+        let commitments = match &credential.value {
+            AccountCredentialWithoutProofs::Initial { icdv: _, .. } => {
+                return Err(ProofError::NotAllowed);
+            }
+            AccountCredentialWithoutProofs::Normal { commitments, .. } => commitments,
+        };
+
+        // Check that the statement sent is that the account is *not* from one particular country,
+        // or from any of the member countries of a bloc.
+        const COUNTRY_OF_RESIDENCY: u8 = 4;
+        let country_code = match &request.statement.statements[..] {
+            [AtomicStatement::AttributeNotInSet {
+                statement:
+                    AttributeNotInSetStatement {
+                        attribute_tag: AttributeTag(tag),
+                        set,
+                        ..
+                    },
+
+            }]
+                // The proof is about country of residency.
+                if *tag == COUNTRY_OF_RESIDENCY =>
+            {
+                excluded_region(set, &self.blocs).ok_or(ProofError::StatementNotAllowed)?
+            }
+            _ => return Err(ProofError::StatementNotAllowed),
+        };
+
+        // The challenge is not really used here, as there is no temporal aspect to the proof,
+        // but the challenge must match the one specified in the dapp.
+        // Otherwise the proof won't be valid.
+        let challenge = [0u8; 4];
+
+        // Verify the proof
+        if request.statement.verify(
+            &challenge,
+            &self.global_context,
+            cred_id.as_ref(),
+            commitments,
+            &request.proof.proof.value,
+        ) {
+            Ok(country_code)
+        } else {
+            // Return an error if the proof is invalid.
+            Err(ProofError::InvalidProofs)
+        }
+    }
+}
+
+/// Get the identifier of the region that a set of countries in a statement excludes.
+/// This is either the country code, if the set consists of a single two-letter country code,
+/// or the identifier of the bloc whose member countries are exactly the countries in the set.
+fn excluded_region(set: &BTreeSet<AttributeKind>, blocs: &Blocs) -> Option<String> {
+    match set.first() {
+        // There is only one country listed, and the country code is two bytes long.
+        Some(country) if set.len() == 1 && country.0.len() == 2 => Some(country.0.clone()),
+        // Otherwise the set must match a bloc exactly.
+        _ => blocs
+            .iter()
+            .find(|(_, members)| members.iter().eq(set.iter().map(|country| &country.0)))
+            .map(|(bloc, _)| bloc.clone()),
+    }
+}
@@ -0,0 +1,280 @@
+//! A registry of the elections created from the voting contract.
+//!
+//! # Description
+//! Dashboards and frontends need to find the elections that are running,
+//! without hardcoding the address of every voting instance. This contract
+//! stores the reference of the module that the voting contract is deployed in,
+//! and keeps a registry of the elections initialized from it.
+//!
+//! Contracts on Concordium cannot initialize other contract instances, so an
+//! organizer creates an election in two steps: it initializes a new instance
+//! of the `voting` contract from the module given by `viewModuleReference`,
+//! and then registers the instance with `registerElection`. The registry
+//! checks that the instance was initialized from that module, so only voting
+//! contracts can be registered, and records the description and deadline
+//! from the `view` of the instance and its admin from `viewAdmin`.
+//!
+//! Querying the module of an instance requires protocol version 7, so the
+//! registry must be deployed to a chain running protocol version 7 or later.
+//!
+//! # Operations
+//! The contract allows for
+//!  - `initializing` the registry with the module reference of the voting
+//!    contract;
+//!  - `registerElection` to add a voting instance to the registry;
+//!  - `listElections` to page through the registered elections;
+//!  - `viewModuleReference` to get the module to initialize elections from.
+//!
+//! # Tests
+//! The tests exist in the `./tests/tests.rs` file.
+
+use concordium_std::*;
+use voting_types::ElectionHeader;
+
+/// The maximum number of elections returned by a single call to
+/// `listElections`, which bounds the size of the return value.
+pub const MAX_LIST_LIMIT: u32 = 100;
+
+/// A registered election.
+#[derive(Serialize, SchemaType, Clone, PartialEq, Eq, Debug)]
+pub struct ElectionEntry {
+    /// The address of the voting instance.
+    pub address: ContractAddress,
+    /// The admin of the voting instance at the time of registration, which is
+    /// the account that initialized it unless the role was transferred.
+    pub admin: AccountAddress,
+    /// The description of the election at the time of registration.
+    pub description: String,
+    /// The last timestamp that an account can vote, at the time of
+    /// registration.
+    pub end_time: Timestamp,
+}
+
+/// The parameter type for the contract function `listElections`.
+#[derive(Serialize, SchemaType)]
+pub struct ListElectionsParameter {
+    /// The number of elections to skip, in the order of registration.
+    pub skip: u32,
+    /// The maximum number of elections to return, at most `MAX_LIST_LIMIT`.
+    pub limit: u32,
+}
+
+/// The events logged by the contract.
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub enum FactoryEvent {
+    /// An election was added to the registry.
+    ElectionRegistered {
+        /// The address of the voting instance.
+        address: ContractAddress,
+        /// The admin of the voting instance.
+        admin: AccountAddress,
+    },
+}
+
+/// The different errors that the contract functions can produce.
+#[derive(Reject, Serialize, PartialEq, Eq, Debug, SchemaType)]
+pub enum FactoryError {
+    /// Raised when parsing the parameter failed.
+    #[from(ParseError)]
+    ParsingFailed,
+    /// Raised when a smart contract tries to register an election.
+    ContractSender,
+    /// Raised when the registered instance does not respond to `view` like a
+    /// voting contract.
+    NotAVotingContract,
+    /// Raised when registering an election that is already registered.
+    AlreadyRegistered,
+    /// Raised when the log is full.
+    LogFull,
+    /// Raised when the log is malformed.
+    LogMalformed,
+    /// Raised when the registered instance was not initialized from the
+    /// module of the voting contract.
+    WrongModule,
+}
+
+/// Mapping the logging errors to `FactoryError`.
+impl From<LogError> for FactoryError {
+    fn from(le: LogError) -> Self {
+        match le {
+            LogError::Full => Self::LogFull,
+            LogError::Malformed => Self::LogMalformed,
+        }
+    }
+}
+
+/// A custom alias type for the `Result` type with the error type fixed to
+/// `FactoryError`.
+pub type FactoryResult<T> = Result<T, FactoryError>;
+
+/// The contract state.
+#[derive(Serial, DeserialWithState)]
+#[concordium(state_parameter = "S")]
+struct State<S> {
+    /// The module that the voting contract is deployed in.
+    module_reference: ModuleReference,
+    /// The map connects the position of an election in the order of
+    /// registration to the election.
+    elections: StateMap<u32, ElectionEntry, S>,
+    /// The number of registered elections. This is tracked separately, as the
+    /// length of a `StateMap` is not available.
+    election_count: u32,
+    /// The addresses of the registered elections, which prevents registering
+    /// an election twice.
+    registered: StateSet<ContractAddress, S>,
+}
+
+// Contract functions
+
+/// Initialize the registry with the reference of the module that the voting
+/// contract is deployed in.
+///
+/// It rejects if it fails to parse the parameter.
+#[init(contract = "voting_factory", parameter = "ModuleReference")]
+fn init<S: HasStateApi>(
+    ctx: &impl HasInitContext,
+    state_builder: &mut StateBuilder<S>,
+) -> InitResult<State<S>> {
+    // Parse the parameter.
+    let module_reference: ModuleReference = ctx.parameter_cursor().get()?;
+
+    // Set the state.
+    Ok(State {
+        module_reference,
+        elections: state_builder.new_map(),
+        election_count: 0,
+        registered: state_builder.new_set(),
+    })
+}
+
+/// Enables accounts to add a voting instance to the registry. The registry
+/// checks that the instance was initialized from the module of the voting
+/// contract, and records its description and `end_time` from its `view`, and
+/// its admin from `viewAdmin`. Anyone can register an election, so the
+/// registry does not record the sender, which may not be the organizer.
+///
+/// It rejects if:
+/// - It fails to parse the parameter.
+/// - A contract tries to register an election.
+/// - The election is already registered.
+/// - The instance does not exist.
+/// - The instance was not initialized from the module of the voting contract.
+/// - The instance does not respond to `view` or `viewAdmin` like a voting
+///   contract.
+#[receive(
+    contract = "voting_factory",
+    name = "registerElection",
+    mutable,
+    parameter = "ContractAddress",
+    error = "FactoryError",
+    enable_logger
+)]
+fn register_election<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &mut impl HasHost<State<S>, StateApiType = S>,
+    logger: &mut impl HasLogger,
+) -> FactoryResult<()> {
+    // Ensure that the sender is an account.
+    if ctx.sender().is_contract() {
+        return Err(FactoryError::ContractSender);
+    }
+
+    // Parse the parameter.
+    let address: ContractAddress = ctx.parameter_cursor().get()?;
+    if host.state().registered.contains(&address) {
+        return Err(FactoryError::AlreadyRegistered);
+    }
+
+    // Check that the instance runs the voting contract.
+    let module_reference = host
+        .contract_module_reference(address)
+        .map_err(|_| FactoryError::NotAVotingContract)?;
+    if module_reference != host.state().module_reference {
+        return Err(FactoryError::WrongModule);
+    }
+
+    // Query the election information from the instance.
+    let mut return_value = host
+        .invoke_contract_read_only(
+            &address,
+            &(),
+            EntrypointName::new_unchecked("view"),
+            Amount::zero(),
+        )
+        .map_err(|_| FactoryError::NotAVotingContract)?
+        .ok_or(FactoryError::NotAVotingContract)?;
+    let view: ElectionHeader = return_value
+        .get()
+        .map_err(|_| FactoryError::NotAVotingContract)?;
+
+    // Query the admin of the instance, which may differ from the sender.
+    let mut return_value = host
+        .invoke_contract_read_only(
+            &address,
+            &(),
+            EntrypointName::new_unchecked("viewAdmin"),
+            Amount::zero(),
+        )
+        .map_err(|_| FactoryError::NotAVotingContract)?
+        .ok_or(FactoryError::NotAVotingContract)?;
+    let admin: AccountAddress = return_value
+        .get()
+        .map_err(|_| FactoryError::NotAVotingContract)?;
+
+    let state = host.state_mut();
+    state.registered.insert(address);
+    state.elections.insert(
+        state.election_count,
+        ElectionEntry {
+            address,
+            admin,
+            description: view.description,
+            end_time: view.end_time,
+        },
+    );
+    state.election_count += 1;
+    logger.log(&FactoryEvent::ElectionRegistered { address, admin })?;
+
+    Ok(())
+}
+
+/// Get a page of the registered elections, in the order of registration.
+/// At most `MAX_LIST_LIMIT` elections are returned, even if the `limit` is
+/// larger.
+#[receive(
+    contract = "voting_factory",
+    name = "listElections",
+    parameter = "ListElectionsParameter",
+    return_value = "Vec<ElectionEntry>",
+    error = "FactoryError"
+)]
+fn list_elections<S: HasStateApi>(
+    ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> FactoryResult<Vec<ElectionEntry>> {
+    // Parse the parameter.
+    let param: ListElectionsParameter = ctx.parameter_cursor().get()?;
+
+    let state = host.state();
+    let end = param
+        .skip
+        .saturating_add(param.limit.min(MAX_LIST_LIMIT))
+        .min(state.election_count);
+    let elections = (param.skip..end)
+        .filter_map(|index| state.elections.get(&index).map(|entry| entry.clone()))
+        .collect();
+    Ok(elections)
+}
+
+/// Get the reference of the module that the voting contract is deployed in.
+#[receive(
+    contract = "voting_factory",
+    name = "viewModuleReference",
+    return_value = "ModuleReference"
+)]
+fn view_module_reference<S: HasStateApi>(
+    _ctx: &impl HasReceiveContext,
+    host: &impl HasHost<State<S>, StateApiType = S>,
+) -> ReceiveResult<ModuleReference> {
+    Ok(host.state().module_reference)
+}
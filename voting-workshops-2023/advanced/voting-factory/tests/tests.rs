@@ -0,0 +1,261 @@
+//! This module contains integration tests for the election registry.
+//!
+//! The best way to run these tests are with `cargo concordium test --out concordium-out/module.wasm.v1`
+//! as that will make sure to compile the module before running the tests.
+//!
+//! The tests additionally load the module of the voting contract.
+//! Build it first by running the same command in the `../smart-contract` folder.
+
+use concordium_smart_contract_testing::*;
+use concordium_std::{collections::BTreeMap, Deserial, HashSha2256, Serial, Timestamp};
+use voting_contract::{InitParameter, OptionInfo, TieBreakingPolicy};
+use voting_factory::*;
+
+/// An account address of all 0s.
+const ACC_0: AccountAddress = AccountAddress([0; 32]);
+/// An account address of all 1s.
+const ACC_1: AccountAddress = AccountAddress([1; 32]);
+/// A `Signer` used for signing the transactions while testing.
+const SIGNER: Signer = Signer::with_one_key();
+/// The unix epoch time in milliseconds for noon at Christmas eve 2023.
+const CHRISTMAS_EVE_EPOCH: u64 = 1701873444000;
+
+/// Helper function that creates the `InitParameter` of an election without
+/// verifiers, described by `description`.
+fn election_parameter(description: &str) -> InitParameter {
+    InitParameter {
+        description: String::from(description),
+        election_id: String::from(description),
+        options: ["DK", "DE", "IT"]
+            .iter()
+            .map(|code| OptionInfo {
+                code: String::from(*code),
+                label: format!("Country {}", code),
+                url: format!("https://example.com/{}.svg", code),
+                image_hash: None,
+            })
+            .collect(),
+        start_time: None,
+        end_time: Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH),
+        registration_end_time: Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH),
+        verifier_public_keys: vec![],
+        verifier_threshold: 0,
+        extension_approval_percentage: 50,
+        blocs: BTreeMap::new(),
+        min_balance: None,
+        allow_vote_change: true,
+        change_cooldown: None,
+        max_changes: None,
+        max_votes_per_option: None,
+        quorum: 0,
+        tie_breaking: TieBreakingPolicy::FirstListed,
+        weights: None,
+        token_weights: None,
+        quadratic_voting: false,
+        tally_authority: None,
+        allow_list_root: None,
+        credential_registry: None,
+        recurrence: None,
+        contract_voters: vec![],
+        max_voters: None,
+        funds_recipient: None,
+        retention_period: None,
+        expected_voters: None,
+        record_vote_history: false,
+        logs_only: false,
+        certification_key: None,
+        badge_contract: None,
+        time_weighted: false,
+        statement_hash: HashSha2256([0; 32]),
+    }
+}
+
+/// Helper function that sets up a chain with two accounts, deploys the voting
+/// module, and initializes the registry with its module reference.
+fn setup_chain_and_factory() -> (Chain, ModuleReference, ContractAddress) {
+    let mut chain = Chain::new_with_time(Timestamp::from_timestamp_millis(0));
+    chain.create_account(Account::new(ACC_0, Amount::from_ccd(10000)));
+    chain.create_account(Account::new(ACC_1, Amount::from_ccd(10000)));
+
+    // Deploy both modules.
+    let voting_module = module_load_v1("../smart-contract/concordium-out/module.wasm.v1")
+        .expect("Voting module file should exist");
+    let voting_module_reference = chain
+        .module_deploy_v1(SIGNER, ACC_0, voting_module)
+        .expect("Deploying valid module should succeed")
+        .module_reference;
+    let factory_module =
+        module_load_v1("./concordium-out/module.wasm.v1").expect("Module file should exist");
+    let factory_module_reference = chain
+        .module_deploy_v1(SIGNER, ACC_0, factory_module)
+        .expect("Deploying valid module should succeed")
+        .module_reference;
+
+    // Initialize the registry.
+    let factory = chain
+        .contract_init(
+            SIGNER,
+            ACC_0,
+            Energy::from(10000),
+            InitContractPayload {
+                amount: Amount::zero(),
+                mod_ref: factory_module_reference,
+                init_name: OwnedContractName::new_unchecked(String::from("init_voting_factory")),
+                param: OwnedParameter::from_serial(&voting_module_reference)
+                    .expect("Valid parameter size"),
+            },
+        )
+        .expect("Initialization should succeed")
+        .contract_address;
+
+    (chain, voting_module_reference, factory)
+}
+
+/// Helper function that initializes an election from the voting module.
+fn create_election(
+    chain: &mut Chain,
+    module_reference: ModuleReference,
+    description: &str,
+) -> ContractAddress {
+    chain
+        .contract_init(
+            SIGNER,
+            ACC_0,
+            Energy::from(100000),
+            InitContractPayload {
+                amount: Amount::zero(),
+                mod_ref: module_reference,
+                init_name: OwnedContractName::new_unchecked(String::from("init_voting")),
+                param: OwnedParameter::from_serial(&election_parameter(description))
+                    .expect("Valid parameter size"),
+            },
+        )
+        .expect("Initialization should succeed")
+        .contract_address
+}
+
+/// Helper function that calls the `entrypoint` of the registry at `address` from the account `sender`.
+fn update(
+    chain: &mut Chain,
+    address: ContractAddress,
+    sender: AccountAddress,
+    entrypoint: &str,
+    parameter: &impl Serial,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    chain.contract_update(
+        SIGNER,
+        sender,
+        Address::Account(sender),
+        Energy::from(100000),
+        UpdateContractPayload {
+            amount: Amount::zero(),
+            address,
+            receive_name: OwnedReceiveName::new_unchecked(format!("voting_factory.{}", entrypoint)),
+            message: OwnedParameter::from_serial(parameter).expect("Parameter has valid length"),
+        },
+    )
+}
+
+/// Helper function that invokes the `entrypoint` of the registry at `address` with the `parameter`
+/// and deserializes the return value.
+fn invoke<T: Deserial>(
+    chain: &Chain,
+    address: ContractAddress,
+    entrypoint: &str,
+    parameter: &impl Serial,
+) -> T {
+    let invoke = chain
+        .contract_invoke(
+            ACC_0,
+            Address::Account(ACC_0),
+            Energy::from(100000),
+            UpdateContractPayload {
+                amount: Amount::zero(),
+                address,
+                receive_name: OwnedReceiveName::new_unchecked(format!(
+                    "voting_factory.{}",
+                    entrypoint
+                )),
+                message: OwnedParameter::from_serial(parameter)
+                    .expect("Parameter has valid length"),
+            },
+        )
+        .expect("Invoke succeeds.");
+    from_bytes(&invoke.return_value).expect("Return value should have the expected type")
+}
+
+/// Helper function that extracts the `FactoryError` from a rejected update.
+fn factory_error(update: ContractInvokeError) -> FactoryError {
+    // Get the return value by unwrapping the `Option`.
+    let return_value = update.return_value().expect("A value should be returned");
+    // Deserialize it into a `FactoryError`.
+    from_bytes(return_value).expect("Return value should be a FactoryError.")
+}
+
+/// Test that elections created from the voting module can be registered once and listed.
+#[test]
+fn test_register_and_list_elections() {
+    let (mut chain, voting_module_reference, factory) = setup_chain_and_factory();
+
+    let module_reference: ModuleReference = invoke(&chain, factory, "viewModuleReference", &());
+    assert_eq!(module_reference, voting_module_reference);
+
+    let first = create_election(&mut chain, voting_module_reference, "First poll");
+    let second = create_election(&mut chain, voting_module_reference, "Second poll");
+    update(&mut chain, factory, ACC_0, "registerElection", &first).expect("Registering succeeds");
+    // Anyone can register an election, but the registry records the admin of the instance.
+    update(&mut chain, factory, ACC_1, "registerElection", &second).expect("Registering succeeds");
+
+    // An election cannot be registered twice.
+    let update_err = update(&mut chain, factory, ACC_1, "registerElection", &first)
+        .expect_err("Registering fails");
+    assert_eq!(factory_error(update_err), FactoryError::AlreadyRegistered);
+
+    // Only voting contracts can be registered.
+    let update_err = update(&mut chain, factory, ACC_0, "registerElection", &factory)
+        .expect_err("Registering fails");
+    assert_eq!(factory_error(update_err), FactoryError::WrongModule);
+    let update_err = update(
+        &mut chain,
+        factory,
+        ACC_0,
+        "registerElection",
+        &ContractAddress::new(1000, 0),
+    )
+    .expect_err("Registering fails");
+    assert_eq!(factory_error(update_err), FactoryError::NotAVotingContract);
+
+    let elections: Vec<ElectionEntry> = invoke(
+        &chain,
+        factory,
+        "listElections",
+        &ListElectionsParameter { skip: 0, limit: 10 },
+    );
+    assert_eq!(
+        elections,
+        [
+            ElectionEntry {
+                address: first,
+                admin: ACC_0,
+                description: String::from("First poll"),
+                end_time: Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH),
+            },
+            ElectionEntry {
+                address: second,
+                admin: ACC_0,
+                description: String::from("Second poll"),
+                end_time: Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH),
+            },
+        ]
+    );
+
+    // The elections can be paged through.
+    let elections: Vec<ElectionEntry> = invoke(
+        &chain,
+        factory,
+        "listElections",
+        &ListElectionsParameter { skip: 1, limit: 10 },
+    );
+    assert_eq!(elections.len(), 1);
+    assert_eq!(elections[0].address, second);
+}
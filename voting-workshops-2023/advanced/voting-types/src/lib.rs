@@ -0,0 +1,432 @@
+//! Types shared between the components of the advanced voting example.
+//!
+//! The smart contract and the verifier backend both need to agree on the
+//! format of a registration and on the exact bytes of the message signed by the
+//! verifier. Keeping these types in a single crate ensures that the
+//! components cannot diverge.
+//!
+//! The contract and the verifier are the only Rust consumers in this
+//! repository. There is no CLI or indexer yet; when one is added, it should
+//! depend on this crate instead of declaring the types again.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use concordium_std::{collections::BTreeMap, *};
+
+/// A voting option, i.e. a country code.
+pub type VotingOption = String;
+/// The voting options are stored in a vector. The vector index is used to refer
+/// to a specific voting option.
+pub type VoteIndex = u32;
+/// Number of votes, or the sum of the weights of the voters in a weighted
+/// election.
+pub type VoteCount = u64;
+
+/// The officially assigned ISO 3166-1 alpha-2 country codes, in ascending
+/// order.
+const ISO_3166_ALPHA_2: [&str; 249] = [
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+/// Check whether `code` is an officially assigned ISO 3166-1 alpha-2 country
+/// code, i.e. two uppercase ASCII letters that name a country.
+pub fn is_country_code(code: &str) -> bool {
+    code.len() == 2
+        && code.bytes().all(|byte| byte.is_ascii_uppercase())
+        && ISO_3166_ALPHA_2.binary_search(&code).is_ok()
+}
+
+/// The public key of a verifier. Verifiers can use one of two signature schemes.
+#[derive(Serialize, SchemaType, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VerifierPublicKey {
+    /// An Ed25519 public key.
+    Ed25519(PublicKeyEd25519),
+    /// A compressed ECDSA public key on the secp256k1 curve.
+    EcdsaSecp256k1(PublicKeyEcdsaSecp256k1),
+}
+
+/// A signature from a verifier, using the signature scheme of its public key.
+#[derive(Serialize, SchemaType, Clone, Copy)]
+pub enum VerifierSignature {
+    /// An Ed25519 signature on the message.
+    Ed25519(SignatureEd25519),
+    /// An ECDSA signature on the SHA2-256 hash of the message.
+    EcdsaSecp256k1(SignatureEcdsaSecp256k1),
+}
+
+/// A registration for a voting option including signatures from the verifiers, which verify that the voter does *not*
+/// live in the country. After registering, the account can vote for the country by providing just the country code.
+#[derive(Serialize, SchemaType)]
+pub struct RegisterParameter {
+    /// The country registered for.
+    pub country_code: VotingOption,
+    /// The time until which the signatures are valid, as chosen by the verifiers.
+    pub expiry: Timestamp,
+    /// The signatures from the verifiers on the `SignatureMessageData`.
+    /// Each signature is keyed by the index of the verifier key in the contract that issued it,
+    /// so every verifier can contribute at most one signature.
+    pub signatures: BTreeMap<u8, VerifierSignature>,
+}
+
+/// The check that failed when a signature was rejected.
+#[derive(Serialize, SchemaType, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SignatureCheck {
+    /// The signatures are not made with the keys of the election, e.g.
+    /// because they refer to a verifier key that does not exist, use another
+    /// signature scheme than the key, or there are too few of them.
+    Key,
+    /// The signature does not match the signed message, e.g. because it was
+    /// issued for another account, election, nonce, or voting option.
+    Message,
+    /// The signatures have expired.
+    Expiry,
+}
+
+/// The different errors that the contract functions can produce.
+#[derive(Reject, Serialize, PartialEq, Eq, Debug, SchemaType)]
+pub enum VotingError {
+    /// Raised when parsing the parameter failed.
+    #[from(ParseError)]
+    ParsingFailed,
+    /// Raised when the vote is placed after the election has ended.
+    VotingFinished,
+    /// Raised when voting for a voting option that does not exist.
+    InvalidVotingOption {
+        /// The voting option that was rejected.
+        option: VotingOption,
+    },
+    /// Raised when a smart contract tries to participate in the election. Only
+    /// accounts, and the contract voters of the election, are allowed to vote.
+    ContractVoter,
+    /// The signature from the verifier is invalid.
+    InvalidSignature {
+        /// The check of the signature that failed.
+        check: SignatureCheck,
+    },
+    /// Raised when the log is full.
+    LogFull,
+    /// Raised when the log is malformed.
+    LogMalformed,
+    /// Raised when an account that has not voted tries to propose or approve
+    /// an extension of the `end_time`, or to retract its vote.
+    NotAVoter,
+    /// Raised when proposing an extension while another extension proposal is
+    /// pending.
+    ExtensionAlreadyProposed,
+    /// Raised when the proposed `end_time` is not later than the current
+    /// `end_time`.
+    InvalidExtension,
+    /// Raised when approving an extension while no extension is proposed.
+    NoExtensionProposed,
+    /// Raised when an account approves the same extension twice.
+    ExtensionAlreadyApproved,
+    /// Raised when the `extension_approval_percentage` is 0 or larger than
+    /// 100.
+    InvalidApprovalPercentage,
+    /// Raised when a bloc provided at initialization is not a voting option,
+    /// has an identifier of two bytes or less, or has members that are not
+    /// two-letter country codes.
+    InvalidBloc,
+    /// Raised when the balance of the voter is below the minimum balance
+    /// required to vote.
+    InsufficientStake,
+    /// Raised when finalizing the election or withdrawing a deposit before the
+    /// `end_time` has passed.
+    VotingNotFinished,
+    /// Raised when finalizing an election that has already been finalized.
+    AlreadyFinalized,
+    /// Raised when requesting the result of an election that has not been
+    /// finalized.
+    NotFinalized,
+    /// Raised when an account other than the admin calls an admin entrypoint.
+    NotAdmin,
+    /// Raised when voting while the election is paused.
+    ElectionPaused,
+    /// Raised when interacting with an election that the admin has cancelled.
+    ElectionCancelled,
+    /// Raised when changing the voting options after votes have been cast.
+    VotesAlreadyCast,
+    /// Raised when adding a voting option that already exists, or when the
+    /// election is initialized with two voting options with the same code.
+    DuplicateOption,
+    /// Raised when removing a voting option that has votes.
+    OptionHasVotes,
+    /// Raised when the verifier threshold provided at initialization is 0
+    /// while there are verifier keys, or larger than the number of verifier
+    /// keys, or there are more than 256 verifier keys.
+    InvalidVerifierThreshold,
+    /// Raised when referring to a verifier key that does not exist.
+    InvalidVerifierIndex,
+    /// Raised when voting for a voting option that the account has not
+    /// registered for.
+    NotRegistered,
+    /// Raised when registering after the registration has closed.
+    RegistrationFinished,
+    /// Raised when voting before the `start_time` of the election.
+    VotingNotStarted,
+    /// Raised when an account votes again in an election that does not allow
+    /// vote changes. Also raised when an account that has voted delegates its
+    /// voting power.
+    AlreadyVoted,
+    /// Raised when an account changes its vote before the `change_cooldown`
+    /// has passed since its last vote.
+    VoteChangeTooSoon,
+    /// Raised when an account changes its vote more than `max_changes` times.
+    TooManyVoteChanges,
+    /// Raised when retracting a vote in an election that does not allow vote
+    /// changes.
+    VoteChangeNotAllowed,
+    /// Raised when approving a write-in that no account has voted for.
+    NoPendingWriteIn,
+    /// Raised when voting for a voting option that has reached the maximum
+    /// number of votes.
+    OptionFull,
+    /// Raised when an account without a voting weight votes in a weighted
+    /// election.
+    NoVotingWeight,
+    /// Raised when the election is initialized with more than one of fixed
+    /// weights, token weights, and quadratic voting, or with one of them and
+    /// encrypted ballots.
+    ConflictingWeights,
+    /// Raised when the token balance of an account cannot be queried from the
+    /// CIS-2 contract in an election weighted by a token.
+    TokenBalanceQueryFailed,
+    /// Raised when an account votes with `vote` in a quadratic election, which
+    /// requires a deposit.
+    DepositRequired,
+    /// Raised when an account votes with a deposit in an election that is not
+    /// quadratic.
+    DepositsNotAccepted,
+    /// Raised when an account without a deposit tries to withdraw, or the
+    /// admin withdraws funds while the contract holds no CCD beyond the
+    /// deposits.
+    NothingToWithdraw,
+    /// Raised when an account that delegates its voting power votes or
+    /// delegates again.
+    VotesDelegated,
+    /// Raised when a delegation would form a cycle, e.g. when an account
+    /// delegates to itself.
+    DelegationCycle,
+    /// Raised when an account that does not delegate its voting power
+    /// undelegates.
+    NotDelegated,
+    /// Raised when a delegation would create a chain of more than
+    /// `MAX_DELEGATION_DEPTH` delegations.
+    DelegationTooDeep,
+    /// Raised when the signature of the voter on a vote submitted on its
+    /// behalf is invalid, or the voter account does not exist.
+    InvalidVoterSignature,
+    /// Raised when casting a public vote, delegating, retracting a vote, or
+    /// finalizing without the tally authority in an election with encrypted
+    /// ballots.
+    EncryptedElection,
+    /// Raised when casting an encrypted ballot or posting a tally in an
+    /// election without encrypted ballots.
+    NotEncryptedElection,
+    /// Raised when an encrypted ballot does not contain one ciphertext per
+    /// voting option.
+    InvalidBallot,
+    /// Raised when an account other than the tally authority posts the tally.
+    NotTallyAuthority,
+    /// Raised when a posted tally does not contain one count per voting
+    /// option, has votes for a removed option, or counts more votes than there
+    /// are voters.
+    InvalidTally,
+    /// Raised when an account that has not proven that it is on the allow-list
+    /// of a closed election registers, writes in, or delegates.
+    NotAllowListed,
+    /// Raised when proving membership of the allow-list in an election without
+    /// an allow-list.
+    NoAllowList,
+    /// Raised when a Merkle proof of membership of the allow-list is invalid.
+    InvalidMerkleProof,
+    /// Raised when registering with signatures from the verifiers in an
+    /// election that checks credentials instead.
+    CredentialRequired,
+    /// Raised when registering a credential in an election that does not check
+    /// credentials.
+    NoCredentialRegistry,
+    /// Raised when registering a credential that is bound to another account,
+    /// or from an account that is bound to another credential.
+    CredentialInUse,
+    /// Raised when registering or voting with a credential that is revoked,
+    /// expired, or not yet active.
+    CredentialNotActive,
+    /// Raised when the status of a credential cannot be queried from the CIS-4
+    /// registry.
+    CredentialQueryFailed,
+    /// Raised when the election is initialized with an `end_time` that has
+    /// already passed.
+    EndTimeInPast,
+    /// Raised when the election is initialized without voting options.
+    NoOptions,
+    /// Raised when a country code is not an ISO 3166-1 alpha-2 code, or a
+    /// voting option is neither a country code nor a bloc.
+    MalformedCountryCode,
+    /// Raised when starting a runoff although a voting option has received
+    /// more than half of the votes, or there are fewer than two voting
+    /// options.
+    RunoffNotNeeded,
+    /// Raised when starting a runoff after a runoff has already been started.
+    RunoffAlreadyStarted,
+    /// Raised when the election is initialized with a `recurrence` of 0, or
+    /// with a `recurrence` and quadratic voting.
+    InvalidRecurrence,
+    /// Raised when upgrading to a module that does not exist, does not contain
+    /// the contract, or has an unsupported version.
+    UpgradeFailed,
+    /// Raised when the migration entrypoint rejects after an upgrade.
+    MigrationFailed,
+    /// Raised when the election is initialized with contract voters and with
+    /// weighted or encrypted votes.
+    InvalidContractVoters,
+    /// Raised when a blacklisted account tries to register, vote, or delegate,
+    /// or an account tries to delegate to a blacklisted account.
+    Blacklisted,
+    /// Raised when blacklisting an account that is already blacklisted.
+    AlreadyBlacklisted,
+    /// Raised when unblacklisting an account that is not blacklisted.
+    NotBlacklisted,
+    /// Raised when a new account votes after `max_voters` accounts have voted.
+    VoterLimitReached,
+    /// Raised when the election is initialized with a `max_voters` of 0.
+    InvalidVoterLimit,
+    /// Raised when CCD are attached to `vote`.
+    UnexpectedFunds,
+    /// Raised when transferring CCD to the `funds_recipient` fails.
+    TransferFailed,
+    /// Raised when cleaning up an election without a `retention_period`, or
+    /// before the `retention_period` has passed since the `end_time`.
+    CleanupNotAllowed,
+    /// Raised when initializing an election that only logs the votes with
+    /// features that require stored ballots.
+    InvalidLogsOnly,
+    /// Raised when finalizing an election that only logs the votes, and so
+    /// does not store a tally.
+    TallyNotStored,
+    /// Raised when certifying the result of an election without a
+    /// `certification_key`.
+    NotCertifiable,
+    /// Raised when certifying the result of an election that is already
+    /// certified.
+    AlreadyCertified,
+    /// Raised when minting the "I voted" badge with the `badge_contract`
+    /// fails.
+    BadgeMintFailed,
+    /// Raised when delegating in a time-weighted election.
+    TimeWeightedElection,
+    /// Raised when an election would have more than `MAX_OPTIONS` voting
+    /// options.
+    TooManyOptions,
+    /// Raised when the description of an election is longer than
+    /// `MAX_DESCRIPTION_LENGTH` bytes.
+    DescriptionTooLong,
+    /// Raised when adding up voting weights would overflow.
+    WeightOverflow,
+    /// Raised when a permit is signed for another contract instance.
+    WrongContract,
+    /// Raised when a permit has expired.
+    PermitExpired,
+    /// Raised when a permit does not include the current nonce of the
+    /// signer, e.g. because it has already been used.
+    NonceMismatch,
+    /// Raised when a permit is for an entrypoint that cannot be invoked with
+    /// `permit`.
+    UnsupportedPermit,
+    /// Raised when the admin withdraws funds while the balance of the
+    /// contract is below the total of the deposits, which would mean that
+    /// the deposits are not fully backed.
+    BalanceBelowDeposits,
+}
+
+/// Mapping the logging errors to `VotingError`.
+impl From<LogError> for VotingError {
+    fn from(le: LogError) -> Self {
+        match le {
+            LogError::Full => Self::LogFull,
+            LogError::Malformed => Self::LogMalformed,
+        }
+    }
+}
+
+/// A custom alias type for the `Result` type with the error type fixed to
+/// `VotingError`.
+pub type VotingResult<T> = Result<T, VotingError>;
+
+/// The leading fields of the `return_value` of the `view` entrypoint of the
+/// voting contract, i.e. of its `VotingView`. Contracts that only need to
+/// identify an election, like the voting factory, parse this prefix and
+/// ignore the remaining fields.
+#[derive(Serialize, SchemaType, Clone, PartialEq, Eq, Debug)]
+pub struct ElectionHeader {
+    /// The description of the election.
+    pub description: String,
+    /// The identifier of the election.
+    pub election_id: String,
+    /// The first timestamp that an account can vote, if any.
+    pub start_time: Option<Timestamp>,
+    /// The last timestamp that an account can vote.
+    pub end_time: Timestamp,
+}
+
+/// The data used for the signature message, which is signed by the verifier
+/// after verifying a proof and checked by the contract when registering.
+pub struct SignatureMessageData {
+    /// The account address for which the proof was verified.
+    pub account_address: AccountAddress,
+    /// The address of the voting contract instance that the signature is meant
+    /// for. This prevents replaying the signature in other elections that use
+    /// the same verifier key.
+    pub contract_address: ContractAddress,
+    /// The identifier of the election that the signature is meant for. This
+    /// separates concurrently running elections that share a verifier key.
+    pub election_id: String,
+    /// The time until which the signature is valid. A leaked signature can
+    /// therefore only be used for a limited time.
+    pub expiry: Timestamp,
+    /// The number of registrations of the account in this election so far. It
+    /// is incremented on every accepted registration, so each signature can
+    /// only be used once.
+    pub nonce: u64,
+    /// The SHA2-256 hash of the statement that the verifier checked. The
+    /// contract only accepts signatures for the statement it was initialized
+    /// with, so a verifier cannot sign for a weaker statement.
+    pub statement_hash: HashSha2256,
+    /// The country code for the country which the account does *not* have residency in.
+    /// For statements about a bloc of countries, this is the identifier of the bloc instead.
+    pub country_code: VotingOption,
+}
+
+impl Serial for SignatureMessageData {
+    fn serial<W: Write>(&self, out: &mut W) -> Result<(), W::Err> {
+        // Write the 32 bytes for the account address.
+        self.account_address.serial(out)?;
+        // Write the 16 bytes for the index and subindex of the contract address.
+        self.contract_address.serial(out)?;
+        // Write the election identifier, prefixed by its length, so it cannot
+        // be confused with the country code that follows.
+        self.election_id.serial(out)?;
+        // Write the 8 bytes for the expiry.
+        self.expiry.serial(out)?;
+        // Write the 8 bytes for the nonce.
+        self.nonce.serial(out)?;
+        // Write the 32 bytes for the statement hash.
+        self.statement_hash.serial(out)?;
+        // Write the bytes for the country code (two bytes) or bloc identifier.
+        out.write_all(self.country_code.as_bytes())
+    }
+}
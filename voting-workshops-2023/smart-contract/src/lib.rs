@@ -11,7 +11,14 @@
 //! The contract allows for
 //!  - `initializing` the election;
 //!  - `vote` for one of the voting options;
-//!  - `view` general information about the election and the tally.
+//!  - `view` general information about the election and the tally;
+//!  - `viewBallot` to look up the voting option of a single account;
+//!  - `getVoterCount` to get the number of accounts that have voted.
+//!
+//! # Events
+//! The contract logs a `VotingEvent::Init` event when the election is
+//! initialized, and a `VotingEvent::Vote` event every time an account casts or
+//! changes its vote.
 //!
 //! # Tests
 //! The tests exist in the `./tests/tests.rs` file.
@@ -58,11 +65,33 @@ pub struct VotingView {
     /// The election is open from the point in time that this smart contract is
     /// initialized until the `end_time`.
     pub end_time: Timestamp,
-    /// The map connects the index of a voting option to the number of votes
-    /// it received so far.
+    /// The map connects each voting option to the number of votes it received
+    /// so far. Options without votes are included with a count of 0.
     pub tally: BTreeMap<VotingOption, VoteCount>,
 }
 
+/// The events logged by the contract.
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub enum VotingEvent {
+    /// The election was initialized.
+    Init {
+        /// A vector of all voting options.
+        options: Vec<VotingOption>,
+        /// The last timestamp that an account can vote.
+        end_time: Timestamp,
+    },
+    /// An account cast or changed its vote.
+    Vote {
+        /// The account that voted.
+        voter: AccountAddress,
+        /// The previously selected voting option, if the account had voted
+        /// before.
+        old_option: Option<VotingOption>,
+        /// The newly selected voting option.
+        new_option: VotingOption,
+    },
+}
+
 /// The contract state
 #[derive(Serialize, Clone)]
 struct State {
@@ -91,6 +120,20 @@ pub enum VotingError {
     /// Raised when a smart contract tries to participate in the election. Only
     /// accounts are allowed to vote.
     ContractVoter,
+    /// Raised when the log is full.
+    LogFull,
+    /// Raised when the log is malformed.
+    LogMalformed,
+}
+
+/// Mapping the logging errors to `VotingError`.
+impl From<LogError> for VotingError {
+    fn from(le: LogError) -> Self {
+        match le {
+            LogError::Full => Self::LogFull,
+            LogError::Malformed => Self::LogMalformed,
+        }
+    }
 }
 
 /// A custom alias type for the `Result` type with the error type fixed to
@@ -102,11 +145,26 @@ pub type VotingResult<T> = Result<T, VotingError>;
 /// Initialize the contract instance and start the election.
 /// A description, the vector of all voting options, and an `end_time`
 /// have to be provided.
-#[init(contract = "voting", parameter = "InitParameter")]
-fn init(ctx: &InitContext, _state_builder: &mut StateBuilder) -> InitResult<State> {
+#[init(
+    contract = "voting",
+    parameter = "InitParameter",
+    event = "VotingEvent",
+    enable_logger
+)]
+fn init(
+    ctx: &InitContext,
+    _state_builder: &mut StateBuilder,
+    logger: &mut Logger,
+) -> InitResult<State> {
     // Parse the parameter.
     let param: InitParameter = ctx.parameter_cursor().get()?;
 
+    // Log the start of the election.
+    logger.log(&VotingEvent::Init {
+        options: param.options.clone(),
+        end_time: param.end_time,
+    })?;
+
     // Set the state.
     Ok(State {
         description: param.description,
@@ -124,15 +182,21 @@ fn init(ctx: &InitContext, _state_builder: &mut StateBuilder) -> InitResult<Stat
 /// - It fails to parse the parameter.
 /// - A contract tries to vote.
 /// - It is past the `end_time`.
+///
+/// The `end_time` itself is inclusive, i.e. a vote with a slot time equal to
+/// the `end_time` is still accepted.
 #[receive(
     contract = "voting",
     name = "vote",
     mutable,
     parameter = "VotingOption",
-    error = "VotingError"
+    error = "VotingError",
+    enable_logger
 )]
-fn vote(ctx: &ReceiveContext, host: &mut Host<State>) -> VotingResult<()> {
-    // Check that the election hasn't finished yet.
+fn vote(ctx: &ReceiveContext, host: &mut Host<State>, logger: &mut Logger) -> VotingResult<()> {
+    // Check that the election hasn't finished yet. The slot time is the block
+    // time agreed on by the chain, not the clock of the voter, so there is no
+    // clock skew to tolerate.
     if ctx.metadata().slot_time() > host.state().end_time {
         return Err(VotingError::VotingFinished);
     }
@@ -152,11 +216,15 @@ fn vote(ctx: &ReceiveContext, host: &mut Host<State>) -> VotingResult<()> {
     };
 
     // Insert or replace the vote for the account.
-    host.state_mut()
-        .ballots
-        .entry(acc)
-        .and_modify(|old_vote_index| *old_vote_index = new_vote_index)
-        .or_insert(new_vote_index);
+    let old_vote_index = host.state_mut().ballots.insert(acc, new_vote_index);
+
+    // Log the vote.
+    let old_option = old_vote_index.map(|index| host.state().options[index as usize].clone());
+    logger.log(&VotingEvent::Vote {
+        voter: acc,
+        old_option,
+        new_option: new_vote,
+    })?;
 
     Ok(())
 }
@@ -168,7 +236,10 @@ fn view(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<VotingView>
     let description = host.state().description.clone();
     let end_time = host.state().end_time;
     let options = host.state().options.clone();
-    let mut tally = BTreeMap::new();
+    // Seed the tally with every voting option, so options without votes are
+    // included with a count of 0.
+    let mut tally: BTreeMap<VotingOption, VoteCount> =
+        options.iter().map(|option| (option.clone(), 0)).collect();
 
     // Sum up the ballots to a tally.
     // Looping over data that can be changed by users should be avoided in
@@ -177,11 +248,10 @@ fn view(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<VotingView>
     for (_, vote_index) in host.state().ballots.iter() {
         // Get the VotingOption (String).
         let voting_option = options[*vote_index as usize].clone();
-        // Increment the existing value or insert 1.
+        // Increment the existing value.
         tally
             .entry(voting_option)
-            .and_modify(|current_count| *current_count += 1)
-            .or_insert(1);
+            .and_modify(|current_count| *current_count += 1);
     }
 
     // Return the election information.
@@ -191,3 +261,33 @@ fn view(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<VotingView>
         tally,
     })
 }
+
+/// Get the voting option that an account has voted for, or `None` if the
+/// account has not voted.
+#[receive(
+    contract = "voting",
+    name = "viewBallot",
+    parameter = "AccountAddress",
+    return_value = "Option<VotingOption>"
+)]
+fn view_ballot(ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<Option<VotingOption>> {
+    // Parse the parameter.
+    let account: AccountAddress = ctx.parameter_cursor().get()?;
+
+    // Look up the ballot of the account.
+    let state = host.state();
+    Ok(state
+        .ballots
+        .get(&account)
+        .map(|vote_index| state.options[*vote_index as usize].clone()))
+}
+
+/// Get the number of accounts that have voted.
+#[receive(
+    contract = "voting",
+    name = "getVoterCount",
+    return_value = "VoteCount"
+)]
+fn get_voter_count(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<VoteCount> {
+    Ok(host.state().ballots.len() as VoteCount)
+}
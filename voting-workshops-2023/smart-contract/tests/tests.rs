@@ -60,7 +60,8 @@ fn setup_chain_and_contract(block_time: Timestamp) -> (Chain, ContractInitSucces
     (chain, initialization)
 }
 
-/// Test that an account cannot vote if it is past the `end_time` of the election.
+/// Test that an account cannot vote if it is past the `end_time` of the election,
+/// already one millisecond after it.
 #[test]
 fn test_vote_after_end_time() {
     // Set up the chain with a block time later than `CHRISTMAS_EVE_EPOCH`, such voting is no longer permitted.
@@ -91,6 +92,57 @@ fn test_vote_after_end_time() {
     assert_eq!(error, VotingError::VotingFinished);
 }
 
+/// Test that an account can still vote when the block time is exactly the `end_time`.
+/// The deadline is inclusive: the `end_time` is the last timestamp at which voting is possible.
+#[test]
+fn test_vote_at_end_time() {
+    // Set up the chain with a block time equal to `CHRISTMAS_EVE_EPOCH`.
+    let (mut chain, initialization) =
+        setup_chain_and_contract(Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH));
+
+    // Vote on Germany.
+    chain
+        .contract_update(
+            SIGNER,
+            ACC_0,
+            Address::Account(ACC_0),
+            Energy::from(10000),
+            UpdateContractPayload {
+                amount: Amount::zero(),
+                address: initialization.contract_address,
+                receive_name: OwnedReceiveName::new_unchecked(String::from("voting.vote")),
+                message: OwnedParameter::from_serial(&VotingOption::from("DE"))
+                    .expect("Parameter has valid length"),
+            },
+        )
+        .expect("Voting at exactly the `end_time` succeeds");
+}
+
+/// Test that an account can vote one millisecond before the `end_time`.
+#[test]
+fn test_vote_one_millisecond_before_end_time() {
+    // Set up the chain with a block time one millisecond before `CHRISTMAS_EVE_EPOCH`.
+    let (mut chain, initialization) =
+        setup_chain_and_contract(Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH - 1));
+
+    // Vote on Germany.
+    chain
+        .contract_update(
+            SIGNER,
+            ACC_0,
+            Address::Account(ACC_0),
+            Energy::from(10000),
+            UpdateContractPayload {
+                amount: Amount::zero(),
+                address: initialization.contract_address,
+                receive_name: OwnedReceiveName::new_unchecked(String::from("voting.vote")),
+                message: OwnedParameter::from_serial(&VotingOption::from("DE"))
+                    .expect("Parameter has valid length"),
+            },
+        )
+        .expect("Voting before the `end_time` succeeds");
+}
+
 /// Test that voting on an unknown option fails.
 #[test]
 fn test_vote_on_unknown_option_fails() {
@@ -167,10 +219,12 @@ fn test_valid_voting_with_multiple_accounts() {
     let voting_view_0: VotingView = view_0
         .parse_return_value()
         .expect("Return values should be a `VotingView`");
-    // There is only a single entry.
-    assert_eq!(voting_view_0.tally.len(), 1);
+    // All options are included.
+    assert_eq!(voting_view_0.tally.len(), 3);
     // There is one vote on Germany.
     assert_eq!(voting_view_0.tally.get("DE"), Some(&1));
+    // .. And none on Denmark.
+    assert_eq!(voting_view_0.tally.get("DK"), Some(&0));
 
     // ACC_1 votes on Denmark.
     chain
@@ -204,8 +258,8 @@ fn test_valid_voting_with_multiple_accounts() {
     let voting_view_1: VotingView = view_1
         .parse_return_value()
         .expect("Return values should be a `VotingView`");
-    // There are now two entries.
-    assert_eq!(voting_view_1.tally.len(), 2);
+    // All options are still included.
+    assert_eq!(voting_view_1.tally.len(), 3);
     // There is one vote on Germany.
     assert_eq!(voting_view_1.tally.get("DE"), Some(&1));
     // .. And one vote on Denmark.
@@ -243,8 +297,139 @@ fn test_valid_voting_with_multiple_accounts() {
     let voting_view_2: VotingView = view_2
         .parse_return_value()
         .expect("Return values should be a `VotingView`");
-    // There is only one entry again.
-    assert_eq!(voting_view_2.tally.len(), 1);
+    // All options are still included.
+    assert_eq!(voting_view_2.tally.len(), 3);
     // There are two votes on Denmark.
     assert_eq!(voting_view_2.tally.get("DK"), Some(&2));
+    // .. And none on Germany anymore.
+    assert_eq!(voting_view_2.tally.get("DE"), Some(&0));
+}
+
+/// Test that the contract logs an event on initialization and on every vote.
+#[test]
+fn test_events() {
+    // Set up the chain with a block time below the end time.
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+
+    // Check the event logged on initialization.
+    let init_events: Vec<VotingEvent> = initialization
+        .events
+        .iter()
+        .map(|e| e.parse().expect("Event should be a `VotingEvent`"))
+        .collect();
+    assert_eq!(
+        init_events,
+        [VotingEvent::Init {
+            options: vec![String::from("DK"), String::from("DE"), String::from("IT")],
+            end_time: Timestamp::from_timestamp_millis(CHRISTMAS_EVE_EPOCH),
+        }]
+    );
+
+    // ACC_0 votes on Germany and then changes the vote to Denmark.
+    let mut vote_events = Vec::new();
+    for option in ["DE", "DK"] {
+        let update = chain
+            .contract_update(
+                SIGNER,
+                ACC_0,
+                Address::Account(ACC_0),
+                Energy::from(10000),
+                UpdateContractPayload {
+                    amount: Amount::zero(),
+                    address: initialization.contract_address,
+                    receive_name: OwnedReceiveName::new_unchecked(String::from("voting.vote")),
+                    message: OwnedParameter::from_serial(&VotingOption::from(option))
+                        .expect("Parameter has valid length"),
+                },
+            )
+            .expect("Voting succeeds");
+        vote_events.extend(update.events().flat_map(|(_, events)| {
+            events.iter().map(|e| {
+                e.parse::<VotingEvent>()
+                    .expect("Event should be a `VotingEvent`")
+            })
+        }));
+    }
+
+    // Check the events logged when voting.
+    assert_eq!(
+        vote_events,
+        [
+            VotingEvent::Vote {
+                voter: ACC_0,
+                old_option: None,
+                new_option: String::from("DE"),
+            },
+            VotingEvent::Vote {
+                voter: ACC_0,
+                old_option: Some(String::from("DE")),
+                new_option: String::from("DK"),
+            },
+        ]
+    );
+}
+
+/// Test the `viewBallot` and `getVoterCount` functions.
+#[test]
+fn test_view_ballot_and_voter_count() {
+    // Set up the chain with a block time below the end time.
+    let (mut chain, initialization) = setup_chain_and_contract(Timestamp::from_timestamp_millis(0));
+
+    // ACC_0 votes on Italy.
+    chain
+        .contract_update(
+            SIGNER,
+            ACC_0,
+            Address::Account(ACC_0),
+            Energy::from(10000),
+            UpdateContractPayload {
+                amount: Amount::zero(),
+                address: initialization.contract_address,
+                receive_name: OwnedReceiveName::new_unchecked(String::from("voting.vote")),
+                message: OwnedParameter::from_serial(&VotingOption::from("IT"))
+                    .expect("Parameter has valid length"),
+            },
+        )
+        .expect("Voting succeeds");
+
+    // Look up the ballots of both accounts.
+    for (account, expected_ballot) in [(ACC_0, Some(String::from("IT"))), (ACC_1, None)] {
+        let ballot: Option<VotingOption> = chain
+            .contract_invoke(
+                ACC_0,
+                Address::Account(ACC_0),
+                Energy::from(10000),
+                UpdateContractPayload {
+                    amount: Amount::zero(),
+                    address: initialization.contract_address,
+                    receive_name: OwnedReceiveName::new_unchecked(String::from(
+                        "voting.viewBallot",
+                    )),
+                    message: OwnedParameter::from_serial(&account)
+                        .expect("Parameter has valid length"),
+                },
+            )
+            .expect("Invoke succeeds.")
+            .parse_return_value()
+            .expect("Return value should be an `Option<VotingOption>`");
+        assert_eq!(ballot, expected_ballot);
+    }
+
+    // Only ACC_0 has voted.
+    let voter_count: VoteCount = chain
+        .contract_invoke(
+            ACC_0,
+            Address::Account(ACC_0),
+            Energy::from(10000),
+            UpdateContractPayload {
+                amount: Amount::zero(),
+                address: initialization.contract_address,
+                receive_name: OwnedReceiveName::new_unchecked(String::from("voting.getVoterCount")),
+                message: OwnedParameter::empty(),
+            },
+        )
+        .expect("Invoke succeeds.")
+        .parse_return_value()
+        .expect("Return value should be a `VoteCount`");
+    assert_eq!(voter_count, 1);
 }